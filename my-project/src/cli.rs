@@ -0,0 +1,940 @@
+// the garden binary's command line. everything works on the project file in
+// the current directory, plants.csv and rules.csv are read from there too if
+// they exist
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::garden::beds::{self, Bed, Soil};
+use crate::garden::calendar::{self, Date};
+use crate::garden::journal::Event;
+use crate::garden::lifecycle::{self, Lifecycles};
+use crate::garden::project::{self, Project};
+use crate::garden::rules::{self, Rules};
+use crate::garden::succession::{self, Supply};
+use crate::garden::vegetables::{Catalog, Lifespan, Plant};
+use crate::garden::watering;
+
+const BIN: &str = "my-project";
+
+// every command with its arguments and what it does, for the help, the
+// error messages and the completion scripts
+const COMMANDS: &[(&str, &str, &str)] = &[
+    ("plant list", "", "show every plant in the catalog"),
+    ("bed new", "<name> <width-cm> <length-cm> [--soil loam]", "add a bed to the garden"),
+    ("bed place", "<plant> <x-cm> <y-cm> [--bed <name>]", "put a plant in a bed"),
+    ("bed fill", "<plant> [--bed <name>]", "fill the free space in a bed with a plant"),
+    ("bed show", "[<name>] [--cell <cm>]", "draw the beds"),
+    ("bed list", "", "list the beds and what's in them"),
+    ("bed clear", "[--bed <name>]", "take the annuals out for next season, the perennials stay"),
+    (
+        "season next",
+        "",
+        "start planning next year: every bed is cleared of annuals and the frost dates move on",
+    ),
+    (
+        "calendar",
+        "[<plant>...] [--last-frost <date>] [--first-frost <date>] [--ics <file>]",
+        "when to sow, plant out and harvest",
+    ),
+    (
+        "journal add",
+        "<planting|plant> <event> [<value>] [--date <date>] [--bed <name>] [--count <plants>]",
+        "write down what happened",
+    ),
+    ("journal list", "[<planting>]", "show the journal"),
+    (
+        "succession",
+        "<plant> <per-week> [--from <date>] [--until <date>] [--bed <name>]",
+        "when to sow batches for a steady supply, like 2 a week or 500g a week",
+    ),
+    (
+        "water",
+        "<weather-file> [--bed <name>] [--latitude <degrees>]",
+        "a watering schedule for the weather in the file",
+    ),
+    ("report", "", "harvests, how long things took, and how the plan scores"),
+    ("completions", "<bash|zsh|fish>", "print a shell completion script"),
+    ("help", "", "show this"),
+];
+
+const EVENTS: &[&str] = &["sown", "transplanted", "watered", "fertilized", "pest", "harvested"];
+
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),  // the command line was wrong
+    Failed(String), // the command was fine but couldn't be done
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Failed(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}\nrun '{} help' to see every command", message, BIN),
+            CliError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> CliError {
+        CliError::Failed(error.to_string())
+    }
+}
+
+fn usage_error(message: String) -> CliError {
+    CliError::Usage(message)
+}
+
+fn failed(message: String) -> CliError {
+    CliError::Failed(message)
+}
+
+// the words of the command line, and the --name value options pulled out of
+// it wherever they were
+#[derive(Debug, Default)]
+struct Args {
+    words: Vec<String>,
+    options: BTreeMap<String, String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Args, CliError> {
+        let mut parsed = Args::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                parsed.words.insert(0, String::from("help"));
+            } else if arg == "--" {
+                parsed.words.extend(args.by_ref().cloned());
+            } else if let Some(option) = arg.strip_prefix("--") {
+                let (name, value) = match option.split_once('=') {
+                    Some((name, value)) => (name, value.to_string()),
+                    None => match args.next() {
+                        Some(value) => (option, value.clone()),
+                        None => return Err(usage_error(format!("--{} needs a value", option))),
+                    },
+                };
+                parsed.options.insert(name.to_string(), value);
+            } else {
+                parsed.words.push(arg.clone());
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|value| value.as_str())
+    }
+
+    // options the command doesn't know are probably typos, say so rather than
+    // quietly ignoring them
+    fn allow(&self, names: &[&str]) -> Result<(), CliError> {
+        match self.options.keys().find(|name| !names.contains(&name.as_str())) {
+            Some(name) => Err(usage_error(format!("unknown option --{}", name))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn number(text: &str, what: &str) -> Result<u32, CliError> {
+    text.parse()
+        .map_err(|_| usage_error(format!("{} should be a whole number, not '{}'", what, text)))
+}
+
+fn date(text: &str) -> Result<Date, CliError> {
+    Date::parse(text).ok_or_else(|| usage_error(format!("'{}' isn't a date, write it like 2026-04-20", text)))
+}
+
+struct Garden {
+    catalog: Catalog,
+    rules: Rules,
+    lifecycles: Lifecycles,
+    project: Project,
+}
+
+impl Garden {
+    fn open() -> Result<Garden, CliError> {
+        Ok(Garden {
+            catalog: Catalog::load(Path::new("plants.csv"))?,
+            rules: Rules::load(Path::new("rules.csv"))?,
+            lifecycles: Lifecycles::load(Path::new("perennials.csv"))?,
+            project: Project::load(Path::new(project::FILE_NAME))?.unwrap_or_default(),
+        })
+    }
+
+    fn save(&self) -> Result<(), CliError> {
+        Ok(self.project.save(Path::new(project::FILE_NAME))?)
+    }
+
+    fn plant(&self, name: &str) -> Result<&Plant, CliError> {
+        self.catalog.get(name).ok_or_else(|| {
+            failed(format!(
+                "there's no plant called '{}' in the catalog, '{} plant list' shows them",
+                name, BIN
+            ))
+        })
+    }
+
+    // the bed named with --bed, or the only bed there is
+    fn bed_name(&self, args: &Args) -> Result<String, CliError> {
+        let names: Vec<&str> = self.project.beds.iter().map(|bed| bed.name.as_str()).collect();
+        match (args.option("bed"), names.as_slice()) {
+            (Some(name), _) => match self.project.bed(name) {
+                Some(bed) => Ok(bed.name.clone()),
+                None if names.is_empty() => Err(failed(format!("there's no bed called '{}', there are no beds yet", name))),
+                None => Err(failed(format!("there's no bed called '{}', the beds are: {}", name, names.join(", ")))),
+            },
+            (None, []) => Err(failed(format!("there are no beds yet, make one with '{} bed new'", BIN))),
+            (None, [only]) => Ok(only.to_string()),
+            (None, _) => Err(usage_error(format!("which bed? use --bed with one of: {}", names.join(", ")))),
+        }
+    }
+}
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let args = Args::parse(args)?;
+    let words: Vec<&str> = args.words.iter().map(|word| word.as_str()).collect();
+    match words.as_slice() {
+        [] | ["help", ..] => {
+            print!("{}", help());
+            Ok(())
+        }
+        ["plant", "list"] => plant_list(&args),
+        ["bed", "new", name, width, length] => bed_new(&args, name, width, length),
+        ["bed", "place", plant, x, y] => bed_place(&args, plant, x, y),
+        ["bed", "fill", plant] => bed_fill(&args, plant),
+        ["bed", "show"] => bed_show(&args, None),
+        ["bed", "show", name] => bed_show(&args, Some(name)),
+        ["bed", "list"] => bed_list(&args),
+        ["bed", "clear"] => bed_clear(&args),
+        ["season", "next"] => season_next(&args),
+        ["calendar", plants @ ..] => calendar(&args, plants),
+        ["journal", "add", planting, event, value @ ..] => journal_add(&args, planting, event, value),
+        ["journal", "list"] => journal_list(&args, None),
+        ["journal", "list", planting] => journal_list(&args, Some(planting)),
+        ["succession", plant, per_week] => succession(&args, plant, per_week),
+        ["water", weather] => water(&args, weather),
+        ["report"] => report(&args),
+        ["completions", shell] => completions(shell),
+        _ => Err(unknown(&words)),
+    }
+}
+
+// the best help we can give for a command line nothing matched
+fn unknown(words: &[&str]) -> CliError {
+    // the right command with the wrong arguments
+    for length in (1..=words.len().min(2)).rev() {
+        let command = words[..length].join(" ");
+        if let Some((name, arguments, _)) = COMMANDS.iter().find(|(name, _, _)| *name == command) {
+            return usage_error(format!("usage: {} {} {}", BIN, name, arguments));
+        }
+    }
+    let typed = words.join(" ");
+    let known: BTreeSet<&str> = COMMANDS.iter().map(|(name, _, _)| *name).collect();
+    let group: Vec<&&str> = known
+        .iter()
+        .filter(|name| name.split(' ').next() == words.first().copied())
+        .collect();
+    if !group.is_empty() {
+        let names: Vec<String> = group.iter().map(|name| name.to_string()).collect();
+        return usage_error(format!("'{}' isn't a command, try one of: {}", typed, names.join(", ")));
+    }
+    match known.iter().min_by_key(|name| distance(name, &typed)) {
+        Some(name) if distance(name, &typed) <= 2 => {
+            usage_error(format!("'{}' isn't a command, did you mean '{}'?", typed, name))
+        }
+        _ => usage_error(format!("'{}' isn't a command", typed)),
+    }
+}
+
+// how many letters have to change to turn one word into the other
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if a == *b {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+fn help() -> String {
+    let mut text = format!("usage: {} <command>\n\ncommands:\n", BIN);
+    for (name, arguments, about) in COMMANDS {
+        let command = format!("{} {}", name, arguments);
+        text.push_str(&format!("  {}\n      {}\n", command.trim_end(), about));
+    }
+    text.push_str(&format!(
+        "\nthe garden is kept in {} in the current directory, dates are written like 2026-04-20\n",
+        project::FILE_NAME
+    ));
+    text
+}
+
+fn plant_list(args: &Args) -> Result<(), CliError> {
+    args.allow(&[])?;
+    let garden = Garden::open()?;
+    println!("{} plants in the catalog:", garden.catalog.len());
+    for plant in garden.catalog.plants() {
+        let lifespan = match plant.lifespan {
+            Lifespan::Annual => String::from("annual"),
+            Lifespan::Perennial { establishment_years } => {
+                let years = if establishment_years == 1 { "year" } else { "years" };
+                format!("perennial, {} {} to establish", establishment_years, years)
+            }
+        };
+        println!(
+            "  {:<12} {:>3} cm apart, {:>3} days, {:?} sun, {:?} water, {:?}, {}, {}",
+            plant.name,
+            plant.spacing_cm,
+            plant.days_to_maturity,
+            plant.sun,
+            plant.water,
+            plant.frost,
+            plant.family,
+            lifespan
+        );
+    }
+    Ok(())
+}
+
+fn bed_new(args: &Args, name: &str, width: &str, length: &str) -> Result<(), CliError> {
+    args.allow(&["soil"])?;
+    let soil = match args.option("soil") {
+        Some(soil) => Soil::parse(soil)
+            .ok_or_else(|| usage_error(format!("unknown soil '{}', use sandy, loam, clay or silt", soil)))?,
+        None => Soil::Loam,
+    };
+    let bed = Bed::new(name, number(width, "the width")?, number(length, "the length")?, soil);
+    if bed.width_cm == 0 || bed.length_cm == 0 {
+        return Err(usage_error(String::from("a bed needs some width and length")));
+    }
+    let mut garden = Garden::open()?;
+    if garden.project.bed(name).is_some() {
+        return Err(failed(format!("there's already a bed called '{}'", name)));
+    }
+    println!("{} is {} x {} cm of {} soil, {:.2} m2", bed.name, bed.width_cm, bed.length_cm, soil.name(), bed.area_m2());
+    garden.project.beds.push(bed);
+    garden.save()
+}
+
+fn bed_place(args: &Args, plant: &str, x: &str, y: &str) -> Result<(), CliError> {
+    args.allow(&["bed"])?;
+    let (x, y) = (number(x, "x")?, number(y, "y")?);
+    let mut garden = Garden::open()?;
+    let plant = garden.plant(plant)?.clone();
+    let name = garden.bed_name(args)?;
+    let bed = garden.project.bed_mut(&name).expect("bed_name found the bed");
+    bed.place(&plant, x, y)
+        .map_err(|error| failed(format!("can't plant in {}: {}", name, error)))?;
+    println!("planted {} in {} at {}, {}", plant.name, name, x, y);
+    if plant.is_perennial() {
+        let planted = garden.project.journal.planted_in(&plant.name, &name);
+        let planted = planted.map_or(garden.project.year, |date| date.year);
+        let first = garden.lifecycles.first_harvest(&plant, planted);
+        match first {
+            Some(year) => println!("it's a perennial, it stays put and can first be picked in {}", year),
+            None => println!("it's a perennial, it stays put"),
+        }
+    }
+    garden.save()
+}
+
+fn bed_fill(args: &Args, plant: &str) -> Result<(), CliError> {
+    args.allow(&["bed"])?;
+    let mut garden = Garden::open()?;
+    let plant = garden.plant(plant)?.clone();
+    let name = garden.bed_name(args)?;
+    let bed = garden.project.bed_mut(&name).expect("bed_name found the bed");
+    let planted = beds::fill(bed, &plant);
+    println!("planted {} {} in {}", planted, plant.name, name);
+    garden.save()
+}
+
+fn bed_show(args: &Args, name: Option<&str>) -> Result<(), CliError> {
+    args.allow(&["cell"])?;
+    let cell = args.option("cell").map(|cell| number(cell, "the cell size")).transpose()?.unwrap_or(10);
+    let garden = Garden::open()?;
+    let shown: Vec<&Bed> = match name {
+        Some(name) => vec![garden
+            .project
+            .bed(name)
+            .ok_or_else(|| failed(format!("there's no bed called '{}'", name)))?],
+        None => garden.project.beds.iter().collect(),
+    };
+    if shown.is_empty() {
+        println!("there are no beds yet, make one with '{} bed new'", BIN);
+    }
+    for bed in shown {
+        println!("{}", beds::render(bed, cell));
+    }
+    Ok(())
+}
+
+fn bed_list(args: &Args) -> Result<(), CliError> {
+    args.allow(&[])?;
+    let garden = Garden::open()?;
+    for bed in &garden.project.beds {
+        println!("{}: {} x {} cm, {}", bed.name, bed.width_cm, bed.length_cm, bed.soil.name());
+        for (plant, count) in bed.counts() {
+            println!("  {} {}", count, plant);
+        }
+    }
+    Ok(())
+}
+
+fn bed_clear(args: &Args) -> Result<(), CliError> {
+    args.allow(&["bed"])?;
+    let mut garden = Garden::open()?;
+    let names: Vec<String> = match args.option("bed") {
+        Some(_) => vec![garden.bed_name(args)?],
+        None => garden.project.beds.iter().map(|bed| bed.name.clone()).collect(),
+    };
+    for name in names {
+        let bed = garden.project.bed_mut(&name).expect("named from the project");
+        let cleared = bed.clear_annuals(&garden.catalog);
+        println!("took {} annuals out of {}, {} perennials stay", cleared, name, bed.placements().len());
+    }
+    garden.save()
+}
+
+fn season_next(args: &Args) -> Result<(), CliError> {
+    args.allow(&[])?;
+    let mut garden = Garden::open()?;
+    let cleared = garden.project.next_season(&garden.catalog);
+    let frosts = garden.project.frosts;
+    println!("planning {}, took {} annuals out of the beds", garden.project.year, cleared);
+    println!("last frost {}, first frost {}", frosts.last_spring, frosts.first_autumn);
+    garden.save()
+}
+
+fn calendar(args: &Args, plants: &[&str]) -> Result<(), CliError> {
+    args.allow(&["last-frost", "first-frost", "ics"])?;
+    let mut garden = Garden::open()?;
+    let mut changed = false;
+    if let Some(last_spring) = args.option("last-frost") {
+        garden.project.frosts.last_spring = date(last_spring)?;
+        changed = true;
+    }
+    if let Some(first_autumn) = args.option("first-frost") {
+        garden.project.frosts.first_autumn = date(first_autumn)?;
+        changed = true;
+    }
+    let frosts = garden.project.frosts;
+    if frosts.first_autumn <= frosts.last_spring {
+        return Err(usage_error(format!(
+            "the first autumn frost ({}) has to come after the last spring one ({})",
+            frosts.first_autumn, frosts.last_spring
+        )));
+    }
+
+    // the plants asked for, or what's in the beds, or the whole catalog
+    let chosen: Vec<&Plant> = if !plants.is_empty() {
+        plants.iter().map(|name| garden.plant(name)).collect::<Result<_, _>>()?
+    } else {
+        let planted: BTreeSet<&str> = garden
+            .project
+            .beds
+            .iter()
+            .flat_map(|bed| bed.placements())
+            .map(|placement| placement.plant.as_str())
+            .collect();
+        match planted.is_empty() {
+            true => garden.catalog.plants().collect(),
+            false => planted.iter().filter_map(|name| garden.catalog.get(name)).collect(),
+        }
+    };
+    // perennials already in the ground aren't planted again, and wait until
+    // they're old enough to pick. each bed's are as old as they are there, so
+    // a perennial in more than one bed gets a calendar for each
+    let mut schedules: Vec<calendar::Schedule> = Vec::new();
+    for plant in chosen {
+        let beds: Vec<&str> = garden
+            .project
+            .beds
+            .iter()
+            .filter(|bed| bed.placements().iter().any(|placement| placement.plant.eq_ignore_ascii_case(&plant.name)))
+            .map(|bed| bed.name.as_str())
+            .collect();
+        if !plant.is_perennial() || beds.is_empty() {
+            schedules.push(lifecycle::schedule(plant, frosts, None, &garden.lifecycles));
+            continue;
+        }
+        for bed in &beds {
+            let planted = garden.project.journal.planted_in(&plant.name, bed).map(|date| date.year);
+            let mut schedule = lifecycle::schedule(plant, frosts, planted, &garden.lifecycles);
+            if beds.len() > 1 {
+                schedule.plant = format!("{} in {}", plant.name, bed);
+            }
+            schedules.push(schedule);
+        }
+    }
+
+    println!("last frost {}, first frost {}", frosts.last_spring, frosts.first_autumn);
+    for schedule in &schedules {
+        println!("{}", schedule.plant);
+        let windows = [
+            ("sow indoors", schedule.sow_indoors),
+            ("plant out", schedule.transplant),
+            ("sow outside", schedule.direct_sow),
+            ("harvest", schedule.harvest),
+        ];
+        for (what, window) in windows {
+            if let Some(window) = window {
+                println!("  {:<12} {}", what, window);
+            }
+        }
+        if let Some(note) = &schedule.note {
+            println!("  {}", note);
+        }
+    }
+    if let Some(path) = args.option("ics") {
+        fs::write(path, calendar::to_ics(&schedules))
+            .map_err(|error| failed(format!("couldn't write {}: {}", path, error)))?;
+        println!("wrote {}", path);
+    }
+    if changed {
+        garden.save()?;
+    }
+    Ok(())
+}
+
+fn journal_add(args: &Args, planting: &str, event: &str, value: &[&str]) -> Result<(), CliError> {
+    args.allow(&["date", "bed", "count"])?;
+    let day = match args.option("date") {
+        Some(day) => date(day)?,
+        None => Date::today(),
+    };
+    // only used when the planting is new, but a bad one is still a bad command line
+    let count = match args.option("count").map(|count| number(count, "the count")).transpose()? {
+        Some(0) => return Err(usage_error(String::from("the count should be at least 1"))),
+        Some(count) => count,
+        None => 1,
+    };
+    let value = value.join(" ");
+    let needs = |what: &str| usage_error(format!("{} needs {}, like '{} journal add 3 {} ...'", event, what, BIN, event));
+    let event = match event {
+        "sown" => Event::Sown,
+        "transplanted" => Event::Transplanted,
+        "watered" if value.is_empty() => return Err(needs("the litres")),
+        "watered" => match value.parse::<f64>() {
+            Ok(litres) if litres.is_finite() && litres >= 0.0 => Event::Watered { litres },
+            _ => return Err(usage_error(format!("litres should be a number, 0 or more, not '{}'", value))),
+        },
+        "fertilized" if value.is_empty() => return Err(needs("what it was fed")),
+        "fertilized" => Event::Fertilized { with: value },
+        "pest" if value.is_empty() => return Err(needs("what pest it was")),
+        "pest" => Event::Pest { what: value },
+        "harvested" if value.is_empty() => return Err(needs("the grams")),
+        "harvested" => Event::Harvested {
+            grams: number(&value, "the grams")?,
+        },
+        other => {
+            return Err(usage_error(format!(
+                "unknown event '{}', use one of: {}",
+                other,
+                EVENTS.join(", ")
+            )))
+        }
+    };
+
+    let mut garden = Garden::open()?;
+    // a number is a planting already in the journal, a plant's name starts a
+    // new one
+    let id = match planting.parse::<u32>() {
+        Ok(id) => id,
+        Err(_) => {
+            let plant = garden.plant(planting)?.name.clone();
+            let bed = garden.bed_name(args)?;
+            let id = garden.project.journal.plant(&plant, &bed, count);
+            println!("started planting #{}, {} x {} in {}", id, count, plant, bed);
+            id
+        }
+    };
+    let shown = event.to_string();
+    garden
+        .project
+        .journal
+        .record(id, day, event)
+        .map_err(|error| failed(error.to_string()))?;
+    let plant = &garden.project.journal.planting(id).expect("recorded against it").plant;
+    println!("#{} {}: {} on {}", id, plant, shown, day);
+    let early = garden.project.journal.early_harvests(&garden.catalog, &garden.lifecycles);
+    if let Some(early) = early.iter().find(|early| early.planting == id && early.date == day) {
+        println!("warning: {}", early);
+    }
+    garden.save()
+}
+
+fn journal_list(args: &Args, planting: Option<&str>) -> Result<(), CliError> {
+    args.allow(&[])?;
+    let garden = Garden::open()?;
+    let journal = &garden.project.journal;
+    let ids: Vec<u32> = match planting {
+        Some(planting) => {
+            let id = number(planting, "the planting")?;
+            if journal.planting(id).is_none() {
+                return Err(failed(format!("there is no planting {} in the journal", id)));
+            }
+            vec![id]
+        }
+        None => journal.plantings().iter().map(|planting| planting.id).collect(),
+    };
+    if ids.is_empty() {
+        println!("the journal is empty, start it with '{} journal add <plant> sown'", BIN);
+    }
+    for id in ids {
+        let planting = journal.planting(id).expect("listed from the journal");
+        println!("#{} {} in {}", planting.id, planting.plant, planting.bed);
+        for entry in journal.history(id) {
+            println!("  {} {}", entry.date, entry.event);
+        }
+    }
+    Ok(())
+}
+
+fn succession(args: &Args, plant: &str, per_week: &str) -> Result<(), CliError> {
+    args.allow(&["from", "until", "bed"])?;
+    let supply = match per_week.strip_suffix('g') {
+        Some(grams) => Supply::Grams(number(grams, "the grams a week")?),
+        None => Supply::Plants(number(per_week, "the plants a week")?),
+    };
+    let garden = Garden::open()?;
+    let plant = garden.plant(plant)?;
+    let frosts = garden.project.frosts;
+    // all the season the plant can be picked, unless asked otherwise
+    let season = calendar::schedule(plant, frosts).harvest;
+    let from = match (args.option("from"), season) {
+        (Some(from), _) => date(from)?,
+        (None, Some(season)) => season.start,
+        (None, None) => return Err(failed(format!("{} can't be picked this year, give --from and --until", plant.name))),
+    };
+    let until = match (args.option("until"), season) {
+        (Some(until), _) => date(until)?,
+        // the calendar's window is for the first picking, a batch can go on
+        // cropping until the frost
+        (None, Some(season)) => season.end.max(frosts.first_autumn),
+        (None, None) => frosts.first_autumn,
+    };
+    let plan = succession::plan(plant, supply, from, until, frosts).map_err(|error| failed(error.to_string()))?;
+
+    println!("{} from {} to {}", plan.plant, from, until);
+    for sowing in &plan.sowings {
+        let how = match sowing.indoors {
+            true => format!("sow {} in pots on {}, plant out {}", sowing.plants, sowing.sow, sowing.plant_out),
+            false => format!("sow {} on {}", sowing.plants, sowing.sow),
+        };
+        println!("  {}, picking {}", how, sowing.harvest);
+    }
+    for gap in &plan.gaps {
+        println!("  nothing for {}, it's too early or too late to sow for it", gap);
+    }
+    println!("{} plants, taking up to {:.2} m2 of bed at once", plan.plants(), plan.peak_area_m2);
+    if args.option("bed").is_some() {
+        let bed = garden.project.bed(&garden.bed_name(args)?).expect("bed_name found the bed");
+        let used: f64 = bed
+            .placements()
+            .iter()
+            .map(|placement| (placement.spacing_cm * placement.spacing_cm) as f64 / 10_000.0)
+            .sum();
+        let free = (bed.area_m2() - used).max(0.0);
+        match plan.peak_area_m2 <= free {
+            true => println!("{} has {:.2} m2 free, that's enough", bed.name, free),
+            false => println!("{} only has {:.2} m2 free, that's not enough", bed.name, free),
+        }
+    }
+    Ok(())
+}
+
+fn water(args: &Args, weather: &str) -> Result<(), CliError> {
+    args.allow(&["bed", "latitude"])?;
+    let latitude = match args.option("latitude") {
+        Some(latitude) => latitude
+            .parse::<f64>()
+            .ok()
+            .filter(|latitude| (-90.0..=90.0).contains(latitude))
+            .ok_or_else(|| usage_error(format!("the latitude should be degrees north, not '{}'", latitude)))?,
+        None => 45.0,
+    };
+    let garden = Garden::open()?;
+    let weather = watering::load_weather(Path::new(weather))?;
+    let shown: Vec<&Bed> = match args.option("bed") {
+        Some(_) => vec![garden.project.bed(&garden.bed_name(args)?).expect("bed_name found the bed")],
+        None => garden.project.beds.iter().collect(),
+    };
+    if shown.is_empty() {
+        return Err(failed(format!("there are no beds yet, make one with '{} bed new'", BIN)));
+    }
+    for bed in shown {
+        let simulation = watering::simulate(bed, &garden.catalog, &weather, latitude);
+        let (low, high) = simulation.band;
+        println!(
+            "{}: keep between {:.0}% and {:.0}% of what the soil holds",
+            simulation.bed,
+            low * 100.0,
+            high * 100.0
+        );
+        for (date, litres) in simulation.schedule() {
+            println!("  {} water {:.1} l", date, litres);
+        }
+        let (dry, wet) = simulation.days_out_of_band();
+        println!(
+            "  {:.1} l over {} days, {} days too dry, {} too wet from rain",
+            simulation.total_litres(),
+            simulation.days.len(),
+            dry,
+            wet
+        );
+    }
+    Ok(())
+}
+
+fn report(args: &Args) -> Result<(), CliError> {
+    args.allow(&[])?;
+    let garden = Garden::open()?;
+    let journal = &garden.project.journal;
+
+    let plants: BTreeSet<&str> = journal.plantings().iter().map(|planting| planting.plant.as_str()).collect();
+    println!("harvests:");
+    for plant in plants {
+        for (year, grams) in journal.harvest_by_year(plant) {
+            println!("  {} {} {:.1} kg", year, plant, grams as f64 / 1000.0);
+        }
+    }
+
+    println!("days from sowing to first harvest:");
+    for maturity in journal.maturity(&garden.catalog) {
+        let expected = match maturity.expected_days {
+            Some(expected) => format!(", expected {}", expected),
+            None => String::new(),
+        };
+        println!("  #{} {} took {}{}", maturity.planting, maturity.plant, maturity.actual_days, expected);
+    }
+
+    let early = journal.early_harvests(&garden.catalog, &garden.lifecycles);
+    if !early.is_empty() {
+        println!("picked too early:");
+        for early in early {
+            println!("  {}", early);
+        }
+    }
+
+    let year = garden.project.year;
+    let checks = succession::compare(journal, &garden.catalog, &garden.lifecycles, year)
+        .map_err(|error| failed(error.to_string()))?;
+    if !checks.is_empty() {
+        println!("expected and picked in {}:", year);
+        for check in checks {
+            println!(
+                "  {} x {}: expected {:.1} kg, picked {:.1} kg",
+                check.plants,
+                check.plant,
+                check.estimated_g as f64 / 1000.0,
+                check.harvested_g as f64 / 1000.0
+            );
+        }
+    }
+
+    let history = journal.seasons();
+    let report = rules::score(&garden.project.beds, garden.project.year, &history, &garden.catalog, &garden.rules);
+    println!("the {} plan scores {}", garden.project.year, report.score);
+    for note in &report.notes {
+        println!("  {}", note);
+    }
+    for bed in &garden.project.beds {
+        let suggestions = rules::suggest(&bed.name, garden.project.year + 1, &history, &garden.catalog, &garden.rules);
+        let families: Vec<&str> = suggestions.iter().take(3).map(|suggestion| suggestion.family.as_str()).collect();
+        if !families.is_empty() {
+            println!("  {} next year: {}", bed.name, families.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn completions(shell: &str) -> Result<(), CliError> {
+    let script = match shell {
+        "bash" => bash_completions(),
+        // zsh can run bash completions once bashcompinit is loaded
+        "zsh" => format!("autoload -U +X bashcompinit && bashcompinit\n{}", bash_completions()),
+        "fish" => fish_completions(),
+        other => return Err(usage_error(format!("no completions for '{}', use bash, zsh or fish", other))),
+    };
+    print!("{}", script);
+    Ok(())
+}
+
+// the first words of the commands, and the second words under each
+fn command_words() -> BTreeMap<&'static str, Vec<&'static str>> {
+    let mut words: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (name, _, _) in COMMANDS {
+        let mut parts = name.split(' ');
+        let first = parts.next().unwrap_or(name);
+        let seconds = words.entry(first).or_default();
+        seconds.extend(parts);
+    }
+    words.insert("completions", vec!["bash", "zsh", "fish"]);
+    words
+}
+
+fn bash_completions() -> String {
+    let words = command_words();
+    let firsts: Vec<&str> = words.keys().copied().collect();
+    let function = format!("_{}", BIN.replace('-', "_"));
+    let mut script = format!(
+        "# source this, or put it in /etc/bash_completion.d/{bin}\n{function}() {{\n    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    if [ \"$COMP_CWORD\" -eq 1 ]; then\n        COMPREPLY=($(compgen -W \"{firsts}\" -- \"$cur\"))\n        return\n    fi\n    case \"${{COMP_WORDS[1]}}\" in\n",
+        bin = BIN,
+        function = function,
+        firsts = firsts.join(" ")
+    );
+    for (first, seconds) in &words {
+        if seconds.is_empty() {
+            continue;
+        }
+        script.push_str(&format!(
+            "        {})\n            [ \"$COMP_CWORD\" -eq 2 ] && COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;\n",
+            first,
+            seconds.join(" ")
+        ));
+    }
+    script.push_str(&format!("    esac\n}}\ncomplete -F {} {}\n", function, BIN));
+    script
+}
+
+fn fish_completions() -> String {
+    let words = command_words();
+    let mut script = format!("# save as ~/.config/fish/completions/{}.fish\ncomplete -c {} -f\n", BIN, BIN);
+    for (name, _, about) in COMMANDS {
+        if let Some(first) = name.split(' ').next().filter(|first| first == name) {
+            script.push_str(&format!(
+                "complete -c {} -n __fish_use_subcommand -a {} -d '{}'\n",
+                BIN, first, about
+            ));
+        }
+    }
+    for (first, seconds) in &words {
+        if seconds.is_empty() {
+            continue;
+        }
+        if !COMMANDS.iter().any(|(name, _, _)| name == first) {
+            script.push_str(&format!("complete -c {} -n __fish_use_subcommand -a {}\n", BIN, first));
+        }
+        script.push_str(&format!(
+            "complete -c {} -n '__fish_seen_subcommand_from {}' -a '{}'\n",
+            BIN,
+            first,
+            seconds.join(" ")
+        ));
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    // the message of a usage error
+    fn usage(result: Result<(), CliError>) -> String {
+        match result {
+            Err(CliError::Usage(message)) => message,
+            other => panic!("expected a usage error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn options_can_go_anywhere() {
+        let parsed = Args::parse(&args("bed --bed=north place Tomato 30 --soil clay 40 -- --odd")).unwrap();
+        assert_eq!(parsed.words, ["bed", "place", "Tomato", "30", "40", "--odd"]);
+        assert_eq!(parsed.option("bed"), Some("north"));
+        assert_eq!(parsed.option("soil"), Some("clay"));
+        assert_eq!(parsed.option("cell"), None);
+        assert_eq!(Args::parse(&args("bed list --help")).unwrap().words, ["help", "bed", "list"]);
+    }
+
+    #[test]
+    fn a_bad_option_is_a_usage_error() {
+        let error = Args::parse(&args("bed show --cell")).unwrap_err();
+        assert_eq!(error.exit_code(), 2);
+        assert_eq!(error.to_string(), "--cell needs a value\nrun 'my-project help' to see every command");
+        let parsed = Args::parse(&args("bed list --bde north")).unwrap();
+        assert_eq!(usage(parsed.allow(&["bed"])), "unknown option --bde");
+        assert_eq!(usage(run(&args("plant list --all yes"))), "unknown option --all");
+    }
+
+    #[test]
+    fn mistyped_commands_get_the_closest_help() {
+        assert_eq!(
+            usage(run(&args("bed new north"))),
+            "usage: my-project bed new <name> <width-cm> <length-cm> [--soil loam]"
+        );
+        assert_eq!(
+            usage(run(&args("season last"))),
+            "'season last' isn't a command, try one of: season next"
+        );
+        assert_eq!(usage(run(&args("reprot"))), "'reprot' isn't a command, did you mean 'report'?");
+        assert_eq!(usage(run(&args("dig a hole"))), "'dig a hole' isn't a command");
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "bed"), 3);
+    }
+
+    #[test]
+    fn arguments_are_checked_before_the_garden_is_opened() {
+        assert_eq!(
+            usage(run(&args("bed new north ten 100"))),
+            "the width should be a whole number, not 'ten'"
+        );
+        assert_eq!(usage(run(&args("bed new north 100 0"))), "a bed needs some width and length");
+        assert_eq!(
+            usage(run(&args("bed new north 100 100 --soil peat"))),
+            "unknown soil 'peat', use sandy, loam, clay or silt"
+        );
+        assert_eq!(
+            usage(run(&args("completions powershell"))),
+            "no completions for 'powershell', use bash, zsh or fish"
+        );
+        for litres in ["-2", "NaN", "inf", "lots"] {
+            assert_eq!(
+                usage(run(&args(&format!("journal add 3 watered {}", litres)))),
+                format!("litres should be a number, 0 or more, not '{}'", litres)
+            );
+        }
+        assert_eq!(
+            usage(run(&args("journal add Lettuce sown --count 0"))),
+            "the count should be at least 1"
+        );
+    }
+
+    #[test]
+    fn help_and_completions_know_every_command() {
+        let help = help();
+        for (name, _, _) in COMMANDS {
+            assert!(help.contains(&format!("  {}", name)), "{} is missing from the help", name);
+        }
+        let bash = bash_completions();
+        assert!(bash.contains("season)\n            [ \"$COMP_CWORD\" -eq 2 ] && COMPREPLY=($(compgen -W \"next\""));
+        assert!(fish_completions().contains("-a season"));
+    }
+}
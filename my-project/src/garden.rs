@@ -1 +1,9 @@
-pub mod vegetables;
\ No newline at end of file
+pub mod beds;
+pub mod calendar;
+pub mod journal;
+pub mod lifecycle;
+pub mod project;
+pub mod rules;
+pub mod succession;
+pub mod vegetables;
+pub mod watering;
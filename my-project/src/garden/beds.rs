@@ -0,0 +1,335 @@
+// raised beds and where the plants go in them. everything is in centimetres
+// from the bed's top left corner, and every plant keeps half its spacing
+// clear all the way round
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::vegetables::{Catalog, Plant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Soil {
+    Sandy,
+    Loam,
+    Clay,
+    Silt,
+}
+
+impl Soil {
+    pub fn parse(text: &str) -> Option<Soil> {
+        match text.to_lowercase().as_str() {
+            "sandy" | "sand" => Some(Soil::Sandy),
+            "loam" => Some(Soil::Loam),
+            "clay" => Some(Soil::Clay),
+            "silt" => Some(Soil::Silt),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Soil::Sandy => "sandy",
+            Soil::Loam => "loam",
+            Soil::Clay => "clay",
+            Soil::Silt => "silt",
+        }
+    }
+}
+
+// one plant in the ground
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    pub plant: String,
+    pub x_cm: u32,
+    pub y_cm: u32,
+    pub spacing_cm: u32, // copied from the catalog when it was planted
+}
+
+impl Placement {
+    // closer than this many cm and the two plants crowd each other
+    fn clearance(&self, spacing_cm: u32) -> u32 {
+        (self.spacing_cm + spacing_cm).div_ceil(2)
+    }
+
+    fn crowds(&self, x_cm: u32, y_cm: u32, spacing_cm: u32) -> bool {
+        let dx = self.x_cm.abs_diff(x_cm) as u64;
+        let dy = self.y_cm.abs_diff(y_cm) as u64;
+        let clearance = self.clearance(spacing_cm) as u64;
+        dx * dx + dy * dy < clearance * clearance
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    OutsideBed { x_cm: u32, y_cm: u32 },
+    // the plant needs more room than there is between x and the edge
+    TooCloseToEdge { plant: String },
+    Overlaps { plant: String, with: String, x_cm: u32, y_cm: u32 },
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::OutsideBed { x_cm, y_cm } => write!(f, "{}, {} is outside the bed", x_cm, y_cm),
+            PlanError::TooCloseToEdge { plant } => write!(f, "{} is too close to the edge of the bed", plant),
+            PlanError::Overlaps { plant, with, x_cm, y_cm } => {
+                write!(f, "{} would crowd the {} at {}, {}", plant, with, x_cm, y_cm)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bed {
+    pub name: String,
+    pub width_cm: u32,  // across, x
+    pub length_cm: u32, // down, y
+    pub soil: Soil,
+    placements: Vec<Placement>,
+}
+
+impl Bed {
+    pub fn new(name: &str, width_cm: u32, length_cm: u32, soil: Soil) -> Bed {
+        Bed {
+            name: String::from(name),
+            width_cm,
+            length_cm,
+            soil,
+            placements: Vec::new(),
+        }
+    }
+
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements
+    }
+
+    pub fn area_m2(&self) -> f64 {
+        self.width_cm as f64 * self.length_cm as f64 / 10_000.0
+    }
+
+    // why the plant can't go here, if it can't
+    pub fn check(&self, plant: &Plant, x_cm: u32, y_cm: u32) -> Result<(), PlanError> {
+        if x_cm >= self.width_cm || y_cm >= self.length_cm {
+            return Err(PlanError::OutsideBed { x_cm, y_cm });
+        }
+        let half = plant.spacing_cm / 2;
+        // x and y are inside the bed, so the room left to the edge can't underflow
+        if x_cm < half || y_cm < half || half > self.width_cm - x_cm || half > self.length_cm - y_cm {
+            return Err(PlanError::TooCloseToEdge {
+                plant: plant.name.clone(),
+            });
+        }
+        match self.placements.iter().find(|other| other.crowds(x_cm, y_cm, plant.spacing_cm)) {
+            Some(other) => Err(PlanError::Overlaps {
+                plant: plant.name.clone(),
+                with: other.plant.clone(),
+                x_cm: other.x_cm,
+                y_cm: other.y_cm,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn place(&mut self, plant: &Plant, x_cm: u32, y_cm: u32) -> Result<(), PlanError> {
+        self.check(plant, x_cm, y_cm)?;
+        self.placements.push(Placement {
+            plant: plant.name.clone(),
+            x_cm,
+            y_cm,
+            spacing_cm: plant.spacing_cm,
+        });
+        Ok(())
+    }
+
+    // put back a plant that was saved, without checking it again: the
+    // catalog might have changed its spacing since it went in
+    pub fn restore(&mut self, placement: Placement) {
+        self.placements.push(placement);
+    }
+
+    // take a plant out again, whatever is closest to x, y
+    pub fn remove(&mut self, x_cm: u32, y_cm: u32) -> Option<Placement> {
+        let (index, _) = self.placements.iter().enumerate().min_by_key(|(_, placement)| {
+            let dx = placement.x_cm.abs_diff(x_cm) as u64;
+            let dy = placement.y_cm.abs_diff(y_cm) as u64;
+            dx * dx + dy * dy
+        })?;
+        Some(self.placements.remove(index))
+    }
+
+    // ready for next season: the annuals come out and the perennials stay
+    // where they are. returns how many came out
+    pub fn clear_annuals(&mut self, catalog: &Catalog) -> usize {
+        let before = self.placements.len();
+        self.placements
+            .retain(|placement| catalog.get(&placement.plant).is_some_and(|plant| plant.is_perennial()));
+        before - self.placements.len()
+    }
+
+    // how many of each plant are in the bed
+    pub fn counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for placement in &self.placements {
+            *counts.entry(placement.plant.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+// the spots a plant would go in a square grid at its spacing, starting half a
+// spacing in from the corner
+fn grid(bed: &Bed, plant: &Plant) -> Vec<(u32, u32)> {
+    let spacing = plant.spacing_cm.max(1);
+    let half = spacing / 2;
+    let mut spots = Vec::new();
+    let mut y = half;
+    while y + half <= bed.length_cm {
+        let mut x = half;
+        while x + half <= bed.width_cm {
+            spots.push((x, y));
+            x += spacing;
+        }
+        y += spacing;
+    }
+    spots
+}
+
+// how many more of the plant fit on the grid around what's already there
+pub fn fits(bed: &Bed, plant: &Plant) -> usize {
+    let mut trial = bed.clone();
+    fill(&mut trial, plant)
+}
+
+// plant the grid spots that are still free, returns how many went in
+pub fn fill(bed: &mut Bed, plant: &Plant) -> usize {
+    let mut planted = 0;
+    for (x, y) in grid(bed, plant) {
+        if bed.place(plant, x, y).is_ok() {
+            planted += 1;
+        }
+    }
+    planted
+}
+
+// the bed seen from above, one character for every cell_cm square. a plant's
+// letter is upper case where it's planted and lower case over the ground it
+// takes up, with a key underneath
+pub fn render(bed: &Bed, cell_cm: u32) -> String {
+    let cell = cell_cm.max(1);
+    let mut letters: Vec<(String, char)> = Vec::new();
+    for placement in &bed.placements {
+        if letters.iter().any(|(name, _)| *name == placement.plant) {
+            continue;
+        }
+        // the first letter of the name nothing else is using
+        let letter = placement
+            .plant
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase())
+            .find(|c| letters.iter().all(|(_, used)| used != c))
+            .or_else(|| ('A'..='Z').find(|c| letters.iter().all(|(_, used)| used != c)))
+            .unwrap_or('?');
+        letters.push((placement.plant.clone(), letter));
+    }
+    let letter_for = |name: &str| letters.iter().find(|(plant, _)| plant == name).map_or('?', |(_, c)| *c);
+
+    let columns = bed.width_cm.div_ceil(cell);
+    let rows = bed.length_cm.div_ceil(cell);
+    let mut map = format!("{} ({} x {} cm, {:?})\n", bed.name, bed.width_cm, bed.length_cm, bed.soil);
+    map.push_str(&format!("+{}+\n", "-".repeat(columns as usize)));
+    for row in 0..rows {
+        map.push('|');
+        for column in 0..columns {
+            let (left, top) = (column * cell, row * cell);
+            let (x, y) = (left + cell / 2, top + cell / 2);
+            let planted = bed.placements.iter().find(|placement| {
+                (left..left + cell).contains(&placement.x_cm) && (top..top + cell).contains(&placement.y_cm)
+            });
+            let covered = bed.placements.iter().find(|placement| placement.crowds(x, y, 0));
+            map.push(match (planted, covered) {
+                (Some(placement), _) => letter_for(&placement.plant),
+                (None, Some(placement)) => letter_for(&placement.plant).to_ascii_lowercase(),
+                (None, None) => '.',
+            });
+        }
+        map.push_str("|\n");
+    }
+    map.push_str(&format!("+{}+\n", "-".repeat(columns as usize)));
+    for (name, letter) in &letters {
+        map.push_str(&format!("{} {}\n", letter, name));
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 25 cm apart, so it wants 12 cm clear to the edge
+    fn lettuce() -> Plant {
+        Catalog::builtin().get("lettuce").unwrap().clone()
+    }
+
+    #[test]
+    fn plants_can_go_right_up_to_their_spacing_from_the_edge() {
+        let bed = Bed::new("test", 100, 60, Soil::Loam);
+        let lettuce = lettuce();
+        for (x, y) in [(12, 12), (88, 12), (12, 48), (88, 48)] {
+            assert_eq!(bed.check(&lettuce, x, y), Ok(()), "{}, {}", x, y);
+        }
+        let too_close = Err(PlanError::TooCloseToEdge {
+            plant: String::from("Lettuce"),
+        });
+        for (x, y) in [(11, 30), (89, 30), (50, 11), (50, 49)] {
+            assert_eq!(bed.check(&lettuce, x, y), too_close, "{}, {}", x, y);
+        }
+        assert_eq!(
+            bed.check(&lettuce, 100, 30),
+            Err(PlanError::OutsideBed { x_cm: 100, y_cm: 30 })
+        );
+        assert_eq!(
+            bed.check(&lettuce, 50, 60),
+            Err(PlanError::OutsideBed { x_cm: 50, y_cm: 60 })
+        );
+    }
+
+    #[test]
+    fn neighbours_can_be_exactly_their_spacing_apart() {
+        let mut bed = Bed::new("test", 100, 60, Soil::Loam);
+        let lettuce = lettuce();
+        bed.place(&lettuce, 12, 12).unwrap();
+        assert_eq!(bed.check(&lettuce, 37, 12), Ok(()));
+        assert_eq!(
+            bed.check(&lettuce, 36, 12),
+            Err(PlanError::Overlaps {
+                plant: String::from("Lettuce"),
+                with: String::from("Lettuce"),
+                x_cm: 12,
+                y_cm: 12,
+            })
+        );
+        assert_eq!(bed.placements().len(), 1);
+    }
+
+    #[test]
+    fn a_huge_bed_has_its_area_worked_out_without_overflowing() {
+        let bed = Bed::new("field", 100_000, 100_000, Soil::Clay);
+        assert_eq!(bed.area_m2(), 1_000_000.0);
+        let bed = Bed::new("test", u32::MAX, u32::MAX, Soil::Clay);
+        assert_eq!(
+            bed.check(&lettuce(), u32::MAX - 1, 12),
+            Err(PlanError::TooCloseToEdge {
+                plant: String::from("Lettuce"),
+            })
+        );
+    }
+
+    #[test]
+    fn filling_a_bed_plants_a_grid() {
+        let mut bed = Bed::new("test", 100, 50, Soil::Loam);
+        assert_eq!(fits(&bed, &lettuce()), 8);
+        assert_eq!(fill(&mut bed, &lettuce()), 8);
+        assert_eq!(fits(&bed, &lettuce()), 0);
+        assert_eq!(bed.counts().get("Lettuce"), Some(&8));
+    }
+}
@@ -0,0 +1,375 @@
+// when to sow, plant out and pick, worked out from the frost dates. the
+// rules of thumb are the ones off the back of a seed packet: tender plants go
+// out after the last frost, hardy ones weeks before it, and nothing gets sown
+// so late it can't ripen before the first autumn frost
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::vegetables::{Frost, Plant, Sowing};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32, // 1 to 12
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    // "2026-04-20"
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Date::from_days((seconds / 86_400) as i64)
+    }
+
+    // days since 1970-01-01, the civil calendar algorithm from
+    // http://howardhinnant.github.io/date_algorithms.html
+    pub fn days(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    pub fn from_days(days: i64) -> Date {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date {
+            year: year as i32,
+            month,
+            day,
+        }
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days() + days)
+    }
+
+    pub fn days_until(&self, other: Date) -> i64 {
+        other.days() - self.days()
+    }
+
+    // the same day in another year, the 29th of February becomes the 28th
+    pub fn in_year(&self, year: i32) -> Date {
+        Date {
+            year,
+            month: self.month,
+            day: self.day.min(days_in_month(year, self.month)),
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// the average frost dates where the garden is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frosts {
+    pub last_spring: Date,
+    pub first_autumn: Date,
+}
+
+// from the first day to the last, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub start: Date,
+    pub end: Date,
+}
+
+impl Window {
+    fn new(start: Date, end: Date) -> Option<Window> {
+        if end < start {
+            return None;
+        }
+        Some(Window { start, end })
+    }
+
+    fn weeks(around: Date, from: i64, to: i64) -> Window {
+        Window {
+            start: around.add_days(from * 7),
+            end: around.add_days(to * 7),
+        }
+    }
+
+    pub fn contains(&self, date: Date) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} to {}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub plant: String,
+    pub sow_indoors: Option<Window>,
+    pub transplant: Option<Window>,
+    pub direct_sow: Option<Window>,
+    pub harvest: Option<Window>,
+    pub note: Option<String>, // anything else worth knowing this year
+}
+
+// weeks either side of the last spring frost a plant can go out
+fn planting_weeks(frost: Frost) -> (i64, i64) {
+    match frost {
+        Frost::Tender => (1, 3),
+        Frost::HalfHardy => (-2, 0),
+        Frost::Hardy => (-4, -2),
+    }
+}
+
+// how long a seedling spends in a pot before it's big enough to plant out
+pub const WEEKS_INDOORS: i64 = 6;
+
+pub fn schedule(plant: &Plant, frosts: Frosts) -> Schedule {
+    let maturity = plant.days_to_maturity as i64;
+    let (from, to) = planting_weeks(plant.frost);
+    // the last day to get it in the ground and still pick before the frost,
+    // hardy plants stand a few frosts at the end
+    let grace = match plant.frost {
+        Frost::Tender => 0,
+        Frost::HalfHardy => 14,
+        Frost::Hardy => 28,
+    };
+    let last_planting = frosts.first_autumn.add_days(grace - maturity);
+
+    let indoors = matches!(plant.sowing, Sowing::Indoors | Sowing::Either);
+    let direct = matches!(plant.sowing, Sowing::Direct | Sowing::Either);
+
+    let transplant = Window::weeks(frosts.last_spring, from, to);
+    let transplant = Window::new(transplant.start, transplant.end.min(last_planting)).filter(|_| indoors);
+    let sow_indoors = transplant.map(|transplant| Window {
+        start: transplant.start.add_days(-WEEKS_INDOORS * 7),
+        end: transplant.end.add_days(-WEEKS_INDOORS * 7),
+    });
+    let mut direct_sow = Window::new(frosts.last_spring.add_days(from * 7), last_planting).filter(|_| direct);
+
+    let mut harvest: Option<Window> = None;
+    for window in [transplant, direct_sow].into_iter().flatten() {
+        let picked = Window {
+            start: window.start.add_days(maturity),
+            end: window.end.add_days(maturity).min(frosts.first_autumn.add_days(grace)),
+        };
+        harvest = Some(match harvest {
+            None => picked,
+            Some(harvest) => Window {
+                start: picked.start.min(harvest.start),
+                end: picked.end.max(harvest.end),
+            },
+        });
+    }
+
+    // too slow to ripen in one summer, hardy things like garlic go in the
+    // autumn before and stand the winter
+    if harvest.is_none() && direct && plant.frost == Frost::Hardy {
+        let autumn = Window::weeks(frosts.first_autumn, -6, -2);
+        harvest = Some(Window {
+            start: autumn.start.add_days(maturity),
+            end: autumn.end.add_days(maturity),
+        });
+        direct_sow = Some(autumn);
+    }
+
+    Schedule {
+        plant: plant.name.clone(),
+        sow_indoors,
+        transplant,
+        direct_sow,
+        harvest,
+        note: None,
+    }
+}
+
+pub fn calendar<'a>(plants: impl IntoIterator<Item = &'a Plant>, frosts: Frosts) -> Vec<Schedule> {
+    plants.into_iter().map(|plant| schedule(plant, frosts)).collect()
+}
+
+// commas, semicolons and backslashes mean something in an iCalendar value,
+// and a value can't run over more than one line
+fn escape_ics(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ',' => escaped.push_str("\\,"),
+            ';' => escaped.push_str("\\;"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// no line in the file may be longer than 75 bytes, a long one carries on
+// on the next line after a space. never in the middle of a character
+fn fold_ics(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+// an iCalendar file with a whole-day event for every window, ready to import
+// into a phone or a shared calendar
+pub fn to_ics(schedules: &[Schedule]) -> String {
+    let stamp = Date::today();
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//my-project//garden calendar//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+    ];
+    for schedule in schedules {
+        let windows = [
+            ("sow-indoors", "Sow indoors", schedule.sow_indoors),
+            ("transplant", "Plant out", schedule.transplant),
+            ("direct-sow", "Sow outside", schedule.direct_sow),
+            ("harvest", "Harvest", schedule.harvest),
+        ];
+        for (kind, summary, window) in windows {
+            let Some(window) = window else { continue };
+            let compact = |date: Date| format!("{:04}{:02}{:02}", date.year, date.month, date.day);
+            lines.push(String::from("BEGIN:VEVENT"));
+            lines.push(format!(
+                "UID:{}-{}-{}@garden",
+                escape_ics(&schedule.plant.to_lowercase().replace(' ', "-")),
+                kind,
+                compact(window.start)
+            ));
+            lines.push(format!("DTSTAMP:{}T000000Z", compact(stamp)));
+            lines.push(format!("DTSTART;VALUE=DATE:{}", compact(window.start)));
+            // the end of an all day event is the day after the last one
+            lines.push(format!("DTEND;VALUE=DATE:{}", compact(window.end.add_days(1))));
+            lines.push(format!("SUMMARY:{}: {}", summary, escape_ics(&schedule.plant)));
+            lines.push(String::from("TRANSP:TRANSPARENT"));
+            lines.push(String::from("END:VEVENT"));
+        }
+    }
+    lines.push(String::from("END:VCALENDAR"));
+    // the spec wants CRLF line endings
+    lines.iter().map(|line| format!("{}\r\n", fold_ics(line))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_go_to_days_and_back() {
+        assert_eq!(Date::new(1970, 1, 1).days(), 0);
+        assert_eq!(Date::new(2000, 3, 1).days(), 11_017);
+        let start = Date::new(1899, 12, 25).days();
+        for days in start..start + 365 * 250 {
+            let date = Date::from_days(days);
+            assert_eq!(date.days(), days);
+            assert_eq!(Date::parse(&date.to_string()), Some(date));
+        }
+        assert_eq!(Date::new(2024, 2, 28).add_days(1), Date::new(2024, 2, 29));
+        assert_eq!(Date::new(2023, 12, 31).days_until(Date::new(2024, 3, 1)), 61);
+        assert_eq!(Date::new(2024, 2, 29).in_year(2025), Date::new(2025, 2, 28));
+        assert_eq!(Date::new(2026, 4, 20).in_year(2027), Date::new(2027, 4, 20));
+    }
+
+    #[test]
+    fn only_real_dates_parse() {
+        assert_eq!(Date::parse(" 2026-04-20 "), Some(Date::new(2026, 4, 20)));
+        for text in [
+            "2026-02-29",
+            "1900-02-29",
+            "2026-13-01",
+            "2026-04-31",
+            "2026-04-00",
+            "2026-04",
+            "april",
+        ] {
+            assert_eq!(Date::parse(text), None, "{}", text);
+        }
+        assert_eq!(Date::parse("2000-02-29"), Some(Date::new(2000, 2, 29)));
+    }
+
+    fn schedule_for(plant: &str) -> Schedule {
+        Schedule {
+            plant: String::from(plant),
+            sow_indoors: None,
+            transplant: None,
+            direct_sow: None,
+            harvest: Some(Window {
+                start: Date::new(2026, 6, 1),
+                end: Date::new(2026, 6, 14),
+            }),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn ics_values_are_escaped() {
+        let ics = to_ics(&[schedule_for("Beans, runner; \\ climbing\nred")]);
+        assert!(ics.contains("SUMMARY:Harvest: Beans\\, runner\\; \\\\ climbing\\nred\r\n"));
+        assert!(ics.contains("UID:beans\\,-runner\\;-\\\\-climbing\\nred-harvest-20260601@garden\r\n"));
+        assert!(
+            ics.contains("DTEND;VALUE=DATE:20260615\r\n"),
+            "the end is the day after the last one"
+        );
+    }
+
+    #[test]
+    fn long_ics_lines_are_folded_at_75_bytes() {
+        let ics = to_ics(&[schedule_for(&"Très longue laitue ".repeat(8))]);
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= 75, "{:?} is {} bytes", line, line.len());
+        }
+        // unfolding gives back the whole summary
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("SUMMARY:Harvest: {}\r\n", "Très longue laitue ".repeat(8))));
+    }
+}
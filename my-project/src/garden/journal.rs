@@ -0,0 +1,481 @@
+// what actually happened in the garden: every plant put in a bed gets an id,
+// and everything done to it is written down against that id with the date.
+// saved as a plain text file with tab separated fields so it can be fixed up
+// by hand
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use super::calendar::{Date, WEEKS_INDOORS};
+use super::lifecycle::{self, Lifecycles};
+use super::rules::Season;
+use super::vegetables::Catalog;
+
+// one plant in the ground, or one row of them sown together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Planting {
+    pub id: u32,
+    pub plant: String,
+    pub bed: String,
+    pub count: u32, // plants in it
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Sown,
+    Transplanted,
+    Watered { litres: f64 },
+    Fertilized { with: String },
+    Pest { what: String },
+    Harvested { grams: u32 },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Sown => write!(f, "sown"),
+            Event::Transplanted => write!(f, "transplanted"),
+            Event::Watered { litres } => write!(f, "watered {} l", litres),
+            Event::Fertilized { with } => write!(f, "fertilized with {}", with),
+            Event::Pest { what } => write!(f, "pest seen: {}", what),
+            Event::Harvested { grams } => write!(f, "harvested {} g", grams),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub planting: u32,
+    pub date: Date,
+    pub event: Event,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalError {
+    UnknownPlanting(u32),
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JournalError::UnknownPlanting(id) => write!(f, "there is no planting {} in the journal", id),
+        }
+    }
+}
+
+// a perennial picked while it should have been left to establish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EarlyHarvest {
+    pub planting: u32,
+    pub plant: String,
+    pub date: Date,
+    pub age: u32,
+    pub first_harvest: Option<i32>, // the year it could have been picked
+}
+
+impl fmt::Display for EarlyHarvest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} was picked on {} in its year {}, too young",
+            self.planting, self.plant, self.date, self.age
+        )?;
+        match self.first_harvest {
+            Some(year) => write!(f, ", leave it until {}", year),
+            None => Ok(()),
+        }
+    }
+}
+
+// how long a planting took to give its first harvest, next to how long the
+// catalog said it would
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maturity {
+    pub planting: u32,
+    pub plant: String,
+    pub actual_days: i64,
+    pub expected_days: Option<i64>, // None if the plant isn't in the catalog
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Journal {
+    plantings: Vec<Planting>,
+    entries: Vec<Entry>,
+}
+
+impl Journal {
+    pub fn new() -> Journal {
+        Journal::default()
+    }
+
+    pub fn plant(&mut self, plant: &str, bed: &str, count: u32) -> u32 {
+        let id = self.plantings.iter().map(|planting| planting.id).max().unwrap_or(0) + 1;
+        self.plantings.push(Planting {
+            id,
+            plant: String::from(plant),
+            bed: String::from(bed),
+            count,
+        });
+        id
+    }
+
+    pub fn record(&mut self, planting: u32, date: Date, event: Event) -> Result<(), JournalError> {
+        if self.planting(planting).is_none() {
+            return Err(JournalError::UnknownPlanting(planting));
+        }
+        self.entries.push(Entry { planting, date, event });
+        Ok(())
+    }
+
+    pub fn planting(&self, id: u32) -> Option<&Planting> {
+        self.plantings.iter().find(|planting| planting.id == id)
+    }
+
+    pub fn plantings(&self) -> &[Planting] {
+        &self.plantings
+    }
+
+    // everything that happened to one planting, oldest first
+    pub fn history(&self, planting: u32) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self.entries.iter().filter(|entry| entry.planting == planting).collect();
+        entries.sort_by_key(|entry| entry.date);
+        entries
+    }
+
+    // grams picked of a plant each year, across every planting of it
+    pub fn harvest_by_year(&self, plant: &str) -> BTreeMap<i32, u32> {
+        let mut years = BTreeMap::new();
+        for entry in &self.entries {
+            let Event::Harvested { grams } = entry.event else { continue };
+            let same_plant = self
+                .planting(entry.planting)
+                .is_some_and(|planting| planting.plant.eq_ignore_ascii_case(plant));
+            if same_plant {
+                *years.entry(entry.date.year).or_insert(0) += grams;
+            }
+        }
+        years
+    }
+
+    fn first(&self, planting: u32, matches: impl Fn(&Event) -> bool) -> Option<Date> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.planting == planting && matches(&entry.event))
+            .map(|entry| entry.date)
+            .min()
+    }
+
+    // when the planting went into the ground, sown there or planted out
+    pub fn planted(&self, planting: u32) -> Option<Date> {
+        self.first(planting, |event| matches!(event, Event::Sown | Event::Transplanted))
+    }
+
+    // when a plant first went into one bed, for how old the perennials there
+    // are. the same plant in another bed can be younger or older
+    pub fn planted_in(&self, plant: &str, bed: &str) -> Option<Date> {
+        self.plantings
+            .iter()
+            .filter(|planting| planting.plant.eq_ignore_ascii_case(plant) && planting.bed.eq_ignore_ascii_case(bed))
+            .filter_map(|planting| self.planted(planting.id))
+            .min()
+    }
+
+    pub fn days_to_first_harvest(&self, planting: u32) -> Option<i64> {
+        let sown = self.first(planting, |event| *event == Event::Sown)?;
+        let harvested = self.first(planting, |event| matches!(event, Event::Harvested { .. }))?;
+        Some(sown.days_until(harvested))
+    }
+
+    // every annual planting that's been sown and picked. seed packets count
+    // the days from planting out, so a transplanted plant is expected to take
+    // its time in a pot on top. perennials go by lifecycle instead
+    pub fn maturity(&self, catalog: &Catalog) -> Vec<Maturity> {
+        self.plantings
+            .iter()
+            .filter(|planting| catalog.get(&planting.plant).is_none_or(|plant| !plant.is_perennial()))
+            .filter_map(|planting| {
+                let actual_days = self.days_to_first_harvest(planting.id)?;
+                let transplanted = self.first(planting.id, |event| *event == Event::Transplanted).is_some();
+                let expected_days = catalog.get(&planting.plant).map(|plant| {
+                    plant.days_to_maturity as i64 + if transplanted { WEEKS_INDOORS * 7 } else { 0 }
+                });
+                Some(Maturity {
+                    planting: planting.id,
+                    plant: planting.plant.clone(),
+                    actual_days,
+                    expected_days,
+                })
+            })
+            .collect()
+    }
+
+    // every harvest of a perennial before it was established. a planting
+    // nobody wrote down going in is as old as the first of it in its bed
+    pub fn early_harvests(&self, catalog: &Catalog, lifecycles: &Lifecycles) -> Vec<EarlyHarvest> {
+        let mut early = Vec::new();
+        for entry in &self.entries {
+            if !matches!(entry.event, Event::Harvested { .. }) {
+                continue;
+            }
+            let Some(planting) = self.planting(entry.planting) else { continue };
+            let planted = self
+                .planted(planting.id)
+                .or_else(|| self.planted_in(&planting.plant, &planting.bed));
+            let (Some(plant), Some(planted)) = (catalog.get(&planting.plant), planted) else {
+                continue;
+            };
+            if let Some(stage) = lifecycle::too_early(plant, planted, entry.date, lifecycles) {
+                early.push(EarlyHarvest {
+                    planting: planting.id,
+                    plant: planting.plant.clone(),
+                    date: entry.date,
+                    age: stage.age,
+                    first_harvest: lifecycles.first_harvest(plant, planted.year),
+                });
+            }
+        }
+        early.sort_by_key(|early| early.date);
+        early
+    }
+
+    // what went in each bed each year, by the year it was sown, for the
+    // rotation rules
+    pub fn seasons(&self) -> Vec<Season> {
+        let mut seasons: Vec<Season> = Vec::new();
+        for planting in &self.plantings {
+            let Some(year) = self.planted(planting.id).map(|date| date.year) else { continue };
+            match seasons
+                .iter_mut()
+                .find(|season| season.year == year && season.bed == planting.bed)
+            {
+                Some(season) => {
+                    if !season.plants.contains(&planting.plant) {
+                        season.plants.push(planting.plant.clone());
+                    }
+                }
+                None => seasons.push(Season {
+                    year,
+                    bed: planting.bed.clone(),
+                    plants: vec![planting.plant.clone()],
+                }),
+            }
+        }
+        seasons
+    }
+
+    // a planting line for every planting, then an event line for every
+    // entry, the fields of each line ready to be joined with tabs
+    pub fn lines(&self) -> Vec<Vec<String>> {
+        let mut lines: Vec<Vec<String>> = Vec::new();
+        for planting in &self.plantings {
+            lines.push(vec![
+                String::from("planting"),
+                planting.id.to_string(),
+                planting.plant.clone(),
+                planting.bed.clone(),
+                planting.count.to_string(),
+            ]);
+        }
+        for entry in &self.entries {
+            let mut fields = vec![String::from("event"), entry.planting.to_string(), entry.date.to_string()];
+            match &entry.event {
+                Event::Sown => fields.push(String::from("sown")),
+                Event::Transplanted => fields.push(String::from("transplanted")),
+                Event::Watered { litres } => fields.extend([String::from("watered"), litres.to_string()]),
+                Event::Fertilized { with } => fields.extend([String::from("fertilized"), with.clone()]),
+                Event::Pest { what } => fields.extend([String::from("pest"), what.clone()]),
+                Event::Harvested { grams } => fields.extend([String::from("harvested"), grams.to_string()]),
+            }
+            lines.push(fields);
+        }
+        lines
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut text = String::new();
+        for fields in self.lines() {
+            // a tab or a newline inside a field would break the line up
+            let fields: Vec<String> = fields.iter().map(|field| field.replace(['\t', '\n'], " ")).collect();
+            text.push_str(&fields.join("\t"));
+            text.push('\n');
+        }
+        // write next to the real file first, so a crash never leaves half a
+        // journal behind
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, text)?;
+        fs::rename(&temp, path)
+    }
+
+    // a missing file is an empty journal
+    pub fn load(path: &Path) -> io::Result<Journal> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Journal::new()),
+            Err(error) => return Err(error),
+        };
+        let mut journal = Journal::new();
+        for (number, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            journal.parse_line(&fields).map_err(|problem| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: line {}: {}", path.display(), number + 1, problem),
+                )
+            })?;
+        }
+        Ok(journal)
+    }
+
+    // one line written by lines() back into the journal
+    pub fn parse_line(&mut self, fields: &[&str]) -> Result<(), String> {
+        match fields {
+            // journals from before plantings had a count were one plant each
+            ["planting", id, plant, bed, count @ ..] => {
+                let id = id.parse().map_err(|_| format!("bad planting id '{}'", id))?;
+                let count = match count {
+                    [] => 1,
+                    [count] => count.parse().map_err(|_| format!("bad count '{}'", count))?,
+                    _ => return Err(String::from("too many fields for a planting")),
+                };
+                self.plantings.push(Planting {
+                    id,
+                    plant: plant.to_string(),
+                    bed: bed.to_string(),
+                    count,
+                });
+            }
+            ["event", planting, date, event @ ..] => {
+                let planting = planting.parse().map_err(|_| format!("bad planting id '{}'", planting))?;
+                let date = Date::parse(date).ok_or_else(|| format!("bad date '{}'", date))?;
+                let event = match event {
+                    ["sown"] => Event::Sown,
+                    ["transplanted"] => Event::Transplanted,
+                    ["watered", litres] => Event::Watered {
+                        litres: litres.parse().map_err(|_| format!("bad litres '{}'", litres))?,
+                    },
+                    ["fertilized", with] => Event::Fertilized { with: with.to_string() },
+                    ["pest", what] => Event::Pest { what: what.to_string() },
+                    ["harvested", grams] => Event::Harvested {
+                        grams: grams.parse().map_err(|_| format!("bad grams '{}'", grams))?,
+                    },
+                    _ => return Err(format!("unknown event '{}'", event.join(" "))),
+                };
+                self.record(planting, date, event).map_err(|error| error.to_string())?;
+            }
+            _ => return Err(String::from("expected a planting or an event")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn journal() -> Journal {
+        let mut journal = Journal::new();
+        let lettuce = journal.plant("Lettuce", "north", 6);
+        let tomato = journal.plant("Tomato", "south", 1);
+        journal.record(lettuce, Date::new(2026, 4, 1), Event::Sown).unwrap();
+        journal.record(lettuce, Date::new(2026, 5, 20), Event::Harvested { grams: 400 }).unwrap();
+        journal.record(lettuce, Date::new(2026, 5, 1), Event::Watered { litres: 2.5 }).unwrap();
+        journal.record(tomato, Date::new(2026, 5, 25), Event::Transplanted).unwrap();
+        let with = String::from("comfrey\ttea");
+        journal.record(tomato, Date::new(2026, 6, 10), Event::Fertilized { with }).unwrap();
+        journal.record(tomato, Date::new(2026, 7, 1), Event::Pest { what: String::from("aphids") }).unwrap();
+        journal.record(tomato, Date::new(2026, 8, 1), Event::Harvested { grams: 1200 }).unwrap();
+        journal
+    }
+
+    #[test]
+    fn the_journal_survives_a_save_and_load() {
+        let path = env::temp_dir().join(format!("garden-journal-{}.tsv", std::process::id()));
+        let journal = journal();
+        journal.save(&path).unwrap();
+        let loaded = Journal::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.plantings(), journal.plantings());
+        assert_eq!(loaded.history(1), journal.history(1));
+        // a tab in a field can't be saved, it comes back as a space
+        let with = String::from("comfrey tea");
+        assert_eq!(loaded.history(2)[1].event, Event::Fertilized { with });
+        assert_eq!(Journal::load(&path).unwrap(), Journal::new(), "a missing journal is an empty one");
+    }
+
+    #[test]
+    fn bad_lines_say_where_they_are() {
+        let path = env::temp_dir().join(format!("garden-journal-bad-{}.tsv", std::process::id()));
+        fs::write(&path, "planting\t1\tLettuce\tnorth\t6\n\nevent\t1\t2026-04-31\tsown\n").unwrap();
+        let error = Journal::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), format!("{}: line 3: bad date '2026-04-31'", path.display()));
+
+        let mut journal = Journal::new();
+        assert_eq!(
+            journal.parse_line(&["event", "7", "2026-04-01", "sown"]),
+            Err(String::from("there is no planting 7 in the journal"))
+        );
+        assert_eq!(
+            journal.parse_line(&["planting", "1", "Lettuce", "north", "6", "extra"]),
+            Err(String::from("too many fields for a planting"))
+        );
+        // saved before plantings had a count
+        journal.parse_line(&["planting", "1", "Lettuce", "north"]).unwrap();
+        assert_eq!(journal.planting(1).unwrap().count, 1);
+    }
+
+    #[test]
+    fn history_is_oldest_first_and_harvests_add_up_by_year() {
+        let mut journal = journal();
+        let dates: Vec<Date> = journal.history(1).iter().map(|entry| entry.date).collect();
+        assert_eq!(dates, [Date::new(2026, 4, 1), Date::new(2026, 5, 1), Date::new(2026, 5, 20)]);
+        assert_eq!(journal.harvest_by_year("tomato"), BTreeMap::from([(2026, 1200)]));
+        assert_eq!(journal.days_to_first_harvest(1), Some(49));
+        assert_eq!(journal.days_to_first_harvest(2), None, "the tomato was never sown, it was bought in");
+        assert_eq!(
+            journal.record(9, Date::new(2026, 4, 1), Event::Sown),
+            Err(JournalError::UnknownPlanting(9))
+        );
+    }
+
+    #[test]
+    fn a_perennial_is_as_old_as_it_is_in_its_own_bed() {
+        let catalog = Catalog::builtin();
+        let lifecycles = Lifecycles::builtin();
+        let mut journal = Journal::new();
+        let old = journal.plant("Asparagus", "north", 10);
+        let young = journal.plant("Asparagus", "south", 10);
+        let unrecorded = journal.plant("Asparagus", "south", 5);
+        journal.record(old, Date::new(2023, 3, 20), Event::Sown).unwrap();
+        journal.record(young, Date::new(2026, 3, 20), Event::Sown).unwrap();
+        for planting in [old, young, unrecorded] {
+            journal.record(planting, Date::new(2026, 5, 10), Event::Harvested { grams: 200 }).unwrap();
+        }
+        assert_eq!(journal.planted_in("asparagus", "North"), Some(Date::new(2023, 3, 20)));
+        assert_eq!(journal.planted_in("Asparagus", "south"), Some(Date::new(2026, 3, 20)));
+        assert_eq!(journal.planted_in("Asparagus", "east"), None);
+
+        // the north bed's crowns are four years old, the south bed's went in this spring
+        let early = journal.early_harvests(&catalog, &lifecycles);
+        let plantings: Vec<u32> = early.iter().map(|early| early.planting).collect();
+        assert_eq!(plantings, [young, unrecorded]);
+        assert_eq!((early[0].age, early[0].first_harvest), (1, Some(2028)));
+    }
+
+    #[test]
+    fn seasons_group_plantings_by_bed_and_year() {
+        let mut journal = journal();
+        let again = journal.plant("Lettuce", "north", 6);
+        journal.record(again, Date::new(2026, 6, 1), Event::Sown).unwrap();
+        let seasons = journal.seasons();
+        assert_eq!(seasons.len(), 2);
+        assert_eq!((seasons[0].year, seasons[0].bed.as_str()), (2026, "north"));
+        assert_eq!(seasons[0].plants, ["Lettuce"]);
+        assert_eq!(seasons[1].plants, ["Tomato"]);
+    }
+}
@@ -0,0 +1,250 @@
+// plants that come back every year change as they get older: asparagus mustn't
+// be cut at all for its first two years, then gets a longer season and a
+// bigger crop every year until it's in its prime. the ages are read from
+// perennials.csv, the year a plant goes in is age 1
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use super::calendar::{self, Date, Frosts, Schedule, Window};
+use super::vegetables::{Lifespan, Plant};
+
+// the table that ships with the binary, for when there's no perennials.csv
+const BUILTIN: &str = include_str!("../../perennials.csv");
+
+// one plant at one age
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stage {
+    pub age: u32,
+    pub harvest_weeks: u32, // 0 while it's establishing
+    pub yield_g: u32,       // a plant, over the whole harvest
+}
+
+impl Stage {
+    pub fn can_harvest(&self) -> bool {
+        self.harvest_weeks > 0
+    }
+}
+
+// a plant's age in a year, None before it went in
+pub fn age(planted: i32, year: i32) -> Option<u32> {
+    if year < planted {
+        return None;
+    }
+    Some((year - planted + 1) as u32)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Lifecycles {
+    // keyed by lowercase name, (from age, weeks, grams) sorted by age
+    stages: BTreeMap<String, Vec<(u32, u32, u32)>>,
+}
+
+impl Lifecycles {
+    pub fn builtin() -> Lifecycles {
+        Lifecycles::parse(BUILTIN).expect("the built in perennials.csv is valid")
+    }
+
+    // perennials.csv from this path, or the built in table if there isn't one
+    pub fn load(path: &Path) -> io::Result<Lifecycles> {
+        match fs::read_to_string(path) {
+            Ok(text) => Lifecycles::parse(&text)
+                .map_err(|problem| io::Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), problem))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Lifecycles::builtin()),
+            Err(error) => Err(error),
+        }
+    }
+
+    // blank lines and lines starting with # are skipped
+    pub fn parse(text: &str) -> Result<Lifecycles, String> {
+        let mut lifecycles = Lifecycles::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let problem = |problem: String| format!("line {}: {}", number + 1, problem);
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            let [name, age, weeks, grams] = fields[..] else {
+                return Err(problem(format!("expected 4 fields, found {}", fields.len())));
+            };
+            let value = |field: &str, what: &str| {
+                field
+                    .parse::<u32>()
+                    .map_err(|_| problem(format!("bad {} '{}'", what, field)))
+            };
+            let stage = (value(age, "age")?, value(weeks, "harvest weeks")?, value(grams, "yield")?);
+            if stage.0 == 0 {
+                return Err(problem(String::from("ages start at 1, the year it went in")));
+            }
+            let stages = lifecycles.stages.entry(name.to_lowercase()).or_default();
+            stages.push(stage);
+            stages.sort_by_key(|(age, _, _)| *age);
+        }
+        Ok(lifecycles)
+    }
+
+    // how the plant does at an age. annuals are always ready, perennials
+    // without a table go by their establishment years and then the catalog
+    pub fn stage(&self, plant: &Plant, age: u32) -> Stage {
+        let prime = Stage {
+            age,
+            harvest_weeks: plant.harvest_weeks,
+            yield_g: plant.yield_g,
+        };
+        let Lifespan::Perennial { establishment_years } = plant.lifespan else {
+            return prime;
+        };
+        // the table can't make a plant ready before the catalog says it is
+        if age <= establishment_years {
+            return Stage {
+                age,
+                harvest_weeks: 0,
+                yield_g: 0,
+            };
+        }
+        let row = self
+            .stages
+            .get(&plant.name.to_lowercase())
+            .and_then(|stages| stages.iter().rev().find(|(from, _, _)| *from <= age));
+        match row {
+            Some(&(_, harvest_weeks, yield_g)) => Stage {
+                age,
+                harvest_weeks,
+                yield_g,
+            },
+            None => prime,
+        }
+    }
+
+    // the first year a plant that went in in `planted` can be picked
+    pub fn first_harvest(&self, plant: &Plant, planted: i32) -> Option<i32> {
+        // no plant is worth waiting longer than this for
+        (1..=25).find(|&age| self.stage(plant, age).can_harvest()).map(|age| planted + age as i32 - 1)
+    }
+}
+
+// the calendar for a plant in the frost dates' year, knowing when it went in.
+// annuals are just the plain calendar. a perennial that's already in isn't
+// planted again, and is only picked once it's established and for as many
+// weeks as its age allows
+pub fn schedule(plant: &Plant, frosts: Frosts, planted: Option<i32>, lifecycles: &Lifecycles) -> Schedule {
+    let mut schedule = calendar::schedule(plant, frosts);
+    if plant.lifespan == Lifespan::Annual {
+        return schedule;
+    }
+    let year = frosts.last_spring.year;
+    let planted = planted.unwrap_or(year);
+    if planted < year {
+        schedule.sow_indoors = None;
+        schedule.transplant = None;
+        schedule.direct_sow = None;
+    } else if schedule.direct_sow.is_some() {
+        // crowns and runners go in while they're still dormant
+        schedule.direct_sow = Some(Window {
+            start: frosts.last_spring.add_days(-28),
+            end: frosts.last_spring.add_days(-14),
+        });
+    }
+    schedule.harvest = age(planted, year).and_then(|age| harvest_window(plant, frosts, lifecycles.stage(plant, age)));
+    if schedule.harvest.is_none() {
+        schedule.note = lifecycles
+            .first_harvest(plant, planted)
+            .map(|first| format!("establishing, first harvest in {}", first));
+    }
+    schedule
+}
+
+// a perennial's harvest starts its days to maturity after the last frost, and
+// runs for its stage's weeks
+pub fn harvest_window(plant: &Plant, frosts: Frosts, stage: Stage) -> Option<Window> {
+    if !stage.can_harvest() {
+        return None;
+    }
+    let start = frosts.last_spring.add_days(plant.days_to_maturity as i64);
+    Some(Window {
+        start,
+        end: start.add_days(stage.harvest_weeks as i64 * 7 - 1),
+    })
+}
+
+// picked before the plant was old enough
+pub fn too_early(plant: &Plant, planted: Date, harvested: Date, lifecycles: &Lifecycles) -> Option<Stage> {
+    let stage = lifecycles.stage(plant, age(planted.year, harvested.year)?);
+    if stage.can_harvest() {
+        return None;
+    }
+    Some(stage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::garden::vegetables::Catalog;
+
+    fn frosts(year: i32) -> Frosts {
+        Frosts {
+            last_spring: Date::new(year, 4, 20),
+            first_autumn: Date::new(year, 10, 15),
+        }
+    }
+
+    #[test]
+    fn asparagus_is_left_alone_for_two_years_then_picked_for_longer() {
+        let lifecycles = Lifecycles::builtin();
+        let asparagus = Catalog::builtin().get("Asparagus").unwrap().clone();
+        let stages: Vec<(u32, u32)> = (1..=4)
+            .map(|age| lifecycles.stage(&asparagus, age))
+            .map(|stage| (stage.harvest_weeks, stage.yield_g))
+            .collect();
+        assert_eq!(stages, [(0, 0), (0, 0), (2, 100), (4, 250)]);
+        assert!(!lifecycles.stage(&asparagus, 2).can_harvest());
+        assert_eq!(lifecycles.first_harvest(&asparagus, 2024), Some(2026));
+        assert_eq!((age(2024, 2023), age(2024, 2024), age(2024, 2027)), (None, Some(1), Some(4)));
+    }
+
+    #[test]
+    fn annuals_and_perennials_without_a_table_go_by_the_catalog() {
+        let lifecycles = Lifecycles::parse("# nothing but a comment\n").unwrap();
+        let catalog = Catalog::builtin();
+        let lettuce = catalog.get("Lettuce").unwrap();
+        assert_eq!(lifecycles.stage(lettuce, 1).harvest_weeks, lettuce.harvest_weeks);
+        let asparagus = catalog.get("Asparagus").unwrap();
+        assert_eq!(lifecycles.stage(asparagus, 2).harvest_weeks, 0);
+        assert_eq!(lifecycles.stage(asparagus, 3).yield_g, asparagus.yield_g);
+    }
+
+    #[test]
+    fn the_calendar_waits_for_a_perennial_to_establish() {
+        let lifecycles = Lifecycles::builtin();
+        let asparagus = Catalog::builtin().get("Asparagus").unwrap().clone();
+        let new = schedule(&asparagus, frosts(2026), None, &lifecycles);
+        assert!(new.direct_sow.is_some());
+        assert_eq!(new.harvest, None);
+        assert_eq!(new.note.as_deref(), Some("establishing, first harvest in 2028"));
+
+        let old = schedule(&asparagus, frosts(2026), Some(2023), &lifecycles);
+        assert_eq!(old.direct_sow, None, "it's already in");
+        let harvest = old.harvest.unwrap();
+        assert_eq!(harvest.start, Date::new(2026, 5, 4));
+        assert_eq!(harvest.start.days_until(harvest.end) + 1, 4 * 7);
+    }
+
+    #[test]
+    fn bad_lines_say_where_they_are() {
+        assert_eq!(Lifecycles::parse("Asparagus,0,0,0").unwrap_err(), "line 1: ages start at 1, the year it went in");
+        assert_eq!(Lifecycles::parse("\nAsparagus,3,two,100").unwrap_err(), "line 2: bad harvest weeks 'two'");
+        assert_eq!(Lifecycles::parse("Asparagus,3,2").unwrap_err(), "line 1: expected 4 fields, found 3");
+    }
+
+    #[test]
+    fn picking_before_its_time_is_too_early() {
+        let lifecycles = Lifecycles::builtin();
+        let asparagus = Catalog::builtin().get("Asparagus").unwrap().clone();
+        let planted = Date::new(2025, 3, 20);
+        let stage = too_early(&asparagus, planted, Date::new(2026, 5, 1), &lifecycles).unwrap();
+        assert_eq!(stage.age, 2);
+        assert_eq!(too_early(&asparagus, planted, Date::new(2027, 5, 1), &lifecycles), None);
+    }
+}
@@ -0,0 +1,226 @@
+// everything about one garden in a single file: the frost dates, the beds and
+// what's planted in them, and the journal. one record per line with tab
+// separated fields, like the journal on its own
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use super::beds::{Bed, Placement, Soil};
+use super::calendar::{Date, Frosts};
+use super::journal::Journal;
+use super::vegetables::Catalog;
+
+// what a project is called in the directory it belongs to
+pub const FILE_NAME: &str = "garden.tsv";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    pub year: i32, // the season the beds are planned for
+    pub frosts: Frosts,
+    pub beds: Vec<Bed>,
+    pub journal: Journal,
+}
+
+impl Default for Project {
+    fn default() -> Project {
+        Project::new()
+    }
+}
+
+impl Project {
+    // a new garden for this year, with frost dates that will do until the
+    // real ones are set
+    pub fn new() -> Project {
+        let year = Date::today().year;
+        Project {
+            year,
+            frosts: Frosts {
+                last_spring: Date::new(year, 4, 20),
+                first_autumn: Date::new(year, 10, 15),
+            },
+            beds: Vec::new(),
+            journal: Journal::new(),
+        }
+    }
+
+    pub fn bed(&self, name: &str) -> Option<&Bed> {
+        self.beds.iter().find(|bed| bed.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn bed_mut(&mut self, name: &str) -> Option<&mut Bed> {
+        self.beds.iter_mut().find(|bed| bed.name.eq_ignore_ascii_case(name))
+    }
+
+    // on to the next year: the annuals come out of every bed and the frost
+    // dates move on a year. returns how many plants came out
+    pub fn next_season(&mut self, catalog: &Catalog) -> usize {
+        self.year += 1;
+        self.frosts = Frosts {
+            last_spring: self.frosts.last_spring.in_year(self.frosts.last_spring.year + 1),
+            first_autumn: self.frosts.first_autumn.in_year(self.frosts.first_autumn.year + 1),
+        };
+        self.beds.iter_mut().map(|bed| bed.clear_annuals(catalog)).sum()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut lines: Vec<Vec<String>> = vec![
+            vec![String::from("year"), self.year.to_string()],
+            vec![
+                String::from("frosts"),
+                self.frosts.last_spring.to_string(),
+                self.frosts.first_autumn.to_string(),
+            ],
+        ];
+        for bed in &self.beds {
+            lines.push(vec![
+                String::from("bed"),
+                bed.name.clone(),
+                bed.width_cm.to_string(),
+                bed.length_cm.to_string(),
+                String::from(bed.soil.name()),
+            ]);
+            for placement in bed.placements() {
+                lines.push(vec![
+                    String::from("place"),
+                    bed.name.clone(),
+                    placement.plant.clone(),
+                    placement.x_cm.to_string(),
+                    placement.y_cm.to_string(),
+                    placement.spacing_cm.to_string(),
+                ]);
+            }
+        }
+        lines.extend(self.journal.lines());
+
+        let mut text = String::new();
+        for fields in lines {
+            // a tab or a newline inside a field would break the line up
+            let fields: Vec<String> = fields.iter().map(|field| field.replace(['\t', '\n'], " ")).collect();
+            text.push_str(&fields.join("\t"));
+            text.push('\n');
+        }
+        // write next to the real file first, so a crash never leaves half a
+        // project behind
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, text)?;
+        fs::rename(&temp, path)
+    }
+
+    // None if there's no project file here yet
+    pub fn load(path: &Path) -> io::Result<Option<Project>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let mut project = Project::new();
+        for (number, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            project.parse_line(&fields).map_err(|problem| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}: line {}: {}", path.display(), number + 1, problem),
+                )
+            })?;
+        }
+        Ok(Some(project))
+    }
+
+    fn parse_line(&mut self, fields: &[&str]) -> Result<(), String> {
+        let number = |field: &str, what: &str| field.parse::<u32>().map_err(|_| format!("bad {} '{}'", what, field));
+        let date = |field: &str| Date::parse(field).ok_or_else(|| format!("bad date '{}'", field));
+        match fields {
+            ["year", year] => self.year = year.parse().map_err(|_| format!("bad year '{}'", year))?,
+            ["frosts", last_spring, first_autumn] => {
+                self.frosts = Frosts {
+                    last_spring: date(last_spring)?,
+                    first_autumn: date(first_autumn)?,
+                }
+            }
+            ["bed", name, width, length, soil] => {
+                let soil = Soil::parse(soil).ok_or_else(|| format!("unknown soil '{}'", soil))?;
+                self.beds
+                    .push(Bed::new(name, number(width, "width")?, number(length, "length")?, soil));
+            }
+            ["place", bed, plant, x, y, spacing] => {
+                let placement = Placement {
+                    plant: plant.to_string(),
+                    x_cm: number(x, "x")?,
+                    y_cm: number(y, "y")?,
+                    spacing_cm: number(spacing, "spacing")?,
+                };
+                self.bed_mut(bed)
+                    .ok_or_else(|| format!("no bed called '{}' before this line", bed))?
+                    .restore(placement);
+            }
+            ["planting", ..] | ["event", ..] => self.journal.parse_line(fields)?,
+            _ => return Err(format!("unknown record '{}'", fields.first().unwrap_or(&""))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::garden::journal::Event;
+    use std::env;
+
+    fn project() -> Project {
+        let catalog = Catalog::builtin();
+        let mut project = Project::new();
+        project.year = 2026;
+        project.frosts = Frosts {
+            last_spring: Date::new(2026, 4, 20),
+            first_autumn: Date::new(2026, 10, 15),
+        };
+        let mut bed = Bed::new("North\tbed", 200, 100, Soil::Clay);
+        bed.place(catalog.get("Asparagus").unwrap(), 50, 50).unwrap();
+        bed.place(catalog.get("Lettuce").unwrap(), 150, 50).unwrap();
+        project.beds.push(bed);
+        let planting = project.journal.plant("Asparagus", "North bed", 1);
+        project.journal.record(planting, Date::new(2026, 3, 25), Event::Sown).unwrap();
+        project
+    }
+
+    #[test]
+    fn a_project_survives_a_save_and_load() {
+        let path = env::temp_dir().join(format!("garden-project-{}.tsv", std::process::id()));
+        assert_eq!(Project::load(&path).unwrap(), None);
+        let project = project();
+        project.save(&path).unwrap();
+        let loaded = Project::load(&path).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // a tab in a name can't be saved, it comes back as a space
+        assert_eq!(loaded.beds[0].name, "North bed");
+        assert_eq!(loaded.beds[0].placements(), project.beds[0].placements());
+        assert_eq!((loaded.year, loaded.frosts), (project.year, project.frosts));
+        assert_eq!(loaded.journal, project.journal);
+    }
+
+    #[test]
+    fn bad_lines_say_where_they_are() {
+        let path = env::temp_dir().join(format!("garden-project-bad-{}.tsv", std::process::id()));
+        fs::write(&path, "year\t2026\nplace\tNorth\tLettuce\t10\t10\t25\n").unwrap();
+        let error = Project::load(&path).unwrap_err();
+        fs::write(&path, "year\t2026\nbed\tNorth\t100\t100\tpeat\n").unwrap();
+        let soil = Project::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            error.to_string(),
+            format!("{}: line 2: no bed called 'North' before this line", path.display())
+        );
+        assert_eq!(soil.to_string(), format!("{}: line 2: unknown soil 'peat'", path.display()));
+    }
+
+    #[test]
+    fn the_next_season_moves_the_year_on_and_keeps_the_perennials() {
+        let mut project = project();
+        assert_eq!(project.next_season(&Catalog::builtin()), 1);
+        assert_eq!(project.year, 2027);
+        assert_eq!(project.frosts.last_spring, Date::new(2027, 4, 20));
+        assert_eq!(project.frosts.first_autumn, Date::new(2027, 10, 15));
+        assert_eq!(project.beds[0].counts().into_keys().collect::<Vec<_>>(), ["Asparagus"]);
+    }
+}
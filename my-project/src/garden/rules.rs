@@ -0,0 +1,404 @@
+// companion planting and crop rotation: which plants get on in a bed, and
+// which families shouldn't come back to the same bed too soon. the rules are
+// read from rules.csv, like the plants are from plants.csv
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use super::beds::Bed;
+use super::vegetables::Catalog;
+
+// the rules that ship with the binary, for when there's no rules.csv
+const BUILTIN: &str = include_str!("../../rules.csv");
+
+// how much each thing counts for in a plan's score
+const GOOD_COMPANION: i32 = 2;
+const BAD_COMPANION: i32 = -5;
+const GOOD_FOLLOW: i32 = 3;
+const TOO_SOON: i32 = -10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Companion {
+    plants: (String, String), // lower case
+    good: bool,
+    why: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rest {
+    family: String,
+    years: u32,
+    why: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Follow {
+    before: String,
+    after: String,
+    why: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    companions: Vec<Companion>,
+    rests: Vec<Rest>,
+    follows: Vec<Follow>,
+}
+
+impl Rules {
+    pub fn builtin() -> Rules {
+        Rules::parse(BUILTIN).expect("the built in rules.csv is valid")
+    }
+
+    // rules.csv from this path, or the built in rules if there isn't one
+    pub fn load(path: &Path) -> io::Result<Rules> {
+        match fs::read_to_string(path) {
+            Ok(text) => Rules::parse(&text)
+                .map_err(|problem| io::Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), problem))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Rules::builtin()),
+            Err(error) => Err(error),
+        }
+    }
+
+    // blank lines and lines starting with # are skipped. the reason is last
+    // so it can have commas in it
+    pub fn parse(text: &str) -> Result<Rules, String> {
+        let mut rules = Rules::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            rules
+                .parse_line(line)
+                .map_err(|problem| format!("line {}: {}", number + 1, problem))?;
+        }
+        Ok(rules)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), String> {
+        let (kind, rest) = line.split_once(',').ok_or("expected a rule")?;
+        match kind.trim() {
+            "companion" => {
+                let fields: Vec<&str> = rest.splitn(4, ',').map(|field| field.trim()).collect();
+                let [first, second, effect, why] = fields[..] else {
+                    return Err(String::from("expected companion,<plant>,<plant>,good|bad,<why>"));
+                };
+                let good = match effect {
+                    "good" => true,
+                    "bad" => false,
+                    _ => return Err(format!("unknown effect '{}'", effect)),
+                };
+                self.companions.push(Companion {
+                    plants: (first.to_lowercase(), second.to_lowercase()),
+                    good,
+                    why: String::from(why),
+                });
+            }
+            "rest" => {
+                let fields: Vec<&str> = rest.splitn(3, ',').map(|field| field.trim()).collect();
+                let [family, years, why] = fields[..] else {
+                    return Err(String::from("expected rest,<family>,<years>,<why>"));
+                };
+                self.rests.push(Rest {
+                    family: family.to_lowercase(),
+                    years: years.parse().map_err(|_| format!("bad years '{}'", years))?,
+                    why: String::from(why),
+                });
+            }
+            "after" => {
+                let fields: Vec<&str> = rest.splitn(3, ',').map(|field| field.trim()).collect();
+                let [before, after, why] = fields[..] else {
+                    return Err(String::from("expected after,<family>,<family>,<why>"));
+                };
+                self.follows.push(Follow {
+                    before: before.to_lowercase(),
+                    after: after.to_lowercase(),
+                    why: String::from(why),
+                });
+            }
+            other => return Err(format!("unknown rule '{}'", other)),
+        }
+        Ok(())
+    }
+
+    fn companion(&self, first: &str, second: &str) -> Option<&Companion> {
+        let (first, second) = (first.to_lowercase(), second.to_lowercase());
+        self.companions.iter().find(|companion| {
+            let (a, b) = &companion.plants;
+            (*a == first && *b == second) || (*a == second && *b == first)
+        })
+    }
+
+    // years the family has to stay out of a bed, nothing means it can come
+    // straight back
+    fn rest(&self, family: &str) -> Option<&Rest> {
+        self.rests.iter().find(|rest| rest.family == family)
+    }
+}
+
+// what was grown in one bed one year
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Season {
+    pub year: i32,
+    pub bed: String,
+    pub plants: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Good,
+    Warning,
+}
+
+// one thing the rules found, and why it matters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    pub bed: String,
+    pub verdict: Verdict,
+    pub points: i32,
+    pub message: String,
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mark = match self.verdict {
+            Verdict::Good => "+",
+            Verdict::Warning => "!",
+        };
+        write!(f, "{} {} ({:+}): {}", mark, self.bed, self.points, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub score: i32,
+    pub notes: Vec<Note>,
+}
+
+impl Report {
+    fn note(&mut self, bed: &str, points: i32, message: String) {
+        self.score += points;
+        self.notes.push(Note {
+            bed: String::from(bed),
+            verdict: if points < 0 { Verdict::Warning } else { Verdict::Good },
+            points,
+            message,
+        });
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Note> {
+        self.notes.iter().filter(|note| note.verdict == Verdict::Warning)
+    }
+}
+
+fn families<'a>(plants: impl IntoIterator<Item = &'a String>, catalog: &Catalog) -> BTreeSet<String> {
+    plants
+        .into_iter()
+        .filter_map(|plant| catalog.get(plant))
+        .map(|plant| plant.family.clone())
+        .collect()
+}
+
+// the families grown in a bed each year before this one, latest first
+fn past<'a>(bed: &str, year: i32, history: &'a [Season]) -> Vec<&'a Season> {
+    let mut seasons: Vec<&Season> = history
+        .iter()
+        .filter(|season| season.bed == bed && season.year < year)
+        .collect();
+    seasons.sort_by_key(|season| -season.year);
+    seasons
+}
+
+// score this year's beds against each other and against what was grown in
+// them before. higher is better, every point comes with a note saying why
+pub fn score(beds: &[Bed], year: i32, history: &[Season], catalog: &Catalog, rules: &Rules) -> Report {
+    let mut report = Report::default();
+    for bed in beds {
+        let plants: BTreeSet<String> = bed.placements().iter().map(|placement| placement.plant.clone()).collect();
+        let plants: Vec<&String> = plants.iter().collect();
+
+        for (i, first) in plants.iter().enumerate() {
+            for second in &plants[i + 1..] {
+                if let Some(companion) = rules.companion(first, second) {
+                    let (points, how) = if companion.good {
+                        (GOOD_COMPANION, "go well together")
+                    } else {
+                        (BAD_COMPANION, "shouldn't share a bed")
+                    };
+                    report.note(&bed.name, points, format!("{} and {} {}: {}", first, second, how, companion.why));
+                }
+            }
+        }
+
+        let now = families(plants.iter().copied(), catalog);
+        let seasons = past(&bed.name, year, history);
+        // perennials are meant to stay where they are year after year
+        let annuals = plants
+            .iter()
+            .copied()
+            .filter(|plant| catalog.get(plant).is_some_and(|plant| !plant.is_perennial()));
+        for family in &families(annuals, catalog) {
+            let Some(rest) = rules.rest(family) else { continue };
+            let last = seasons
+                .iter()
+                .find(|season| families(&season.plants, catalog).contains(family));
+            if let Some(last) = last {
+                let gap = (year - last.year - 1) as u32;
+                if gap < rest.years {
+                    report.note(
+                        &bed.name,
+                        TOO_SOON,
+                        format!(
+                            "{} family was here in {}, it should rest {} years and has rested {}: {}",
+                            family, last.year, rest.years, gap, rest.why
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(last_year) = seasons.first().filter(|season| season.year == year - 1) {
+            let before = families(&last_year.plants, catalog);
+            for follow in &rules.follows {
+                if before.contains(&follow.before) && now.contains(&follow.after) {
+                    report.note(
+                        &bed.name,
+                        GOOD_FOLLOW,
+                        format!("{} after last year's {}: {}", follow.after, follow.before, follow.why),
+                    );
+                }
+            }
+        }
+    }
+    report
+}
+
+// a family that could go in a bed this year
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub family: String,
+    pub why: String,
+}
+
+// the families a bed is ready for this year, the ones that do well after
+// last year's crop first. families still resting are left out
+pub fn suggest(bed: &str, year: i32, history: &[Season], catalog: &Catalog, rules: &Rules) -> Vec<Suggestion> {
+    let seasons = past(bed, year, history);
+    let last_year = seasons
+        .first()
+        .filter(|season| season.year == year - 1)
+        .map(|season| families(&season.plants, catalog))
+        .unwrap_or_default();
+
+    // perennials don't rotate, they get a bed of their own
+    let all: BTreeSet<String> = catalog
+        .plants()
+        .filter(|plant| !plant.is_perennial())
+        .map(|plant| plant.family.clone())
+        .collect();
+    let mut good = Vec::new();
+    let mut fine = Vec::new();
+    for family in all {
+        let resting = rules.rest(&family).is_some_and(|rest| {
+            seasons.iter().any(|season| {
+                (year - season.year - 1) < rest.years as i32 && families(&season.plants, catalog).contains(&family)
+            })
+        });
+        if resting {
+            continue;
+        }
+        match rules
+            .follows
+            .iter()
+            .find(|follow| follow.after == family && last_year.contains(&follow.before))
+        {
+            Some(follow) => good.push(Suggestion {
+                family,
+                why: follow.why.clone(),
+            }),
+            None => fine.push(Suggestion {
+                family,
+                why: String::from("hasn't been in the bed recently"),
+            }),
+        }
+    }
+    good.extend(fine);
+    good
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::garden::beds::Soil;
+
+    // a bed with one of each plant in a row
+    fn bed(name: &str, plants: &[&str], catalog: &Catalog) -> Bed {
+        let mut bed = Bed::new(name, 100 * plants.len() as u32, 100, Soil::Loam);
+        for (i, plant) in plants.iter().enumerate() {
+            bed.place(catalog.get(plant).unwrap(), 100 * i as u32 + 50, 50).unwrap();
+        }
+        bed
+    }
+
+    fn season(year: i32, bed: &str, plants: &[&str]) -> Season {
+        Season {
+            year,
+            bed: String::from(bed),
+            plants: plants.iter().map(|plant| plant.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn companions_score_both_ways() {
+        let catalog = Catalog::builtin();
+        let beds = [bed("north", &["Tomato", "Basil", "Potato"], &catalog)];
+        let report = score(&beds, 2026, &[], &catalog, &Rules::builtin());
+        assert_eq!(report.score, GOOD_COMPANION + BAD_COMPANION);
+        let warnings: Vec<&Note> = report.warnings().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "! north (-5): Potato and Tomato shouldn't share a bed: they share blight and pass it between them"
+        );
+    }
+
+    #[test]
+    fn a_family_back_too_soon_loses_points_and_a_good_follow_gains_them() {
+        let catalog = Catalog::builtin();
+        let beds = [bed("north", &["Potato"], &catalog), bed("south", &["Potato"], &catalog)];
+        let history = [
+            season(2022, "south", &["Tomato"]),
+            season(2024, "north", &["Tomato"]),
+            season(2025, "north", &["Onion"]),
+        ];
+        let report = score(&beds, 2026, &history, &catalog, &Rules::builtin());
+        assert_eq!(report.score, TOO_SOON + GOOD_FOLLOW);
+        assert!(report.notes.iter().all(|note| note.bed == "north"), "south has rested three years");
+        assert!(report.notes[0].message.starts_with("nightshade family was here in 2024"));
+    }
+
+    #[test]
+    fn suggestions_put_good_follows_first_and_leave_out_resting_families() {
+        let catalog = Catalog::builtin();
+        let history = [season(2025, "north", &["Bean"])];
+        let suggestions = suggest("north", 2026, &history, &catalog, &Rules::builtin());
+        assert_eq!(suggestions[0].family, "brassica");
+        assert!(suggestions.iter().all(|suggestion| suggestion.family != "legume"));
+        assert!(suggestions.iter().all(|suggestion| suggestion.family != "asparagus"), "perennials stay put");
+    }
+
+    #[test]
+    fn bad_rules_say_where_they_are() {
+        let rules = Rules::parse("# a comment\n\nrest, allium, 2, white rot, and onion fly\n").unwrap();
+        assert_eq!(rules.rest("allium").unwrap().why, "white rot, and onion fly");
+        assert_eq!(
+            Rules::parse("rest,allium,2,ok\ncompanion,Tomato,Basil,meh,why").unwrap_err(),
+            "line 2: unknown effect 'meh'"
+        );
+        assert_eq!(Rules::parse("sometimes,Tomato").unwrap_err(), "line 1: unknown rule 'sometimes'");
+        assert_eq!(Rules::parse("rest,allium,two,why").unwrap_err(), "line 1: bad years 'two'");
+    }
+}
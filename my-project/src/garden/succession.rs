@@ -0,0 +1,312 @@
+// sowing a little every few weeks instead of everything at once, so there's
+// always something to pick: from how much a week we want, work back to when
+// each batch has to be sown and how much bed it takes up. and afterwards, how
+// much the journal says we actually got next to what we expected
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::calendar::{self, Date, Frosts, Window, WEEKS_INDOORS};
+use super::journal::{Event, Journal};
+use super::lifecycle::{self, Lifecycles};
+use super::vegetables::{Catalog, Plant};
+
+// how much we want to pick every week
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Supply {
+    Plants(u32), // like 2 lettuces
+    Grams(u32),
+}
+
+// one batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sowing {
+    pub sow: Date,
+    pub indoors: bool,
+    pub plant_out: Date, // the same as sow for a batch sown straight in the bed
+    pub harvest: Window,
+    pub plants: u32,
+    pub area_m2: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub plant: String,
+    pub sowings: Vec<Sowing>,
+    pub gaps: Vec<Window>, // weeks nothing can be sown in time for
+    pub peak_area_m2: f64, // the most bed in use on any one day
+}
+
+impl Plan {
+    pub fn plants(&self) -> u32 {
+        self.sowings.iter().map(|sowing| sowing.plants).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuccessionError {
+    Perennial(String),
+    NoYield(String),
+    NoSupply,
+    BackwardsDates(Date, Date),
+    TooMuch(String), // more plants or grams than fit in a u32
+}
+
+impl fmt::Display for SuccessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SuccessionError::Perennial(plant) => write!(f, "{} is a perennial, it isn't sown again every year", plant),
+            SuccessionError::NoYield(plant) => write!(f, "the catalog has no yield for {}, ask for plants instead", plant),
+            SuccessionError::NoSupply => write!(f, "ask for at least one plant or gram a week"),
+            SuccessionError::BackwardsDates(from, until) => write!(f, "{} comes after {}", from, until),
+            SuccessionError::TooMuch(plant) => write!(f, "that's more {} than anyone could grow, ask for less", plant),
+        }
+    }
+}
+
+// a plant's share of the bed, spacing all round
+fn area_m2(plant: &Plant) -> f64 {
+    (plant.spacing_cm * plant.spacing_cm) as f64 / 10_000.0
+}
+
+// a batch every time the last one stops cropping, each big enough to cover
+// the weeks until the next. the seed goes straight in the bed when the
+// calendar says that's all right on the day, otherwise it's started in pots
+// to be planted out on the day; weeks neither can reach are gaps
+pub fn plan(plant: &Plant, supply: Supply, from: Date, until: Date, frosts: Frosts) -> Result<Plan, SuccessionError> {
+    if plant.is_perennial() {
+        return Err(SuccessionError::Perennial(plant.name.clone()));
+    }
+    if until < from {
+        return Err(SuccessionError::BackwardsDates(from, until));
+    }
+    match supply {
+        Supply::Plants(0) | Supply::Grams(0) => return Err(SuccessionError::NoSupply),
+        Supply::Grams(_) if plant.yield_g == 0 => return Err(SuccessionError::NoYield(plant.name.clone())),
+        _ => {}
+    }
+    // plants in a batch that has to keep us going this many weeks
+    let too_much = || SuccessionError::TooMuch(plant.name.clone());
+    let batch = |weeks: u32| match supply {
+        Supply::Plants(count) => count.checked_mul(weeks),
+        Supply::Grams(grams) => grams.checked_mul(weeks).map(|grams| grams.div_ceil(plant.yield_g)),
+    };
+    let weeks = plant.harvest_weeks.max(1);
+    let schedule = calendar::schedule(plant, frosts);
+    let maturity = plant.days_to_maturity as i64;
+
+    let mut sowings = Vec::new();
+    let mut gaps = Vec::new();
+    let mut total: u32 = 0; // so Plan::plants can't overflow either
+    let mut start = from;
+    while start <= until {
+        let harvest = Window {
+            start,
+            end: start.add_days(weeks as i64 * 7 - 1).min(until),
+        };
+        let plant_out = start.add_days(-maturity);
+        let direct = schedule.direct_sow.is_some_and(|window| window.contains(plant_out));
+        let potted = schedule.transplant.is_some_and(|window| window.contains(plant_out));
+        if direct || potted {
+            // the last batch only has to cover what's left
+            let plants = batch((harvest.start.days_until(harvest.end) as u32 + 1).div_ceil(7)).ok_or_else(too_much)?;
+            total = total.checked_add(plants).ok_or_else(too_much)?;
+            sowings.push(Sowing {
+                sow: if direct { plant_out } else { plant_out.add_days(-WEEKS_INDOORS * 7) },
+                indoors: !direct,
+                plant_out,
+                harvest,
+                plants,
+                area_m2: plants as f64 * area_m2(plant),
+            });
+        } else {
+            gaps.push(harvest);
+        }
+        start = harvest.end.add_days(1);
+    }
+
+    // a batch is in the bed from planting out until its harvest is over
+    let peak_area_m2 = sowings
+        .iter()
+        .map(|sowing| {
+            sowings
+                .iter()
+                .filter(|other| other.plant_out <= sowing.plant_out && sowing.plant_out <= other.harvest.end)
+                .map(|other| other.area_m2)
+                .sum::<f64>()
+        })
+        .fold(0.0, f64::max);
+
+    Ok(Plan {
+        plant: plant.name.clone(),
+        sowings,
+        gaps,
+        peak_area_m2,
+    })
+}
+
+// one plant's year in the journal next to what the catalog expects of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YieldCheck {
+    pub plant: String,
+    pub plants: u32,
+    pub estimated_g: u32,
+    pub harvested_g: u32,
+}
+
+// every plant in the ground in a year: annuals sown that year, perennials by
+// how old they were
+pub fn compare(
+    journal: &Journal,
+    catalog: &Catalog,
+    lifecycles: &Lifecycles,
+    year: i32,
+) -> Result<Vec<YieldCheck>, SuccessionError> {
+    let mut checks: BTreeMap<String, YieldCheck> = BTreeMap::new();
+    for planting in journal.plantings() {
+        let (Some(plant), Some(planted)) = (catalog.get(&planting.plant), journal.planted(planting.id)) else {
+            continue;
+        };
+        let estimate = match lifecycle::age(planted.year, year) {
+            Some(age) if plant.is_perennial() => lifecycles.stage(plant, age).yield_g,
+            _ if planted.year == year => plant.yield_g,
+            _ => continue,
+        };
+        let too_much = || SuccessionError::TooMuch(plant.name.clone());
+        let harvested = journal
+            .history(planting.id)
+            .iter()
+            .filter(|entry| entry.date.year == year)
+            .map(|entry| match entry.event {
+                Event::Harvested { grams } => grams,
+                _ => 0,
+            })
+            .try_fold(0u32, u32::checked_add)
+            .ok_or_else(too_much)?;
+        let estimate = estimate.checked_mul(planting.count).ok_or_else(too_much)?;
+        let check = checks.entry(plant.name.clone()).or_insert_with(|| YieldCheck {
+            plant: plant.name.clone(),
+            plants: 0,
+            estimated_g: 0,
+            harvested_g: 0,
+        });
+        check.plants = check.plants.checked_add(planting.count).ok_or_else(too_much)?;
+        check.estimated_g = check.estimated_g.checked_add(estimate).ok_or_else(too_much)?;
+        check.harvested_g = check.harvested_g.checked_add(harvested).ok_or_else(too_much)?;
+    }
+    Ok(checks.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frosts() -> Frosts {
+        Frosts {
+            last_spring: Date::new(2026, 4, 20),
+            first_autumn: Date::new(2026, 10, 15),
+        }
+    }
+
+    // ready 45 days after it goes in, and picked for two weeks
+    fn lettuce() -> Plant {
+        Catalog::builtin().get("Lettuce").unwrap().clone()
+    }
+
+    fn two_a_week(from: Date, until: Date) -> Plan {
+        plan(&lettuce(), Supply::Plants(2), from, until, frosts()).unwrap()
+    }
+
+    fn plan_grams(grams: u32) -> Plan {
+        plan(&lettuce(), Supply::Grams(grams), Date::new(2026, 6, 1), Date::new(2026, 6, 14), frosts()).unwrap()
+    }
+
+    #[test]
+    fn two_lettuces_a_week_is_a_batch_of_four_every_fortnight() {
+        let plan = two_a_week(Date::new(2026, 6, 1), Date::new(2026, 7, 26));
+        let sown: Vec<Date> = plan.sowings.iter().map(|sowing| sowing.sow).collect();
+        assert_eq!(
+            sown,
+            [Date::new(2026, 4, 17), Date::new(2026, 5, 1), Date::new(2026, 5, 15), Date::new(2026, 5, 29)]
+        );
+        assert!(plan.sowings.iter().all(|sowing| sowing.plants == 4 && !sowing.indoors));
+        assert_eq!(plan.sowings[0].harvest.end, Date::new(2026, 6, 14));
+        assert_eq!(plan.plants(), 16);
+        assert!(plan.gaps.is_empty());
+        // all four batches are in the bed when the last one goes in
+        assert!((plan.peak_area_m2 - 16.0 * 0.0625).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_last_batch_only_covers_what_is_left() {
+        let plan = two_a_week(Date::new(2026, 6, 1), Date::new(2026, 6, 20));
+        let plants: Vec<u32> = plan.sowings.iter().map(|sowing| sowing.plants).collect();
+        assert_eq!(plants, [4, 2]);
+        let plan = plan_grams(500);
+        assert_eq!(plan.sowings[0].plants, 4, "1000 g over two weeks is 3.3 lettuces");
+    }
+
+    #[test]
+    fn weeks_too_early_to_sow_for_are_gaps() {
+        let plan = two_a_week(Date::new(2026, 5, 1), Date::new(2026, 6, 14));
+        let gaps: Vec<Date> = plan.gaps.iter().map(|gap| gap.start).collect();
+        assert_eq!(gaps, [Date::new(2026, 5, 1), Date::new(2026, 5, 15)]);
+        let harvests: Vec<Date> = plan.sowings.iter().map(|sowing| sowing.harvest.start).collect();
+        assert_eq!(harvests, [Date::new(2026, 5, 29), Date::new(2026, 6, 12)]);
+    }
+
+    #[test]
+    fn plans_that_make_no_sense_are_refused() {
+        let catalog = Catalog::builtin();
+        let (from, until) = (Date::new(2026, 6, 1), Date::new(2026, 7, 1));
+        let asparagus = catalog.get("Asparagus").unwrap();
+        assert_eq!(
+            plan(asparagus, Supply::Plants(2), from, until, frosts()),
+            Err(SuccessionError::Perennial(String::from("Asparagus")))
+        );
+        assert_eq!(plan(&lettuce(), Supply::Grams(0), from, until, frosts()), Err(SuccessionError::NoSupply));
+        assert_eq!(
+            plan(&lettuce(), Supply::Plants(2), until, from, frosts()),
+            Err(SuccessionError::BackwardsDates(until, from))
+        );
+    }
+
+    #[test]
+    fn the_journal_is_compared_with_what_the_catalog_expects() {
+        let catalog = Catalog::builtin();
+        let mut journal = Journal::new();
+        let lettuce = journal.plant("Lettuce", "north", 6);
+        let asparagus = journal.plant("Asparagus", "south", 10);
+        journal.record(lettuce, Date::new(2026, 4, 17), Event::Sown).unwrap();
+        journal.record(lettuce, Date::new(2026, 6, 5), Event::Harvested { grams: 1000 }).unwrap();
+        journal.record(asparagus, Date::new(2023, 3, 20), Event::Sown).unwrap();
+        journal.record(asparagus, Date::new(2026, 5, 10), Event::Harvested { grams: 2000 }).unwrap();
+        journal.record(asparagus, Date::new(2025, 5, 10), Event::Harvested { grams: 900 }).unwrap();
+
+        let checks = compare(&journal, &catalog, &Lifecycles::builtin(), 2026).unwrap();
+        let found: Vec<(&str, u32, u32, u32)> = checks
+            .iter()
+            .map(|check| (check.plant.as_str(), check.plants, check.estimated_g, check.harvested_g))
+            .collect();
+        // the asparagus is four years old, 250 g a crown
+        assert_eq!(found, [("Asparagus", 10, 2500, 2000), ("Lettuce", 6, 1800, 1000)]);
+        assert!(compare(&journal, &catalog, &Lifecycles::builtin(), 2027)
+            .unwrap()
+            .iter()
+            .all(|check| check.plant == "Asparagus"));
+    }
+
+    #[test]
+    fn more_than_can_be_counted_is_refused() {
+        let (from, until) = (Date::new(2026, 6, 1), Date::new(2026, 7, 26));
+        let too_much = SuccessionError::TooMuch(String::from("Lettuce"));
+        assert_eq!(plan(&lettuce(), Supply::Plants(3_000_000_000), from, until, frosts()), Err(too_much.clone()));
+        assert_eq!(plan(&lettuce(), Supply::Grams(u32::MAX), from, until, frosts()), Err(too_much.clone()));
+        // each batch fits, all of them together don't
+        assert_eq!(plan(&lettuce(), Supply::Plants(u32::MAX / 4), from, until, frosts()), Err(too_much.clone()));
+
+        let mut journal = Journal::new();
+        let lettuce = journal.plant("Lettuce", "north", u32::MAX);
+        journal.record(lettuce, Date::new(2026, 4, 17), Event::Sown).unwrap();
+        assert_eq!(compare(&journal, &Catalog::builtin(), &Lifecycles::builtin(), 2026), Err(too_much));
+    }
+}
@@ -1,2 +1,206 @@
-#[derive(Debug)]
-pub struct Asaparagus {}
\ No newline at end of file
+// what we know about every crop we grow, read from plants.csv so a new crop
+// is one more line in the file
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+// the catalog that ships with the binary, for when there's no plants.csv
+const BUILTIN: &str = include_str!("../../plants.csv");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sun {
+    Full,
+    Partial,
+    Shade,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Water {
+    Low,
+    Medium,
+    High,
+}
+
+// how much cold a plant takes before it dies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Frost {
+    Tender,
+    HalfHardy,
+    Hardy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifespan {
+    Annual,
+    // comes back every year, but needs this many years to get established
+    // before it's worth harvesting (asparagus takes two or three)
+    Perennial { establishment_years: u32 },
+}
+
+// how it gets into the bed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sowing {
+    Indoors, // started in pots and transplanted
+    Direct,
+    Either,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plant {
+    pub name: String,
+    pub spacing_cm: u32, // between plants, and between rows
+    pub days_to_maturity: u32,
+    pub sun: Sun,
+    pub water: Water,
+    pub frost: Frost,
+    pub lifespan: Lifespan,
+    pub sowing: Sowing,
+    pub family: String,     // lower case, like "nightshade"
+    pub harvest_weeks: u32, // how long one sowing keeps cropping
+    pub yield_g: u32,       // a plant, over those weeks
+}
+
+impl Plant {
+    pub fn is_perennial(&self) -> bool {
+        matches!(self.lifespan, Lifespan::Perennial { .. })
+    }
+
+    // one line of plants.csv back into a plant
+    fn parse(line: &str) -> Result<Plant, String> {
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        let [name, spacing, days, sun, water, frost, lifespan, sowing, family, weeks, grams] = fields[..] else {
+            return Err(format!("expected 11 fields, found {}", fields.len()));
+        };
+        Ok(Plant {
+            name: name.to_string(),
+            spacing_cm: spacing.parse().map_err(|_| format!("bad spacing '{}'", spacing))?,
+            days_to_maturity: days.parse().map_err(|_| format!("bad days to maturity '{}'", days))?,
+            sun: match sun {
+                "full" => Sun::Full,
+                "partial" => Sun::Partial,
+                "shade" => Sun::Shade,
+                _ => return Err(format!("unknown sun '{}'", sun)),
+            },
+            water: match water {
+                "low" => Water::Low,
+                "medium" => Water::Medium,
+                "high" => Water::High,
+                _ => return Err(format!("unknown water '{}'", water)),
+            },
+            frost: match frost {
+                "tender" => Frost::Tender,
+                "half-hardy" => Frost::HalfHardy,
+                "hardy" => Frost::Hardy,
+                _ => return Err(format!("unknown frost tolerance '{}'", frost)),
+            },
+            lifespan: match lifespan.split_once(':') {
+                None if lifespan == "annual" => Lifespan::Annual,
+                Some(("perennial", years)) => Lifespan::Perennial {
+                    establishment_years: years.parse().map_err(|_| format!("bad establishment years '{}'", years))?,
+                },
+                _ => return Err(format!("unknown lifespan '{}'", lifespan)),
+            },
+            sowing: match sowing {
+                "indoors" => Sowing::Indoors,
+                "direct" => Sowing::Direct,
+                "either" => Sowing::Either,
+                _ => return Err(format!("unknown sowing '{}'", sowing)),
+            },
+            family: family.to_lowercase(),
+            harvest_weeks: weeks.parse().map_err(|_| format!("bad harvest weeks '{}'", weeks))?,
+            yield_g: grams.parse().map_err(|_| format!("bad yield '{}'", grams))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    plants: BTreeMap<String, Plant>, // keyed by lowercase name
+}
+
+impl Catalog {
+    pub fn builtin() -> Catalog {
+        Catalog::parse(BUILTIN).expect("the built in plants.csv is valid")
+    }
+
+    // plants.csv from this path, or the built in catalog if there isn't one
+    pub fn load(path: &Path) -> io::Result<Catalog> {
+        match fs::read_to_string(path) {
+            Ok(text) => Catalog::parse(&text)
+                .map_err(|problem| io::Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), problem))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Catalog::builtin()),
+            Err(error) => Err(error),
+        }
+    }
+
+    // blank lines and lines starting with # are skipped
+    pub fn parse(text: &str) -> Result<Catalog, String> {
+        let mut catalog = Catalog::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let plant = Plant::parse(line).map_err(|problem| format!("line {}: {}", number + 1, problem))?;
+            catalog.add(plant);
+        }
+        Ok(catalog)
+    }
+
+    pub fn add(&mut self, plant: Plant) {
+        self.plants.insert(plant.name.to_lowercase(), plant);
+    }
+
+    // names are matched without caring about case
+    pub fn get(&self, name: &str) -> Option<&Plant> {
+        self.plants.get(&name.to_lowercase())
+    }
+
+    pub fn plants(&self) -> impl Iterator<Item = &Plant> {
+        self.plants.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.plants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plants.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_line_of_plants_csv() {
+        let text = "# a comment\n\nAsparagus, 45, 14, full, medium, hardy, perennial:2, direct, Asparagus, 8, 450\n";
+        let catalog = Catalog::parse(text).unwrap();
+        assert_eq!(catalog.len(), 1);
+        let asparagus = catalog.get("ASPARAGUS").unwrap();
+        assert_eq!(asparagus.spacing_cm, 45);
+        assert_eq!(asparagus.lifespan, Lifespan::Perennial { establishment_years: 2 });
+        assert_eq!(asparagus.sowing, Sowing::Direct);
+        assert_eq!(asparagus.family, "asparagus");
+        assert!(asparagus.is_perennial());
+    }
+
+    #[test]
+    fn bad_lines_say_where_they_are() {
+        let text = "Lettuce,25,45,partial,medium,half-hardy,annual,either,daisy,2,300\nTomato,60,70,full,medium,tender\n";
+        assert_eq!(Catalog::parse(text).unwrap_err(), "line 2: expected 11 fields, found 6");
+        let text = "Lettuce,25,45,moonlight,medium,half-hardy,annual,either,daisy,2,300";
+        assert_eq!(Catalog::parse(text).unwrap_err(), "line 1: unknown sun 'moonlight'");
+        let text = "Lettuce,25,45,partial,medium,half-hardy,biennial,either,daisy,2,300";
+        assert_eq!(Catalog::parse(text).unwrap_err(), "line 1: unknown lifespan 'biennial'");
+    }
+
+    #[test]
+    fn the_builtin_catalog_parses() {
+        let catalog = Catalog::builtin();
+        assert!(!catalog.is_empty());
+        assert_eq!(catalog.get("lettuce").unwrap().lifespan, Lifespan::Annual);
+    }
+}
@@ -0,0 +1,297 @@
+// how wet each bed's soil is day by day, and when to water it. the soil is a
+// bucket: rain and watering fill it, the plants and the sun empty it, and
+// anything over the top drains away. how fast it empties comes from the
+// weather, by the Hargreaves estimate when the weather file doesn't give one
+use std::f64::consts::PI;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use super::beds::{Bed, Soil};
+use super::calendar::Date;
+use super::vegetables::{Catalog, Water};
+
+// how deep the roots we're keeping wet go
+const ROOT_DEPTH_CM: f64 = 30.0;
+
+// how hard bare soil dries out next to a crop, for beds with nothing in them
+const BARE_SOIL: f64 = 0.3;
+
+// a watering goes this far over the bottom of the band so it isn't needed
+// again tomorrow, unless rain in the next few days will do that anyway
+const MARGIN: f64 = 0.05;
+const FORECAST_DAYS: usize = 2;
+
+// one day from the weather file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weather {
+    pub date: Date,
+    pub rain_mm: f64,
+    pub temp_max_c: f64,
+    pub temp_min_c: f64,
+    pub et_mm: Option<f64>, // evapotranspiration, if the weather station gives it
+}
+
+impl Weather {
+    // millimetres of water a short grass lawn would lose today
+    pub fn evapotranspiration(&self, latitude: f64) -> f64 {
+        if let Some(et) = self.et_mm {
+            return et;
+        }
+        let mean = (self.temp_max_c + self.temp_min_c) / 2.0;
+        let range = (self.temp_max_c - self.temp_min_c).max(0.0);
+        (0.0023 * (mean + 17.8) * range.sqrt() * radiation_mm(self.date, latitude)).max(0.0)
+    }
+}
+
+// the sun's energy at the top of the atmosphere for the day and latitude, as
+// the millimetres of water it could evaporate (FAO paper 56, equation 21)
+fn radiation_mm(date: Date, latitude: f64) -> f64 {
+    let day_of_year = (Date::new(date.year, 1, 1).days_until(date) + 1) as f64;
+    let angle = 2.0 * PI * day_of_year / 365.0;
+    let distance = 1.0 + 0.033 * angle.cos();
+    let declination = 0.409 * (angle - 1.39).sin();
+    let latitude = latitude.to_radians();
+    let sunset = (-latitude.tan() * declination.tan()).clamp(-1.0, 1.0).acos();
+    let megajoules = 24.0 * 60.0 / PI
+        * 0.082
+        * distance
+        * (sunset * latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * sunset.sin());
+    megajoules * 0.408
+}
+
+// date,rain_mm,temp_max_c,temp_min_c with an optional et_mm on the end. a
+// header line and lines starting with # are skipped
+pub fn parse_weather(text: &str) -> Result<Vec<Weather>, String> {
+    let mut days = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("date") {
+            continue;
+        }
+        let problem = |problem: String| format!("line {}: {}", number + 1, problem);
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        let (date, rain, max, min, et) = match fields[..] {
+            [date, rain, max, min] => (date, rain, max, min, ""),
+            [date, rain, max, min, et] => (date, rain, max, min, et),
+            _ => return Err(problem(format!("expected 4 or 5 fields, found {}", fields.len()))),
+        };
+        // "inf" and "NaN" parse as numbers too, but no weather station means them
+        let value = |field: &str, what: &str| match field.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(problem(format!("bad {} '{}'", what, field))),
+        };
+        // it can be below freezing, but it can't rain less than nothing
+        let amount = |field: &str, what: &str| match value(field, what)? {
+            value if value < 0.0 => Err(problem(format!("{} can't be negative, found '{}'", what, field))),
+            value => Ok(value),
+        };
+        days.push(Weather {
+            date: Date::parse(date).ok_or_else(|| problem(format!("bad date '{}'", date)))?,
+            rain_mm: amount(rain, "rain")?,
+            temp_max_c: value(max, "top temperature")?,
+            temp_min_c: value(min, "bottom temperature")?,
+            et_mm: if et.is_empty() { None } else { Some(amount(et, "evapotranspiration")?) },
+        });
+    }
+    days.sort_by_key(|day| day.date);
+    Ok(days)
+}
+
+pub fn load_weather(path: &Path) -> io::Result<Vec<Weather>> {
+    let text = fs::read_to_string(path)?;
+    parse_weather(&text)
+        .map_err(|problem| io::Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), problem)))
+}
+
+// millimetres of water the soil holds for the plants per centimetre of depth
+fn holding_mm_per_cm(soil: Soil) -> f64 {
+    match soil {
+        Soil::Sandy => 1.0,
+        Soil::Loam => 1.7,
+        Soil::Clay => 1.8,
+        Soil::Silt => 2.0,
+    }
+}
+
+// how much a crop drinks next to the grass the evapotranspiration is for
+fn crop_factor(water: Water) -> f64 {
+    match water {
+        Water::Low => 0.6,
+        Water::Medium => 0.85,
+        Water::High => 1.05,
+    }
+}
+
+// the share of a full bucket to keep the soil between
+fn band(water: Water) -> (f64, f64) {
+    match water {
+        Water::Low => (0.3, 0.6),
+        Water::Medium => (0.45, 0.75),
+        Water::High => (0.55, 0.85),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Day {
+    pub date: Date,
+    pub rain_mm: f64,
+    pub used_mm: f64,    // what the plants and the sun took out
+    pub watered_mm: f64, // what we put in
+    pub drained_mm: f64, // what ran out of the bottom
+    pub moisture: f64,   // share of a full bucket at the end of the day
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub bed: String,
+    pub area_m2: f64,
+    pub band: (f64, f64),
+    pub days: Vec<Day>,
+}
+
+impl Simulation {
+    fn litres(&self, mm: f64) -> f64 {
+        // a millimetre over a square metre is a litre
+        mm * self.area_m2
+    }
+
+    // the days to water and how many litres, the watering schedule itself
+    pub fn schedule(&self) -> Vec<(Date, f64)> {
+        self.days
+            .iter()
+            .filter(|day| day.watered_mm > 0.0)
+            .map(|day| (day.date, self.litres(day.watered_mm)))
+            .collect()
+    }
+
+    pub fn total_litres(&self) -> f64 {
+        self.litres(self.days.iter().map(|day| day.watered_mm).sum())
+    }
+
+    // days that ended too dry and too wet, rain can always overdo it
+    pub fn days_out_of_band(&self) -> (usize, usize) {
+        let (low, high) = self.band;
+        let dry = self.days.iter().filter(|day| day.moisture < low - 1e-9).count();
+        let wet = self.days.iter().filter(|day| day.moisture > high + 1e-9).count();
+        (dry, wet)
+    }
+}
+
+// run the bed through the weather, starting at the middle of its band. the
+// thirstiest plant in the bed sets how much it drinks and how wet to keep it.
+// it's only watered on a day it would end up too dry, and only back up to
+// just over the bottom of the band, so nothing is poured on that the next
+// shower would have given for free
+pub fn simulate(bed: &Bed, catalog: &Catalog, weather: &[Weather], latitude: f64) -> Simulation {
+    let needs = bed
+        .placements()
+        .iter()
+        .filter_map(|placement| catalog.get(&placement.plant))
+        .map(|plant| plant.water)
+        .max();
+    let factor = needs.map_or(BARE_SOIL, crop_factor);
+    let (low, high) = band(needs.unwrap_or(Water::Low));
+    let capacity = holding_mm_per_cm(bed.soil) * ROOT_DEPTH_CM;
+
+    let mut water = capacity * (low + high) / 2.0;
+    let mut days = Vec::new();
+    for (i, today) in weather.iter().enumerate() {
+        water += today.rain_mm;
+        // plants short of water close up and drink less
+        let stress = (water / (capacity * 0.5)).min(1.0);
+        let used = (today.evapotranspiration(latitude) * factor * stress).min(water);
+        water -= used;
+
+        let mut watered = 0.0;
+        if water < capacity * low {
+            let coming: f64 = weather[i + 1..]
+                .iter()
+                .take(FORECAST_DAYS)
+                .map(|day| day.rain_mm)
+                .sum();
+            let short = capacity * low - water;
+            watered = short + (capacity * MARGIN - coming).max(0.0);
+            water += watered;
+        }
+
+        let drained = (water - capacity).max(0.0);
+        water -= drained;
+        days.push(Day {
+            date: today.date,
+            rain_mm: today.rain_mm,
+            used_mm: used,
+            watered_mm: watered,
+            drained_mm: drained,
+            moisture: water / capacity,
+        });
+    }
+    Simulation {
+        bed: bed.name.clone(),
+        area_m2: bed.area_m2(),
+        band: (low, high),
+        days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_weather_file_parses_in_date_order() {
+        let text = "date,rain_mm,temp_max_c,temp_min_c\n2026-06-02, 10.6, 20.7, 13.4\n# a comment\n";
+        let text = format!("{}2026-06-01,0,21.9,-1.5,4.2\n", text);
+        let days = parse_weather(&text).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, Date::new(2026, 6, 1));
+        assert_eq!((days[0].temp_min_c, days[0].et_mm), (-1.5, Some(4.2)));
+        assert_eq!(days[0].evapotranspiration(51.5), 4.2);
+        assert_eq!(days[1].et_mm, None);
+        assert!(days[1].evapotranspiration(51.5) > 0.0);
+        assert!(parse_weather(include_str!("../../weather.csv")).is_ok());
+    }
+
+    #[test]
+    fn weather_that_cant_be_right_is_refused() {
+        let cases = [
+            ("2026-06-01,NaN,20,10", "line 1: bad rain 'NaN'"),
+            ("2026-06-01,1,inf,10", "line 1: bad top temperature 'inf'"),
+            ("2026-06-01,-2,20,10", "line 1: rain can't be negative, found '-2'"),
+            ("2026-06-01,0,20,10,-0.5", "line 1: evapotranspiration can't be negative, found '-0.5'"),
+            ("2026-06-01,0,20", "line 1: expected 4 or 5 fields, found 3"),
+        ];
+        for (text, error) in cases {
+            assert_eq!(parse_weather(text).unwrap_err(), error);
+        }
+    }
+
+    #[test]
+    fn a_dry_month_is_watered_to_stay_in_the_band() {
+        let catalog = Catalog::builtin();
+        let mut bed = Bed::new("north", 100, 100, Soil::Sandy);
+        bed.place(catalog.get("Zucchini").unwrap(), 50, 50).unwrap();
+        // a hot dry month with a downpour in the middle of it
+        let weather: Vec<Weather> = (0..30)
+            .map(|day| Weather {
+                date: Date::new(2026, 7, 1).add_days(day),
+                rain_mm: if day == 15 { 40.0 } else { 0.0 },
+                temp_max_c: 30.0,
+                temp_min_c: 15.0,
+                et_mm: None,
+            })
+            .collect();
+        let simulation = simulate(&bed, &catalog, &weather, 51.5);
+        assert_eq!(simulation.days.len(), 30);
+        assert_eq!(simulation.band, band(Water::High));
+        assert_eq!(simulation.days_out_of_band().0, 0, "never left too dry");
+        assert!(!simulation.schedule().is_empty());
+        assert!(simulation.total_litres() > 0.0);
+        let (_, high) = simulation.band;
+        assert!(simulation.days.iter().all(|day| day.moisture <= 1.0));
+        // the downpour is the only thing that takes it over the top
+        for day in &simulation.days {
+            assert!(day.moisture <= high + 1e-9 || day.rain_mm > 0.0, "{} is too wet", day.date);
+        }
+    }
+}
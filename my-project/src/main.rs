@@ -1,11 +1,14 @@
-// get the Asparagus struct from inside a submodule
-use crate::garden::vegetables::Asaparagus;
+// the subcommands live in src/cli.rs
+mod cli;
 
 // include code from src/garden.rs
 // this is how you include garden.rs inside the crate root!
-pub mod garden; 
+pub mod garden;
 
 fn main() {
-    let plant = Asaparagus {};
-    println!("I'm growing {:?}!", plant);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(error) = cli::run(&args) {
+        eprintln!("error: {}", error);
+        std::process::exit(error.exit_code());
+    }
 }
@@ -0,0 +1,280 @@
+// money the restaurant holds for guests: gift cards and house accounts
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use crate::error::RestaurantError;
+use crate::persistence::{self, Record};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GiftCard {
+  pub number: String,
+  pub balance: u32, // in cents
+}
+
+impl Record for GiftCard {
+  fn to_fields(&self) -> Vec<String> {
+    vec![self.number.clone(), self.balance.to_string()]
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<GiftCard> {
+    match fields {
+      [number, balance] => Some(GiftCard {
+        number: number.to_string(),
+        balance: balance.parse().ok()?,
+      }),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct GiftCards {
+  cards: BTreeMap<String, GiftCard>,
+  last_number: u32, // numbers are never handed out twice
+}
+
+impl GiftCards {
+  pub fn new() -> GiftCards {
+    GiftCards::default()
+  }
+
+  // sell a new card, returns its number
+  pub fn issue(&mut self, amount: u32) -> String {
+    self.last_number += 1;
+    let number = format!("GC{:06}", self.last_number);
+    let card = GiftCard {
+      number: number.clone(),
+      balance: amount,
+    };
+    self.cards.insert(number.clone(), card);
+    number
+  }
+
+  pub fn reload(&mut self, number: &str, amount: u32) -> Result<u32, RestaurantError> {
+    let card = self.card_mut(number)?;
+    card.balance = card
+      .balance
+      .checked_add(amount)
+      .ok_or_else(|| RestaurantError::GiftCardFull(number.to_string()))?;
+    Ok(card.balance)
+  }
+
+  pub fn balance(&self, number: &str) -> Result<u32, RestaurantError> {
+    self.cards
+      .get(number)
+      .map(|card| card.balance)
+      .ok_or_else(|| RestaurantError::GiftCardNotFound(number.to_string()))
+  }
+
+  // take part or all of the balance, but never more than is on the card
+  // returns what is left
+  pub fn redeem(&mut self, number: &str, amount: u32) -> Result<u32, RestaurantError> {
+    let card = self.card_mut(number)?;
+    if amount > card.balance {
+      return Err(RestaurantError::InsufficientBalance(number.to_string(), card.balance));
+    }
+    card.balance -= amount;
+    Ok(card.balance)
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    let cards: Vec<GiftCard> = self.cards.values().cloned().collect();
+    persistence::save(path, &cards)
+  }
+
+  pub fn load(path: &Path) -> io::Result<GiftCards> {
+    let cards = persistence::load::<GiftCard>(path)?;
+    // carry on after the highest number on file
+    let last_number = cards
+      .iter()
+      .filter_map(|card| card.number.strip_prefix("GC")?.parse().ok())
+      .max()
+      .unwrap_or(0);
+    Ok(GiftCards {
+      cards: cards.into_iter().map(|card| (card.number.clone(), card)).collect(),
+      last_number,
+    })
+  }
+
+  fn card_mut(&mut self, number: &str) -> Result<&mut GiftCard, RestaurantError> {
+    self.cards
+      .get_mut(number)
+      .ok_or_else(|| RestaurantError::GiftCardNotFound(number.to_string()))
+  }
+}
+
+// a regular who runs a tab and pays it off every month
+#[derive(Debug, Clone, PartialEq)]
+pub struct HouseAccount {
+  pub id: String,
+  pub name: String,
+  pub limit: u32, // the most they can owe at once, in cents
+  pub paid: u32,  // everything they've paid off so far
+}
+
+impl Record for HouseAccount {
+  fn to_fields(&self) -> Vec<String> {
+    vec![self.id.clone(), self.name.clone(), self.limit.to_string(), self.paid.to_string()]
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<HouseAccount> {
+    match fields {
+      [id, name, limit, paid] => Some(HouseAccount {
+        id: id.to_string(),
+        name: name.to_string(),
+        limit: limit.parse().ok()?,
+        paid: paid.parse().ok()?,
+      }),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Charge {
+  pub account: String,
+  pub order_id: u32,
+  pub amount: u32,
+  pub period: String, // the statement month, like "2026-10"
+}
+
+impl Record for Charge {
+  fn to_fields(&self) -> Vec<String> {
+    vec![
+      self.account.clone(),
+      self.order_id.to_string(),
+      self.amount.to_string(),
+      self.period.clone(),
+    ]
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<Charge> {
+    match fields {
+      [account, order_id, amount, period] => Some(Charge {
+        account: account.to_string(),
+        order_id: order_id.parse().ok()?,
+        amount: amount.parse().ok()?,
+        period: period.to_string(),
+      }),
+      _ => None,
+    }
+  }
+}
+
+// what we mail to the account holder at the end of the month
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+  pub account: String,
+  pub name: String,
+  pub period: String,
+  pub charges: Vec<Charge>,
+  pub period_total: u32,
+  pub balance_due: u32, // everything still owed, including earlier months
+}
+
+#[derive(Debug, Default)]
+pub struct HouseAccounts {
+  accounts: BTreeMap<String, HouseAccount>,
+  charges: Vec<Charge>,
+  period: String,
+}
+
+const ACCOUNTS_FILE: &str = "house_accounts.tsv";
+const CHARGES_FILE: &str = "house_charges.tsv";
+
+impl HouseAccounts {
+  pub fn new(period: &str) -> HouseAccounts {
+    HouseAccounts {
+      period: String::from(period),
+      ..HouseAccounts::default()
+    }
+  }
+
+  // new charges land on this month's statement
+  pub fn start_period(&mut self, period: &str) {
+    self.period = String::from(period);
+  }
+
+  pub fn open_account(&mut self, id: &str, name: &str, limit: u32) -> Result<(), RestaurantError> {
+    if self.accounts.contains_key(id) {
+      return Err(RestaurantError::AccountExists(id.to_string()));
+    }
+    let account = HouseAccount {
+      id: String::from(id),
+      name: String::from(name),
+      limit,
+      paid: 0,
+    };
+    self.accounts.insert(account.id.clone(), account);
+    Ok(())
+  }
+
+  pub fn balance(&self, id: &str) -> Result<u32, RestaurantError> {
+    let account = self.account(id)?;
+    let charged: u32 = self.charges.iter().filter(|charge| charge.account == id).map(|charge| charge.amount).sum();
+    Ok(charged - account.paid)
+  }
+
+  pub fn charge(&mut self, id: &str, order_id: u32, amount: u32) -> Result<(), RestaurantError> {
+    let limit = self.account(id)?.limit;
+    // a charge too big to add up is over any limit
+    if self.balance(id)?.checked_add(amount).is_none_or(|owed| owed > limit) {
+      return Err(RestaurantError::CreditLimitExceeded(id.to_string()));
+    }
+    self.charges.push(Charge {
+      account: String::from(id),
+      order_id,
+      amount,
+      period: self.period.clone(),
+    });
+    Ok(())
+  }
+
+  // the account holder paid (some of) their bill
+  pub fn settle(&mut self, id: &str, amount: u32) -> Result<u32, RestaurantError> {
+    let owed = self.balance(id)?;
+    if amount > owed {
+      return Err(RestaurantError::InsufficientBalance(id.to_string(), owed));
+    }
+    self.accounts.get_mut(id).expect("account was checked above").paid += amount;
+    Ok(owed - amount)
+  }
+
+  pub fn statement(&self, id: &str, period: &str) -> Result<Statement, RestaurantError> {
+    let account = self.account(id)?;
+    let charges: Vec<Charge> = self
+      .charges
+      .iter()
+      .filter(|charge| charge.account == id && charge.period == period)
+      .cloned()
+      .collect();
+    Ok(Statement {
+      account: account.id.clone(),
+      name: account.name.clone(),
+      period: String::from(period),
+      period_total: charges.iter().map(|charge| charge.amount).sum(),
+      charges,
+      balance_due: self.balance(id)?,
+    })
+  }
+
+  pub fn save(&self, dir: &Path) -> io::Result<()> {
+    let accounts: Vec<HouseAccount> = self.accounts.values().cloned().collect();
+    persistence::save(&dir.join(ACCOUNTS_FILE), &accounts)?;
+    persistence::save(&dir.join(CHARGES_FILE), &self.charges)
+  }
+
+  pub fn load(dir: &Path, period: &str) -> io::Result<HouseAccounts> {
+    let accounts = persistence::load::<HouseAccount>(&dir.join(ACCOUNTS_FILE))?;
+    Ok(HouseAccounts {
+      accounts: accounts.into_iter().map(|account| (account.id.clone(), account)).collect(),
+      charges: persistence::load(&dir.join(CHARGES_FILE))?,
+      period: String::from(period),
+    })
+  }
+
+  fn account(&self, id: &str) -> Result<&HouseAccount, RestaurantError> {
+    self.accounts.get(id).ok_or_else(|| RestaurantError::AccountNotFound(id.to_string()))
+  }
+}
@@ -0,0 +1,138 @@
+// who moved which check where, so the manager can sort out a wrong tip-out
+// or a missing order after the shift
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::persistence::{self, Record};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+  TransferOrder { order: u32, from: u32, to: u32 }, // tables
+  MergeChecks { from: u32, into: u32 },             // orders
+  SplitCheck { order: u32, new_order: u32, lines: usize },
+  TransferTable { table: u32, from: Option<String>, to: String }, // servers
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+  pub id: u32,
+  pub staff: String, // who did it
+  pub action: Action,
+}
+
+impl fmt::Display for AuditEntry {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "#{} {}: ", self.id, self.staff)?;
+    match &self.action {
+      Action::TransferOrder { order, from, to } => {
+        write!(f, "moved order {} from table {} to table {}", order, from, to)
+      }
+      Action::MergeChecks { from, into } => write!(f, "merged order {} into order {}", from, into),
+      Action::SplitCheck { order, new_order, lines } => {
+        write!(f, "split {} lines off order {} onto order {}", lines, order, new_order)
+      }
+      Action::TransferTable { table, from, to } => match from {
+        Some(from) => write!(f, "handed table {} from {} to {}", table, from, to),
+        None => write!(f, "gave table {} to {}", table, to),
+      },
+    }
+  }
+}
+
+impl Record for AuditEntry {
+  fn to_fields(&self) -> Vec<String> {
+    let mut fields = vec![self.id.to_string(), self.staff.clone()];
+    match &self.action {
+      Action::TransferOrder { order, from, to } => {
+        fields.extend(["transfer order".to_string(), order.to_string(), from.to_string(), to.to_string()])
+      }
+      Action::MergeChecks { from, into } => fields.extend(["merge".to_string(), from.to_string(), into.to_string()]),
+      Action::SplitCheck { order, new_order, lines } => {
+        fields.extend(["split".to_string(), order.to_string(), new_order.to_string(), lines.to_string()])
+      }
+      Action::TransferTable { table, from, to } => fields.extend([
+        "transfer table".to_string(),
+        table.to_string(),
+        from.clone().unwrap_or_default(),
+        to.clone(),
+      ]),
+    }
+    fields
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<AuditEntry> {
+    let (id, staff, action) = match fields {
+      [id, staff, action @ ..] => (id.parse().ok()?, staff.to_string(), action),
+      _ => return None,
+    };
+    let action = match action {
+      ["transfer order", order, from, to] => Action::TransferOrder {
+        order: order.parse().ok()?,
+        from: from.parse().ok()?,
+        to: to.parse().ok()?,
+      },
+      ["merge", from, into] => Action::MergeChecks {
+        from: from.parse().ok()?,
+        into: into.parse().ok()?,
+      },
+      ["split", order, new_order, lines] => Action::SplitCheck {
+        order: order.parse().ok()?,
+        new_order: new_order.parse().ok()?,
+        lines: lines.parse().ok()?,
+      },
+      ["transfer table", table, from, to] => Action::TransferTable {
+        table: table.parse().ok()?,
+        from: Some(from.to_string()).filter(|from| !from.is_empty()),
+        to: to.to_string(),
+      },
+      _ => return None,
+    };
+    Some(AuditEntry { id, staff, action })
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct AuditTrail {
+  entries: Vec<AuditEntry>,
+}
+
+impl AuditTrail {
+  pub fn new() -> AuditTrail {
+    AuditTrail::default()
+  }
+
+  pub fn record(&mut self, staff: &str, action: Action) -> u32 {
+    let id = self.entries.len() as u32 + 1;
+    self.entries.push(AuditEntry {
+      id,
+      staff: String::from(staff),
+      action,
+    });
+    id
+  }
+
+  pub fn entries(&self) -> &[AuditEntry] {
+    &self.entries
+  }
+
+  // everything that touched one order
+  pub fn for_order(&self, id: u32) -> impl Iterator<Item = &AuditEntry> {
+    self.entries.iter().filter(move |entry| match entry.action {
+      Action::TransferOrder { order, .. } => order == id,
+      Action::MergeChecks { from, into } => from == id || into == id,
+      Action::SplitCheck { order, new_order, .. } => order == id || new_order == id,
+      Action::TransferTable { .. } => false,
+    })
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    persistence::save(path, &self.entries)
+  }
+
+  pub fn load(path: &Path) -> io::Result<AuditTrail> {
+    Ok(AuditTrail {
+      entries: persistence::load(path)?,
+    })
+  }
+}
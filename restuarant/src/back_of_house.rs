@@ -1,10 +1,251 @@
-fn fix_incorrect_order() {
-  cook_order();
-  super::deliver_order();
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+pub mod costing;
+pub mod forecast;
+pub mod inventory;
+pub mod stations;
+pub mod waste;
+
+use self::stations::{ExpoLine, Routing, StationQueue, StationTicket};
+
+use crate::error::RestaurantError;
+use crate::front_of_house::serving::{Course, Order, OrderLine, OrderStatus, Orders};
+use crate::menu::Allergen;
+
+// the ticket for remaking the course that just went out, without sending it
+pub fn remake_ticket(order: &Order) -> Result<Ticket, RestaurantError> {
+  match order.fired.last() {
+    Some(course) if order.status == OrderStatus::Delivered => Ok(order.ticket(*course)),
+    _ => Err(RestaurantError::OrderNotDelivered(order.id)),
+  }
+}
+
+// send the course that just went out back to the kitchen to be cooked again,
+// returns the remake ticket
+pub fn fix_incorrect_order(orders: &mut Orders, kitchen: &mut Kitchen, order_id: u32) -> Result<Ticket, RestaurantError> {
+  let order = orders.get_mut(order_id)?;
+  let ticket = remake_ticket(order)?;
+  order.status = OrderStatus::Sent;
+  order.remakes += 1;
+  kitchen.send(ticket.clone());
+  Ok(ticket)
+}
+
+// the kitchen finished the oldest ticket, mark its order ready to deliver
+pub fn cook_order(orders: &mut Orders, kitchen: &mut Kitchen) -> Option<Ticket> {
+  let ticket = kitchen.bump()?;
+  mark_ready(orders, &ticket);
+  Some(ticket)
 }
 
-fn cook_order() {}
+// the expediter runs the oldest ticket every station has finished
+pub fn expedite(orders: &mut Orders, kitchen: &mut Kitchen) -> Option<Ticket> {
+  let ticket = kitchen.bump_ready()?;
+  mark_ready(orders, &ticket);
+  Some(ticket)
+}
+
+fn mark_ready(orders: &mut Orders, ticket: &Ticket) {
+  if let Ok(order) = orders.get_mut(ticket.order_id) {
+    order.status = OrderStatus::Ready;
+  }
+}
+
+// where in the kitchen a dish gets cooked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Station {
+  Line,
+  Grill,
+  Fryer,
+  Saute,
+  Pantry,
+  Pastry,
+}
+
+// what the kitchen sees for one course of an order
+#[derive(Debug, Clone)]
+pub struct Ticket {
+  pub order_id: u32,
+  pub table: u32,
+  pub course: Course,
+  pub lines: Vec<OrderLine>,
+}
+
+// when one dish should go on, in minutes after the course is fired
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartTime {
+  pub item: String,
+  pub station: Station,
+  pub start_at: u32,
+  pub ready_at: u32,
+}
+
+// stagger the starts so every dish on the ticket is ready at the same time:
+// the slowest dish starts right away and the quicker ones wait for it
+pub fn schedule_ticket(ticket: &Ticket, fired_at: u32) -> Vec<StartTime> {
+  let ready_at = fired_at + ticket.lines.iter().map(|line| line.prep_minutes()).max().unwrap_or(0);
+  let mut starts: Vec<StartTime> = ticket
+    .lines
+    .iter()
+    .map(|line| StartTime {
+      item: line.item(),
+      station: line.station(),
+      start_at: ready_at - line.prep_minutes(),
+      ready_at,
+    })
+    .collect();
+  starts.sort_by_key(|start| start.start_at);
+  starts
+}
+
+// a rough plan for the whole order if the server fires each course as soon as
+// the last one is eaten, `eating_minutes` after it comes out of the kitchen
+pub fn pace_order(order: &Order, fired_at: u32, eating_minutes: u32) -> Vec<(Course, Vec<StartTime>)> {
+  let mut fire_at = fired_at;
+  let mut plan = Vec::new();
+  for course in order.courses() {
+    let starts = schedule_ticket(&order.ticket(course), fire_at);
+    fire_at = starts.first().map_or(fire_at, |start| start.ready_at) + eating_minutes;
+    plan.push((course, starts));
+  }
+  plan
+}
+
+// every ticket goes on the expediter's rail in the order it came in, and
+// each station gets its part of it on its own screen
+//
+// a kitchen without station screens just bumps from the rail
+#[derive(Debug, Default)]
+pub struct Kitchen {
+  queue: VecDeque<Ticket>,
+  stations: HashMap<Station, StationQueue>,
+  routing: Routing,
+  now: u32, // minutes into service
+}
+
+impl Kitchen {
+  pub fn new() -> Kitchen {
+    Kitchen::default()
+  }
+
+  pub fn with_routing(mut self, routing: Routing) -> Kitchen {
+    self.routing = routing;
+    self
+  }
+
+  // how many tickets a station can work on at once
+  pub fn capacity(&mut self, station: Station, capacity: usize) {
+    self.station_mut(station).capacity = capacity;
+  }
+
+  // the kitchen clock, moved on by whoever runs the service
+  pub fn set_time(&mut self, minutes: u32) {
+    self.now = minutes;
+  }
+
+  pub fn time(&self) -> u32 {
+    self.now
+  }
+
+  pub fn send(&mut self, ticket: Ticket) {
+    for (station, part) in stations::split_ticket(&ticket, &self.routing, &self.stations, self.now) {
+      self.station_mut(station).push(part);
+    }
+    self.queue.push_back(ticket);
+  }
+
+  // the expediter sends the oldest ticket out, ready or not
+  pub fn bump(&mut self) -> Option<Ticket> {
+    let ticket = self.queue.pop_front()?;
+    self.finish(&ticket);
+    Some(ticket)
+  }
+
+  // a station finished its oldest ticket
+  pub fn bump_station(&mut self, station: Station) -> Option<StationTicket> {
+    let now = self.now;
+    self.stations.get_mut(&station)?.bump(now)
+  }
+
+  // the oldest ticket every station has finished its part of
+  pub fn bump_ready(&mut self) -> Option<Ticket> {
+    let index = self.expo().iter().position(|line| line.is_ready())?;
+    let ticket = self.queue.remove(index)?;
+    self.finish(&ticket);
+    Some(ticket)
+  }
+
+  // the order moved tables, so the food has to go somewhere else
+  pub(crate) fn move_order(&mut self, order_id: u32, table: u32) {
+    for ticket in self.queue.iter_mut().filter(|ticket| ticket.order_id == order_id) {
+      ticket.table = table;
+    }
+    for queue in self.stations.values_mut() {
+      queue.move_order(order_id, table);
+    }
+  }
+
+  fn finish(&mut self, ticket: &Ticket) {
+    let now = self.now;
+    for queue in self.stations.values_mut() {
+      queue.finish_order(ticket.order_id, ticket.course, now);
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.queue.len()
+  }
 
+  pub fn is_empty(&self) -> bool {
+    self.queue.is_empty()
+  }
+
+  pub fn tickets(&self) -> impl Iterator<Item = &Ticket> {
+    self.queue.iter()
+  }
+
+  pub fn station(&self, station: Station) -> Option<&StationQueue> {
+    self.stations.get(&station)
+  }
+
+  fn station_mut(&mut self, station: Station) -> &mut StationQueue {
+    self.stations
+      .entry(station)
+      .or_insert_with(|| StationQueue::new(station, stations::DEFAULT_CAPACITY))
+  }
+
+  // the rail, oldest first, with what each ticket is still waiting on
+  pub fn expo(&self) -> Vec<ExpoLine> {
+    self.queue
+      .iter()
+      .map(|ticket| {
+        let mut waiting_on: Vec<Station> = self
+          .stations
+          .values()
+          .filter(|queue| queue.has(ticket.order_id, ticket.course))
+          .map(|queue| queue.station)
+          .collect();
+        waiting_on.sort();
+        ExpoLine {
+          order_id: ticket.order_id,
+          table: ticket.table,
+          course: ticket.course,
+          waiting_on,
+        }
+      })
+      .collect()
+  }
+
+  // minutes from a ticket reaching a station to the station bumping it
+  pub fn average_ticket_times(&self) -> BTreeMap<Station, f64> {
+    self.stations
+      .values()
+      .filter_map(|queue| Some((queue.station, queue.average_ticket_time()?)))
+      .collect()
+  }
+}
+
+#[derive(Debug, Clone)]
 pub struct Breakfast {
   pub toast: String,
   seasonal_fruit: String,
@@ -19,7 +260,43 @@ impl Breakfast {
   }
 }
 
+// every breakfast is the same dish to the recipes and reports, the toast and
+// fruit are only for the kitchen and the guest
+menu_item!(Breakfast, |breakfast| {
+  name: "Breakfast",
+  price: 1200,
+  station: Station::Grill,
+  prep_minutes: 12,
+  allergens: [Allergen::Gluten, Allergen::Egg],
+  modifiers: [("extra bacon", 300), ("no butter", 0)],
+  details: format!("{} toast and {}", breakfast.toast, breakfast.seasonal_fruit),
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Appetizer {
   Soup, 
   Salad,
-}
\ No newline at end of file
+}
+
+// appetizers always go out first
+menu_item!(Appetizer, |appetizer| {
+  name: match appetizer {
+    Appetizer::Soup => "Soup",
+    Appetizer::Salad => "Salad",
+  },
+  price: match appetizer {
+    Appetizer::Soup => 650,
+    Appetizer::Salad => 900,
+  },
+  station: match appetizer {
+    Appetizer::Soup => Station::Saute,
+    Appetizer::Salad => Station::Pantry,
+  },
+  prep_minutes: match appetizer {
+    Appetizer::Soup => 6,
+    Appetizer::Salad => 4,
+  },
+  course: Course::Appetizer,
+  allergens: [Allergen::Dairy],
+  modifiers: [("extra bread", 150), ("dressing on the side", 0)],
+});
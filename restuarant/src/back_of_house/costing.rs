@@ -0,0 +1,241 @@
+// what a plate costs us to make, and how that compares with what we charge
+use std::collections::BTreeMap;
+
+use super::inventory::{Counts, Unit};
+use crate::error::RestaurantError;
+use crate::front_of_house::serving::Orders;
+
+// how we buy an ingredient, like a 25000 g sack of flour for 1850 cents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ingredient {
+  pub name: String,
+  pub unit: Unit,
+  pub pack_size: f64,
+  pub pack_cost: u32, // in cents
+}
+
+impl Ingredient {
+  pub fn new(name: &str, unit: Unit, pack_size: f64, pack_cost: u32) -> Ingredient {
+    Ingredient {
+      name: String::from(name),
+      unit,
+      pack_size,
+      pack_cost,
+    }
+  }
+
+  // cents for one gram, millilitre or piece
+  pub fn unit_cost(&self) -> f64 {
+    self.pack_cost as f64 / self.pack_size
+  }
+}
+
+// how much of each ingredient goes on one plate, keyed by the dish name
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+  pub item: String,
+  pub ingredients: Vec<(String, f64)>,
+}
+
+impl Recipe {
+  pub fn new(item: &str) -> Recipe {
+    Recipe {
+      item: String::from(item),
+      ingredients: Vec::new(),
+    }
+  }
+
+  pub fn uses(mut self, ingredient: &str, quantity: f64) -> Recipe {
+    self.ingredients.push((String::from(ingredient), quantity));
+    self
+  }
+}
+
+// one row of the menu costing report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemCost {
+  pub item: String,
+  pub price: u32,
+  pub plate_cost: f64, // in cents
+}
+
+impl ItemCost {
+  pub fn food_cost_percent(&self) -> f64 {
+    if self.price == 0 {
+      return 100.0;
+    }
+    self.plate_cost / self.price as f64 * 100.0
+  }
+
+  pub fn margin_percent(&self) -> f64 {
+    100.0 - self.food_cost_percent()
+  }
+}
+
+// food cost for a stretch of service, what it should have been against what it was
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoodCostReport {
+  pub revenue: u32,
+  pub theoretical_cost: f64, // what the recipes say the sales used
+  pub actual_cost: f64,      // what actually left the shelves
+}
+
+impl FoodCostReport {
+  pub fn theoretical_percent(&self) -> f64 {
+    percent_of(self.theoretical_cost, self.revenue)
+  }
+
+  pub fn actual_percent(&self) -> f64 {
+    percent_of(self.actual_cost, self.revenue)
+  }
+
+  // cost that isn't explained by sales: waste, over-portioning, theft
+  pub fn variance(&self) -> f64 {
+    self.actual_cost - self.theoretical_cost
+  }
+}
+
+fn percent_of(cost: f64, revenue: u32) -> f64 {
+  if revenue == 0 {
+    return 0.0;
+  }
+  cost / revenue as f64 * 100.0
+}
+
+// items sold and the money they brought in, by dish name. a check only
+// counts once it's paid off, open and walked-out checks aren't sales
+pub fn sales(orders: &Orders) -> BTreeMap<String, (u32, u32)> {
+  let mut sales: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+  for line in orders.iter().filter(|order| order.is_paid()).flat_map(|order| order.lines.iter()) {
+    let entry = sales.entry(line.dish.name()).or_insert((0, 0));
+    entry.0 += 1;
+    entry.1 += line.price();
+  }
+  sales
+}
+
+#[derive(Debug, Default)]
+pub struct CostBook {
+  ingredients: BTreeMap<String, Ingredient>,
+  recipes: BTreeMap<String, Recipe>,
+}
+
+impl CostBook {
+  pub fn new() -> CostBook {
+    CostBook::default()
+  }
+
+  // a new price replaces the old one
+  pub fn add_ingredient(&mut self, ingredient: Ingredient) {
+    self.ingredients.insert(ingredient.name.clone(), ingredient);
+  }
+
+  pub fn add_recipe(&mut self, recipe: Recipe) -> Result<(), RestaurantError> {
+    if let Some((missing, _)) = recipe.ingredients.iter().find(|(name, _)| !self.ingredients.contains_key(name)) {
+      return Err(RestaurantError::UnknownIngredient(missing.clone()));
+    }
+    self.recipes.insert(recipe.item.clone(), recipe);
+    Ok(())
+  }
+
+  pub fn ingredient(&self, name: &str) -> Result<&Ingredient, RestaurantError> {
+    self.ingredients.get(name).ok_or_else(|| RestaurantError::UnknownIngredient(name.to_string()))
+  }
+
+  pub fn recipe(&self, item: &str) -> Result<&Recipe, RestaurantError> {
+    self.recipes.get(item).ok_or_else(|| RestaurantError::NoRecipe(item.to_string()))
+  }
+
+  // in cents
+  pub fn plate_cost(&self, item: &str) -> Result<f64, RestaurantError> {
+    let mut cost = 0.0;
+    for (ingredient, quantity) in &self.recipe(item)?.ingredients {
+      cost += self.ingredient(ingredient)?.unit_cost() * quantity;
+    }
+    Ok(cost)
+  }
+
+  pub fn item_cost(&self, item: &str, price: u32) -> Result<ItemCost, RestaurantError> {
+    Ok(ItemCost {
+      item: String::from(item),
+      price,
+      plate_cost: self.plate_cost(item)?,
+    })
+  }
+
+  // how much of each ingredient these sales should have used
+  pub fn theoretical_usage(&self, sold: &BTreeMap<String, (u32, u32)>) -> Result<Counts, RestaurantError> {
+    let mut usage = Counts::new();
+    for (item, (count, _)) in sold {
+      for (ingredient, quantity) in &self.recipe(item)?.ingredients {
+        *usage.entry(ingredient.clone()).or_insert(0.0) += quantity * *count as f64;
+      }
+    }
+    Ok(usage)
+  }
+
+  // cost of usage at today's prices
+  pub fn cost_of(&self, usage: &Counts) -> Result<f64, RestaurantError> {
+    let mut cost = 0.0;
+    for (ingredient, quantity) in usage {
+      cost += self.ingredient(ingredient)?.unit_cost() * quantity;
+    }
+    Ok(cost)
+  }
+
+  // actual usage is what we started with, plus deliveries, minus what's left
+  pub fn food_cost(
+    &self,
+    orders: &Orders,
+    opening: &Counts,
+    purchases: &Counts,
+    closing: &Counts,
+  ) -> Result<FoodCostReport, RestaurantError> {
+    let sold = sales(orders);
+    let mut actual = opening.clone();
+    for (ingredient, quantity) in purchases {
+      *actual.entry(ingredient.clone()).or_insert(0.0) += quantity;
+    }
+    for (ingredient, quantity) in closing {
+      *actual.entry(ingredient.clone()).or_insert(0.0) -= quantity;
+    }
+    Ok(FoodCostReport {
+      revenue: sold.values().map(|(_, revenue)| revenue).sum(),
+      theoretical_cost: self.cost_of(&self.theoretical_usage(&sold)?)?,
+      actual_cost: self.cost_of(&actual)?,
+    })
+  }
+}
+
+// items whose margin fell under the threshold, worst first
+pub fn below_margin(costs: &[ItemCost], minimum_margin_percent: f64) -> Vec<&ItemCost> {
+  let mut flagged: Vec<&ItemCost> = costs.iter().filter(|cost| cost.margin_percent() < minimum_margin_percent).collect();
+  flagged.sort_by(|a, b| a.margin_percent().total_cmp(&b.margin_percent()));
+  flagged
+}
+
+// the costing report as CSV for the accountant, money in dollars
+pub fn costs_csv(costs: &[ItemCost], minimum_margin_percent: f64) -> String {
+  let mut csv = String::from("item,price,plate_cost,food_cost_percent,margin_percent,below_margin\n");
+  for cost in costs {
+    csv.push_str(&format!(
+      "{},{:.2},{:.2},{:.1},{:.1},{}\n",
+      csv_field(&cost.item),
+      cost.price as f64 / 100.0,
+      cost.plate_cost / 100.0,
+      cost.food_cost_percent(),
+      cost.margin_percent(),
+      cost.margin_percent() < minimum_margin_percent,
+    ));
+  }
+  csv
+}
+
+// quote fields with commas or quotes in them
+pub fn csv_field(field: &str) -> String {
+  if field.contains(',') || field.contains('"') || field.contains('\n') {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
@@ -0,0 +1,315 @@
+// guessing how much of each dish we'll sell today from how much we sold on
+// the same weekday before, and turning that into a prep list
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use super::costing::{self, CostBook};
+use super::inventory::{Counts, Inventory};
+use crate::error::RestaurantError;
+use crate::front_of_house::serving::Orders;
+use crate::persistence::{self, Record};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+  Monday,
+  Tuesday,
+  Wednesday,
+  Thursday,
+  Friday,
+  Saturday,
+  Sunday,
+}
+
+impl Weekday {
+  pub const ALL: [Weekday; 7] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+    Weekday::Sunday,
+  ];
+
+  // Monday is 0
+  pub fn index(self) -> u32 {
+    Weekday::ALL.iter().position(|day| *day == self).unwrap_or(0) as u32
+  }
+
+  pub(crate) fn parse(text: &str) -> Option<Weekday> {
+    Weekday::ALL.into_iter().find(|day| format!("{:?}", day) == text)
+  }
+}
+
+// how many of one item sold in one hour of one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct SalesRecord {
+  pub day: u32, // days since we started keeping records
+  pub weekday: Weekday,
+  pub hour: u32,
+  pub item: String,
+  pub quantity: u32,
+}
+
+impl Record for SalesRecord {
+  fn to_fields(&self) -> Vec<String> {
+    vec![
+      self.day.to_string(),
+      format!("{:?}", self.weekday),
+      self.hour.to_string(),
+      self.item.clone(),
+      self.quantity.to_string(),
+    ]
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<SalesRecord> {
+    match fields {
+      [day, weekday, hour, item, quantity] => Some(SalesRecord {
+        day: day.parse().ok()?,
+        weekday: Weekday::parse(weekday)?,
+        hour: hour.parse().ok()?,
+        item: item.to_string(),
+        quantity: quantity.parse().ok()?,
+      }),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+  // the average of the last this-many same weekdays
+  MovingAverage(usize),
+  // every new week counts this much (more than 0, up to 1), older weeks
+  // fade away
+  ExponentialSmoothing(f64),
+}
+
+impl Method {
+  // with no weight the first week would count forever, and past 1 the
+  // forecast overshoots every change and swings back
+  fn check(&self) -> Result<(), RestaurantError> {
+    match *self {
+      Method::ExponentialSmoothing(alpha) if !(alpha > 0.0 && alpha <= 1.0) => {
+        Err(RestaurantError::InvalidSmoothing(alpha.to_string()))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  // oldest first
+  fn predict(&self, history: &[f64]) -> f64 {
+    if history.is_empty() {
+      return 0.0;
+    }
+    match *self {
+      Method::MovingAverage(weeks) => {
+        let recent = &history[history.len().saturating_sub(weeks.max(1))..];
+        recent.iter().sum::<f64>() / recent.len() as f64
+      }
+      Method::ExponentialSmoothing(alpha) => history[1..]
+        .iter()
+        .fold(history[0], |level, actual| alpha * actual + (1.0 - alpha) * level),
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct SalesHistory {
+  records: Vec<SalesRecord>,
+}
+
+impl SalesHistory {
+  pub fn new() -> SalesHistory {
+    SalesHistory::default()
+  }
+
+  pub fn record(&mut self, day: u32, weekday: Weekday, hour: u32, item: &str, quantity: u32) {
+    self.records.push(SalesRecord {
+      day,
+      weekday,
+      hour,
+      item: String::from(item),
+      quantity,
+    });
+  }
+
+  // everything on these orders, logged against one hour
+  pub fn record_orders(&mut self, day: u32, weekday: Weekday, hour: u32, orders: &Orders) {
+    for (item, (quantity, _)) in costing::sales(orders) {
+      self.record(day, weekday, hour, &item, quantity);
+    }
+  }
+
+  pub fn records(&self) -> &[SalesRecord] {
+    &self.records
+  }
+
+  // the total for every past day that matches, oldest day first
+  fn daily_totals(&self, weekday: Weekday, matches: impl Fn(&SalesRecord) -> bool) -> Vec<f64> {
+    let mut totals: BTreeMap<u32, f64> = BTreeMap::new();
+    for record in self.records.iter().filter(|record| record.weekday == weekday) {
+      let total = totals.entry(record.day).or_insert(0.0);
+      if matches(record) {
+        *total += record.quantity as f64;
+      }
+    }
+    totals.into_values().collect()
+  }
+
+  fn items(&self) -> Vec<String> {
+    let mut items: Vec<String> = self.records.iter().map(|record| record.item.clone()).collect();
+    items.sort();
+    items.dedup();
+    items
+  }
+
+  // how many of each item we expect to sell on this weekday
+  pub fn forecast_day(&self, weekday: Weekday, method: Method) -> Result<BTreeMap<String, f64>, RestaurantError> {
+    method.check()?;
+    Ok(self
+      .items()
+      .into_iter()
+      .map(|item| {
+        let history = self.daily_totals(weekday, |record| record.item == item);
+        (item, method.predict(&history))
+      })
+      .collect())
+  }
+
+  // the same forecast split by hour, keyed by (hour, item)
+  pub fn forecast_hours(
+    &self,
+    weekday: Weekday,
+    method: Method,
+  ) -> Result<BTreeMap<(u32, String), f64>, RestaurantError> {
+    method.check()?;
+    let mut hours: Vec<(u32, String)> = self
+      .records
+      .iter()
+      .filter(|record| record.weekday == weekday)
+      .map(|record| (record.hour, record.item.clone()))
+      .collect();
+    hours.sort();
+    hours.dedup();
+    Ok(hours
+      .into_iter()
+      .map(|(hour, item)| {
+        let history = self.daily_totals(weekday, |record| record.hour == hour && record.item == item);
+        let forecast = method.predict(&history);
+        ((hour, item), forecast)
+      })
+      .collect())
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    persistence::save(path, &self.records)
+  }
+
+  pub fn load(path: &Path) -> io::Result<SalesHistory> {
+    Ok(SalesHistory {
+      records: persistence::load(path)?,
+    })
+  }
+}
+
+// one line of the morning prep list
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrepItem {
+  pub ingredient: String,
+  pub needed: f64,
+  pub on_hand: f64,
+  pub to_prep: f64,
+}
+
+// what the forecast needs from every ingredient, minus what's already on hand
+pub fn prep_list(
+  forecast: &BTreeMap<String, f64>,
+  cost_book: &CostBook,
+  inventory: &Inventory,
+) -> Result<Vec<PrepItem>, RestaurantError> {
+  let mut needed = Counts::new();
+  for (item, quantity) in forecast {
+    for (ingredient, amount) in &cost_book.recipe(item)?.ingredients {
+      *needed.entry(ingredient.clone()).or_insert(0.0) += amount * quantity.ceil();
+    }
+  }
+  Ok(needed
+    .into_iter()
+    .map(|(ingredient, needed)| {
+      let on_hand = inventory.on_hand(&ingredient);
+      PrepItem {
+        to_prep: (needed - on_hand).max(0.0),
+        ingredient,
+        needed,
+        on_hand,
+      }
+    })
+    .collect())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastError {
+  pub day: u32,
+  pub item: String,
+  pub forecast: f64,
+  pub actual: f64,
+}
+
+// how far off the forecasts were, kept day by day so we can tell whether
+// they are getting better
+#[derive(Debug, Default)]
+pub struct ForecastLog {
+  entries: Vec<ForecastError>,
+}
+
+impl ForecastLog {
+  pub fn new() -> ForecastLog {
+    ForecastLog::default()
+  }
+
+  pub fn record(&mut self, day: u32, item: &str, forecast: f64, actual: f64) {
+    self.entries.push(ForecastError {
+      day,
+      item: String::from(item),
+      forecast,
+      actual,
+    });
+  }
+
+  // compare yesterday's forecast with what actually sold
+  pub fn record_day(&mut self, day: u32, forecast: &BTreeMap<String, f64>, history: &SalesHistory) {
+    for (item, predicted) in forecast {
+      let actual: u32 = history
+        .records()
+        .iter()
+        .filter(|record| record.day == day && &record.item == item)
+        .map(|record| record.quantity)
+        .sum();
+      self.record(day, item, *predicted, actual as f64);
+    }
+  }
+
+  pub fn entries(&self) -> &[ForecastError] {
+    &self.entries
+  }
+
+  // average miss in plates, either direction
+  pub fn mean_absolute_error(&self, item: &str) -> Option<f64> {
+    self.average(item, |entry| (entry.forecast - entry.actual).abs())
+  }
+
+  // positive when we keep making too much
+  pub fn bias(&self, item: &str) -> Option<f64> {
+    self.average(item, |entry| entry.forecast - entry.actual)
+  }
+
+  fn average(&self, item: &str, measure: impl Fn(&ForecastError) -> f64) -> Option<f64> {
+    let errors: Vec<f64> = self.entries.iter().filter(|entry| entry.item == item).map(measure).collect();
+    if errors.is_empty() {
+      return None;
+    }
+    Some(errors.iter().sum::<f64>() / errors.len() as f64)
+  }
+}
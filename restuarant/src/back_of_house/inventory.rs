@@ -0,0 +1,60 @@
+// what's on the shelves, in each ingredient's own unit
+use std::collections::BTreeMap;
+
+use crate::error::RestaurantError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+  Gram,
+  Millilitre,
+  Each,
+}
+
+// a snapshot of how much of every ingredient there is
+pub type Counts = BTreeMap<String, f64>;
+
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+  on_hand: Counts,
+}
+
+impl Inventory {
+  pub fn new() -> Inventory {
+    Inventory::default()
+  }
+
+  pub fn on_hand(&self, ingredient: &str) -> f64 {
+    self.on_hand.get(ingredient).copied().unwrap_or(0.0)
+  }
+
+  pub fn counts(&self) -> Counts {
+    self.on_hand.clone()
+  }
+
+  // a delivery came in, an empty or negative one is a typo on the invoice
+  pub fn receive(&mut self, ingredient: &str, quantity: f64) -> Result<(), RestaurantError> {
+    if !(quantity > 0.0 && quantity.is_finite()) {
+      return Err(RestaurantError::InvalidQuantity(quantity.to_string()));
+    }
+    *self.on_hand.entry(ingredient.to_string()).or_insert(0.0) += quantity;
+    Ok(())
+  }
+
+  // stock never goes below zero, using more than we have is a mistake somewhere
+  pub fn deduct(&mut self, ingredient: &str, quantity: f64) -> Result<(), RestaurantError> {
+    if !(quantity > 0.0 && quantity.is_finite()) {
+      return Err(RestaurantError::InvalidQuantity(quantity.to_string()));
+    }
+    let on_hand = self.on_hand(ingredient);
+    if quantity > on_hand {
+      return Err(RestaurantError::NotEnoughStock(ingredient.to_string()));
+    }
+    self.on_hand.insert(ingredient.to_string(), on_hand - quantity);
+    Ok(())
+  }
+
+  // a physical count replaces what we thought we had
+  pub fn set_count(&mut self, ingredient: &str, quantity: f64) {
+    self.on_hand.insert(ingredient.to_string(), quantity.max(0.0));
+  }
+}
@@ -0,0 +1,193 @@
+// splitting tickets up between the stations: which station cooks what, how
+// much each one can have going at once, and how long they take
+use std::collections::{HashMap, VecDeque};
+
+use super::{Station, Ticket};
+use crate::front_of_house::serving::Course;
+
+// tickets a station works on at once when nobody said otherwise
+pub const DEFAULT_CAPACITY: usize = 4;
+
+// which stations can cook an item, best first, for items that shouldn't just
+// go where the dish says (the fryer can drop fries for the grill when the
+// grill is slammed)
+#[derive(Debug, Clone, Default)]
+pub struct Routing {
+  rules: HashMap<String, Vec<Station>>,
+}
+
+impl Routing {
+  pub fn new() -> Routing {
+    Routing::default()
+  }
+
+  pub fn route(mut self, item: &str, stations: &[Station]) -> Routing {
+    self.rules.insert(String::from(item), stations.to_vec());
+    self
+  }
+
+  // the stations that could take the item, the dish's own station if there
+  // is no rule for it
+  pub fn stations_for(&self, item: &str, station: Station) -> Vec<Station> {
+    match self.rules.get(item) {
+      Some(stations) if !stations.is_empty() => stations.clone(),
+      _ => vec![station],
+    }
+  }
+}
+
+// the part of one ticket a single station has to cook
+#[derive(Debug, Clone, PartialEq)]
+pub struct StationTicket {
+  pub order_id: u32,
+  pub table: u32,
+  pub course: Course,
+  pub items: Vec<String>,
+  pub sent_at: u32, // kitchen clock, in minutes
+}
+
+#[derive(Debug, Clone)]
+pub struct StationQueue {
+  pub station: Station,
+  pub capacity: usize,
+  queue: VecDeque<StationTicket>,
+  times: Vec<u32>, // minutes from sent to bumped, for every ticket done
+}
+
+impl StationQueue {
+  pub fn new(station: Station, capacity: usize) -> StationQueue {
+    StationQueue {
+      station,
+      capacity,
+      queue: VecDeque::new(),
+      times: Vec::new(),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.queue.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.queue.is_empty()
+  }
+
+  // nothing more can start here until something is bumped
+  pub fn is_full(&self) -> bool {
+    self.queue.len() >= self.capacity
+  }
+
+  // what the cooks are on right now
+  pub fn working(&self) -> impl Iterator<Item = &StationTicket> {
+    self.queue.iter().take(self.capacity)
+  }
+
+  // what's queued up behind it
+  pub fn waiting(&self) -> impl Iterator<Item = &StationTicket> {
+    self.queue.iter().skip(self.capacity)
+  }
+
+  pub fn tickets(&self) -> impl Iterator<Item = &StationTicket> {
+    self.queue.iter()
+  }
+
+  pub(crate) fn push(&mut self, ticket: StationTicket) {
+    self.queue.push_back(ticket);
+  }
+
+  // the oldest ticket is done
+  pub(crate) fn bump(&mut self, now: u32) -> Option<StationTicket> {
+    let ticket = self.queue.pop_front()?;
+    self.times.push(now.saturating_sub(ticket.sent_at));
+    Some(ticket)
+  }
+
+  // the expediter took the whole order, whatever this station had of it is
+  // done too
+  pub(crate) fn finish_order(&mut self, order_id: u32, course: Course, now: u32) {
+    let (done, left): (Vec<StationTicket>, Vec<StationTicket>) = self
+      .queue
+      .drain(..)
+      .partition(|ticket| ticket.order_id == order_id && ticket.course == course);
+    self.times.extend(done.iter().map(|ticket| now.saturating_sub(ticket.sent_at)));
+    self.queue = left.into();
+  }
+
+  pub(crate) fn move_order(&mut self, order_id: u32, table: u32) {
+    for ticket in self.queue.iter_mut().filter(|ticket| ticket.order_id == order_id) {
+      ticket.table = table;
+    }
+  }
+
+  pub fn has(&self, order_id: u32, course: Course) -> bool {
+    self.queue.iter().any(|ticket| ticket.order_id == order_id && ticket.course == course)
+  }
+
+  // minutes per ticket, None until the station has bumped something
+  pub fn average_ticket_time(&self) -> Option<f64> {
+    if self.times.is_empty() {
+      return None;
+    }
+    Some(self.times.iter().sum::<u32>() as f64 / self.times.len() as f64)
+  }
+}
+
+// one ticket as the expediter sees it: which stations it's still waiting on
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpoLine {
+  pub order_id: u32,
+  pub table: u32,
+  pub course: Course,
+  pub waiting_on: Vec<Station>,
+}
+
+impl ExpoLine {
+  // every station has sent its part, run the food
+  pub fn is_ready(&self) -> bool {
+    self.waiting_on.is_empty()
+  }
+}
+
+// split a ticket by station, each item going to the first station in its
+// routing that has room, or the least busy one if they are all full
+pub(crate) fn split_ticket(
+  ticket: &Ticket,
+  routing: &Routing,
+  queues: &HashMap<Station, StationQueue>,
+  now: u32,
+) -> Vec<(Station, StationTicket)> {
+  let mut parts: Vec<(Station, StationTicket)> = Vec::new();
+  for line in &ticket.lines {
+    let choices = routing.stations_for(&line.dish.name(), line.station());
+    let load = |station: &Station| {
+      let queued = queues.get(station).map_or(0, |queue| queue.len());
+      let added = parts.iter().filter(|(s, _)| s == station).count();
+      let capacity = queues.get(station).map_or(DEFAULT_CAPACITY, |queue| queue.capacity);
+      (queued + added, capacity)
+    };
+    let station = choices
+      .iter()
+      .find(|station| {
+        let (load, capacity) = load(station);
+        load < capacity
+      })
+      .or_else(|| choices.iter().min_by_key(|station| load(station).0))
+      .copied()
+      .unwrap_or_else(|| line.station());
+
+    match parts.iter_mut().find(|(s, _)| *s == station) {
+      Some((_, part)) => part.items.push(line.item()),
+      None => parts.push((
+        station,
+        StationTicket {
+          order_id: ticket.order_id,
+          table: ticket.table,
+          course: ticket.course,
+          items: vec![line.item()],
+          sent_at: now,
+        },
+      )),
+    }
+  }
+  parts
+}
@@ -0,0 +1,134 @@
+// food that went in the bin instead of to a guest: remakes, dropped plates,
+// stock that went off
+use std::collections::BTreeMap;
+
+use super::costing::CostBook;
+use super::inventory::{Counts, Inventory};
+use crate::error::RestaurantError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WasteReason {
+  Remake,
+  Dropped,
+  Expired,
+  Other(String),
+}
+
+// a whole dish, or some of one ingredient
+#[derive(Debug, Clone, PartialEq)]
+pub enum Wasted {
+  Item(String),
+  Ingredient(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WasteEntry {
+  pub wasted: Wasted,
+  pub quantity: f64, // plates for items, the ingredient's unit otherwise
+  pub reason: WasteReason,
+  pub staff: String,
+  pub cost: f64, // in cents, zero if we have no recipe or price for it
+}
+
+#[derive(Debug, Default)]
+pub struct WasteLog {
+  entries: Vec<WasteEntry>,
+}
+
+impl WasteLog {
+  pub fn new() -> WasteLog {
+    WasteLog::default()
+  }
+
+  pub fn entries(&self) -> &[WasteEntry] {
+    &self.entries
+  }
+
+  // write it down, take it off the shelves and put a price on it
+  pub fn log(
+    &mut self,
+    wasted: Wasted,
+    quantity: f64,
+    reason: WasteReason,
+    staff: &str,
+    cost_book: &CostBook,
+    inventory: &mut Inventory,
+  ) -> Result<&WasteEntry, RestaurantError> {
+    self.log_all(vec![(wasted, quantity)], reason, staff, cost_book, inventory)?;
+    Ok(self.entries.last().expect("just logged"))
+  }
+
+  // several things thrown out together, either all of them are logged or
+  // none are
+  pub fn log_all(
+    &mut self,
+    wasted: Vec<(Wasted, f64)>,
+    reason: WasteReason,
+    staff: &str,
+    cost_book: &CostBook,
+    inventory: &mut Inventory,
+  ) -> Result<(), RestaurantError> {
+    if let Some((_, quantity)) = wasted.iter().find(|(_, quantity)| !(*quantity > 0.0 && quantity.is_finite())) {
+      return Err(RestaurantError::InvalidQuantity(quantity.to_string()));
+    }
+    let used: Vec<Counts> = wasted
+      .iter()
+      .map(|(wasted, quantity)| ingredients_used(wasted, *quantity, cost_book))
+      .collect();
+    let mut total = Counts::new();
+    for (ingredient, amount) in used.iter().flatten() {
+      *total.entry(ingredient.clone()).or_insert(0.0) += amount;
+    }
+    // check everything first so a short ingredient doesn't leave the rest deducted
+    if let Some((ingredient, _)) = total.iter().find(|(name, amount)| **amount > inventory.on_hand(name)) {
+      return Err(RestaurantError::NotEnoughStock(ingredient.clone()));
+    }
+    for (ingredient, amount) in total.iter().filter(|(_, amount)| **amount > 0.0) {
+      inventory.deduct(ingredient, *amount)?;
+    }
+    for ((wasted, quantity), used) in wasted.into_iter().zip(used) {
+      self.entries.push(WasteEntry {
+        cost: cost_book.cost_of(&used).unwrap_or(0.0),
+        wasted,
+        quantity,
+        reason: reason.clone(),
+        staff: String::from(staff),
+      });
+    }
+    Ok(())
+  }
+
+  pub fn total_cost(&self) -> f64 {
+    self.entries.iter().map(|entry| entry.cost).sum()
+  }
+
+  pub fn cost_by_reason(&self) -> BTreeMap<String, f64> {
+    let mut costs = BTreeMap::new();
+    for entry in &self.entries {
+      let reason = match &entry.reason {
+        WasteReason::Other(reason) => reason.clone(),
+        reason => format!("{:?}", reason),
+      };
+      *costs.entry(reason).or_insert(0.0) += entry.cost;
+    }
+    costs
+  }
+}
+
+// an item we have no recipe for is logged but can't be taken off the shelves
+fn ingredients_used(wasted: &Wasted, quantity: f64, cost_book: &CostBook) -> Counts {
+  let mut used = Counts::new();
+  match wasted {
+    Wasted::Ingredient(ingredient) => {
+      used.insert(ingredient.clone(), quantity);
+    }
+    Wasted::Item(item) => {
+      if let Ok(recipe) = cost_book.recipe(item) {
+        for (ingredient, amount) in &recipe.ingredients {
+          *used.entry(ingredient.clone()).or_insert(0.0) += amount * quantity;
+        }
+      }
+    }
+  }
+  used
+}
@@ -0,0 +1,81 @@
+// the guest ordering API on localhost, with a few tables already seated and
+// the menu read from menu.txt, or the file named on the command line
+//
+// every seated table's token is printed at startup; servers type
+//   pending        what guests have sent
+//   approve <id>   ring it in
+//   reject <id>    send it back
+//   requests       tables that called for their server or the check
+//   seen <table>   clear a table's requests
+use std::env;
+use std::io::{self, BufRead};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::thread;
+
+use restuarant::guest_api;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Menu;
+use restuarant::service::{Restaurant, Service};
+
+const ADDRESS: &str = "127.0.0.1:8080";
+const MENU_FILE: &str = "menu.txt";
+
+fn main() -> io::Result<()> {
+    let path = env::args().nth(1).map_or_else(|| PathBuf::from(MENU_FILE), PathBuf::from);
+    let floor = Floor::new((1..=4).map(|number| Table::new(number, 4)).collect());
+    let mut restaurant = Restaurant::new(floor);
+    restaurant.menu = Menu::load(&path)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {}", path.display(), error)))?;
+
+    for number in 1..=4 {
+        restaurant.add_to_waitlist(Party::new(&format!("table {}", number), 2));
+        restaurant.seat_at_table(number).expect("the table is open");
+        let token = restaurant.open_session(number).expect("the table is seated");
+        println!("table {}: http://{}/session/{}", number, ADDRESS, token);
+    }
+    let service = Service::new(restaurant);
+
+    let listener = TcpListener::bind(ADDRESS)?;
+    let api = service.clone();
+    thread::spawn(move || guest_api::serve(listener, api));
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["pending"] => service.with(|r| {
+                for submission in r.sessions.pending() {
+                    let items: Vec<String> = submission.lines.iter().map(|line| line.item()).collect();
+                    println!("#{} table {}: {}", submission.id, submission.table, items.join(", "));
+                }
+            }),
+            ["approve", id] => match id.parse() {
+                Ok(id) => match service.with(|r| r.approve_submission(id)) {
+                    Ok(order) => println!("sent to the kitchen as order {}", order),
+                    Err(error) => println!("{}", error),
+                },
+                Err(_) => println!("which submission?"),
+            },
+            ["reject", id] => match id.parse() {
+                Ok(id) => match service.with(|r| r.reject_submission(id)) {
+                    Ok(()) => println!("sent back"),
+                    Err(error) => println!("{}", error),
+                },
+                Err(_) => println!("which submission?"),
+            },
+            ["requests"] => {
+                for (table, request) in service.with(|r| r.sessions.requests()) {
+                    println!("table {}: {:?}", table, request);
+                }
+            }
+            ["seen", table] => match table.parse() {
+                Ok(table) => service.with(|r| r.sessions.acknowledge(table)),
+                Err(_) => println!("which table?"),
+            },
+            [] => {}
+            _ => println!("pending, approve <id>, reject <id>, requests or seen <table>"),
+        }
+    }
+    Ok(())
+}
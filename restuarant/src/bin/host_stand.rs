@@ -0,0 +1,463 @@
+// keyboard-driven screens for hosts and servers
+// it only needs `stty` and an ANSI terminal, so it works fine over ssh
+//
+// 1 floor map, 2 waitlist, 3 kitchen, q quit
+// floor:    j/k (or arrows) pick a table, s seat, c clear, b bus, o take an order,
+//           n fire the next held course
+// waitlist: a add a party
+// order:    1-9 add a dish, x remove the last one, enter send it, f or esc back to the floor
+// kitchen:  j/k pick a station, b bump its oldest ticket, e run the next ready ticket
+//
+// the menu is menu.txt, or the file named on the command line, the same one
+// the guest ordering API reads
+use std::collections::VecDeque;
+use std::env;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Instant;
+
+use restuarant::back_of_house::Station;
+use restuarant::hosting::{Floor, Party, Table, TableStatus};
+use restuarant::menu::Menu;
+use restuarant::serving::OrderLine;
+use restuarant::service::{Restaurant, Service};
+
+const MENU_FILE: &str = "menu.txt";
+
+// tables per row on the floor map
+const MAP_WIDTH: usize = 4;
+
+const STATIONS: [Station; 6] = [
+    Station::Line,
+    Station::Grill,
+    Station::Fryer,
+    Station::Saute,
+    Station::Pantry,
+    Station::Pastry,
+];
+
+enum Screen {
+    Floor,
+    Waitlist,
+    Order(u32), // the table we are ringing in
+    Kitchen,
+}
+
+enum Key {
+    Char(char),
+    Up,
+    Down,
+    Enter,
+    Backspace,
+    Escape,
+}
+
+// puts the terminal back the way we found it, even if we panic
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        stty(&["raw", "-echo"])?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&["sane"]);
+        print!("\x1b[?25h\r\n");
+        let _ = io::stdout().flush();
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<()> {
+    let status = Command::new("stty").args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other("stty failed, is stdin a terminal?"))
+    }
+}
+
+// keys from the terminal. a read hands back everything typed so far, so an
+// arrow key's whole escape sequence arrives together and an ESC with nothing
+// after it is the Esc key, not the start of a sequence still on its way
+struct Keys<R> {
+    input: R,
+    pending: VecDeque<u8>,
+}
+
+impl<R: Read> Keys<R> {
+    fn new(input: R) -> Keys<R> {
+        Keys {
+            input,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn next(&mut self) -> io::Result<Key> {
+        loop {
+            if self.pending.is_empty() {
+                let mut buffer = [0u8; 64];
+                let read = self.input.read(&mut buffer)?;
+                if read == 0 {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                self.pending.extend(&buffer[..read]);
+            }
+            let Some(byte) = self.pending.pop_front() else { continue };
+            match byte {
+                b'\r' | b'\n' => return Ok(Key::Enter),
+                127 | 8 => return Ok(Key::Backspace),
+                3 => return Ok(Key::Char('q')), // ctrl-c still quits in raw mode
+                // arrow keys come in as ESC [ A / ESC [ B
+                27 => match (self.pending.front(), self.pending.get(1).copied()) {
+                    (Some(b'['), Some(code)) => {
+                        self.pending.drain(..2);
+                        match code {
+                            b'A' => return Ok(Key::Up),
+                            b'B' => return Ok(Key::Down),
+                            _ => continue,
+                        }
+                    }
+                    (Some(b'['), None) => {
+                        self.pending.clear();
+                        continue;
+                    }
+                    _ => return Ok(Key::Escape),
+                },
+                other if other.is_ascii_graphic() || other == b' ' => return Ok(Key::Char(other as char)),
+                _ => continue,
+            }
+        }
+    }
+}
+
+struct App {
+    service: Service,
+    screen: Screen,
+    selected: usize,
+    cart: Vec<OrderLine>,
+    message: String,
+    station: usize,
+    opened: Instant, // the kitchen clock counts from here
+}
+
+impl App {
+    fn new(service: Service) -> App {
+        App {
+            service,
+            screen: Screen::Floor,
+            selected: 0,
+            cart: Vec::new(),
+            message: String::new(),
+            station: 0,
+            opened: Instant::now(),
+        }
+    }
+
+    fn selected_table(&self) -> Option<u32> {
+        self.service.with(|r| r.floor.tables().get(self.selected).map(|table| table.number))
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::from("\x1b[2J\x1b[H\x1b[?25l");
+        out.push_str("\x1b[1mRESTAURANT\x1b[0m   1 floor  2 waitlist  3 kitchen  q quit\r\n\r\n");
+        self.service.with(|r| match self.screen {
+            Screen::Floor => render_floor(&mut out, r, self.selected),
+            Screen::Waitlist => render_waitlist(&mut out, r),
+            Screen::Order(table) => render_order(&mut out, &r.menu, table, &self.cart),
+            Screen::Kitchen => render_kitchen(&mut out, r, self.station),
+        });
+        out.push_str("\r\n");
+        out.push_str(&self.message);
+        out.push_str("\r\n");
+        out
+    }
+
+    // returns false when it's time to quit
+    fn handle(&mut self, key: Key, keys: &mut Keys<impl Read>) -> io::Result<bool> {
+        self.message.clear();
+        let minutes = (self.opened.elapsed().as_secs() / 60) as u32;
+        self.service.with(|r| r.kitchen.set_time(minutes));
+        match (&self.screen, key) {
+            (Screen::Order(_), Key::Char(c @ '1'..='9')) => {
+                let dish = self.service.with(|r| r.menu.get(c as usize - '1' as usize).cloned());
+                if let Some(dish) = dish {
+                    self.cart.push(OrderLine::shared(dish));
+                }
+            }
+            (Screen::Order(_), Key::Char('x')) | (Screen::Order(_), Key::Backspace) => {
+                self.cart.pop();
+            }
+            (Screen::Order(table), Key::Enter) => {
+                let table = *table;
+                let lines = std::mem::take(&mut self.cart);
+                self.message = match self.service.take_order(table, lines) {
+                    Ok(id) => format!("sent order {} for table {}", id, table),
+                    Err(error) => error.to_string(),
+                };
+                self.screen = Screen::Floor;
+            }
+            (Screen::Order(_), Key::Char('f')) | (Screen::Order(_), Key::Escape) => {
+                self.cart.clear();
+                self.screen = Screen::Floor;
+            }
+            (Screen::Order(_), Key::Char('q')) => return Ok(false),
+            (Screen::Order(_), _) => {}
+            (_, Key::Char('q')) => return Ok(false),
+            (_, Key::Char('1')) => self.screen = Screen::Floor,
+            (_, Key::Char('2')) => self.screen = Screen::Waitlist,
+            (_, Key::Char('3')) => self.screen = Screen::Kitchen,
+            (Screen::Kitchen, Key::Char('j')) | (Screen::Kitchen, Key::Down) => {
+                self.station = (self.station + 1).min(STATIONS.len() - 1);
+            }
+            (Screen::Kitchen, Key::Char('k')) | (Screen::Kitchen, Key::Up) => {
+                self.station = self.station.saturating_sub(1);
+            }
+            (Screen::Kitchen, Key::Char('b')) => {
+                let station = STATIONS[self.station];
+                self.message = match self.service.bump_station(station) {
+                    Some(ticket) => format!("{:?} bumped order {}", station, ticket.order_id),
+                    None => format!("nothing up on {:?}", station),
+                };
+            }
+            (Screen::Kitchen, Key::Char('e')) => {
+                self.message = match self.service.expedite() {
+                    Some(ticket) => format!("order {} for table {} is ready to run", ticket.order_id, ticket.table),
+                    None => String::from("no ticket has every part ready"),
+                };
+            }
+            (Screen::Floor, Key::Char('j')) | (Screen::Floor, Key::Down) => {
+                let tables = self.service.with(|r| r.floor.tables().len());
+                self.selected = (self.selected + 1).min(tables.saturating_sub(1));
+            }
+            (Screen::Floor, Key::Char('k')) | (Screen::Floor, Key::Up) => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            (Screen::Floor, Key::Char(c @ ('s' | 'c' | 'b' | 'o' | 'n'))) => {
+                if let Some(table) = self.selected_table() {
+                    self.floor_action(c, table);
+                }
+            }
+            (Screen::Waitlist, Key::Char('a')) => self.add_party(keys)?,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn floor_action(&mut self, action: char, number: u32) {
+        let result = match action {
+            's' => self.service.seat_at_table(number).map(|party| format!("seated {} at table {}", party.name, number)),
+            'c' => self.service.with(|r| r.clear_table(number).map(|party| format!("{} left table {}", party.name, number))),
+            'b' => self.service.with(|r| {
+                restuarant::hosting::bus_table(r.floor.table_mut(number)?);
+                Ok(format!("bussed table {}", number))
+            }),
+            'n' => self.service.with(|r| {
+                let next = r
+                    .orders
+                    .iter()
+                    .filter(|order| order.table == number)
+                    .find_map(|order| order.held_courses().first().map(|course| (order.id, *course)));
+                match next {
+                    Some((id, course)) => r.fire_course(id, course).map(|_| format!("fired {:?} for order {}", course, id)),
+                    None => Ok(format!("nothing held for table {}", number)),
+                }
+            }),
+            _ => {
+                let seated = self.service.with(|r| r.floor.table(number).map(|t| t.status == TableStatus::Seated));
+                match seated {
+                    Ok(true) => {
+                        self.screen = Screen::Order(number);
+                        Ok(String::new())
+                    }
+                    Ok(false) => Err(restuarant::error::RestaurantError::TableNotSeated(number)),
+                    Err(error) => Err(error),
+                }
+            }
+        };
+        self.message = result.unwrap_or_else(|error| error.to_string());
+    }
+
+    fn add_party(&mut self, keys: &mut Keys<impl Read>) -> io::Result<()> {
+        let name = self.prompt("party name: ", keys)?;
+        if name.is_empty() {
+            return Ok(());
+        }
+        let size = self.prompt("party size: ", keys)?;
+        match size.parse::<u32>() {
+            Ok(size) if size > 0 => {
+                self.service.add_to_waitlist(Party::new(&name, size));
+                self.message = format!("added {} ({})", name, size);
+            }
+            _ => self.message = format!("'{}' is not a party size", size),
+        }
+        Ok(())
+    }
+
+    // read a line of text while the terminal is in raw mode, esc gives up
+    fn prompt(&self, label: &str, keys: &mut Keys<impl Read>) -> io::Result<String> {
+        let mut text = String::new();
+        loop {
+            print!("{}\r\n{}{}\x1b[?25h", self.render(), label, text);
+            io::stdout().flush()?;
+            match keys.next()? {
+                Key::Enter => return Ok(text.trim().to_string()),
+                Key::Escape => return Ok(String::new()),
+                Key::Backspace => {
+                    text.pop();
+                }
+                Key::Char(c) => text.push(c),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn status_colour(status: TableStatus) -> &'static str {
+    match status {
+        TableStatus::Open => "\x1b[32m",   // green
+        TableStatus::Seated => "\x1b[33m", // yellow
+        TableStatus::Dirty => "\x1b[31m",  // red
+    }
+}
+
+fn render_floor(out: &mut String, restaurant: &Restaurant, selected: usize) {
+    out.push_str("FLOOR  \x1b[32mopen\x1b[0m \x1b[33mseated\x1b[0m \x1b[31mdirty\x1b[0m\r\n\r\n");
+    for (row, tables) in restaurant.floor.tables().chunks(MAP_WIDTH).enumerate() {
+        for (col, table) in tables.iter().enumerate() {
+            let highlight = if row * MAP_WIDTH + col == selected { "\x1b[7m" } else { "" };
+            out.push_str(&format!(
+                "{}{}[ T{:<2} {}p ]\x1b[0m  ",
+                status_colour(table.status),
+                highlight,
+                table.number,
+                table.seats
+            ));
+        }
+        out.push_str("\r\n\r\n");
+    }
+    if let Some(table) = restaurant.floor.tables().get(selected) {
+        let party = match &table.party {
+            Some(party) => format!("{} ({})", party.name, party.size),
+            None => String::from("-"),
+        };
+        let open_orders = restaurant.orders.iter().filter(|order| order.table == table.number).count();
+        out.push_str(&format!(
+            "table {}: {:?}, party {}, {} orders\r\n",
+            table.number, table.status, party, open_orders
+        ));
+    }
+    out.push_str("j/k pick  s seat  c clear  b bus  o order  n fire next course\r\n");
+}
+
+fn render_waitlist(out: &mut String, restaurant: &Restaurant) {
+    out.push_str("WAITLIST\r\n\r\n");
+    if restaurant.waitlist.is_empty() {
+        out.push_str("  nobody is waiting\r\n");
+    }
+    for (position, party) in restaurant.waitlist.iter().enumerate() {
+        let quote = match restaurant.waitlist.quote_wait(&restaurant.floor, position) {
+            Some(0) => String::from("next table"),
+            Some(minutes) => format!("~{} min", minutes),
+            None => String::from("no table fits"),
+        };
+        out.push_str(&format!("  {:>2}. {:<20} {:>2}  {}\r\n", position + 1, party.name, party.size, quote));
+    }
+    out.push_str("\r\na add party\r\n");
+}
+
+fn render_order(out: &mut String, menu: &Menu, table: u32, cart: &[OrderLine]) {
+    out.push_str(&format!("ORDER FOR TABLE {}\r\n\r\n", table));
+    // only the first nine have a key
+    for (number, dish) in menu.items().iter().take(9).enumerate() {
+        out.push_str(&format!(
+            "  {}  {:<40} {:>4}.{:02}  {:?}\r\n",
+            number + 1,
+            dish.name(),
+            dish.price() / 100,
+            dish.price() % 100,
+            dish.course()
+        ));
+    }
+    out.push_str("\r\n  on this order:\r\n");
+    for line in cart {
+        out.push_str(&format!("     {:<40} {:>4}.{:02}\r\n", line.item(), line.price() / 100, line.price() % 100));
+    }
+    let total: u32 = cart.iter().map(|line| line.price()).sum();
+    out.push_str(&format!("     {:<40} {:>4}.{:02}\r\n", "total", total / 100, total % 100));
+    out.push_str("\r\n1-9 add  x remove  enter send  f/esc back\r\n");
+}
+
+fn render_kitchen(out: &mut String, restaurant: &Restaurant, selected: usize) {
+    out.push_str(&format!("KITCHEN  {} min into service\r\n\r\n", restaurant.kitchen.time()));
+    let times = restaurant.kitchen.average_ticket_times();
+    for (index, station) in STATIONS.iter().enumerate() {
+        let highlight = if index == selected { "\x1b[7m" } else { "" };
+        let (working, waiting, full) = match restaurant.kitchen.station(*station) {
+            Some(queue) => (queue.working().count(), queue.waiting().count(), queue.is_full()),
+            None => (0, 0, false),
+        };
+        let average = match times.get(station) {
+            Some(minutes) => format!("{:.1} min/ticket", minutes),
+            None => String::from("-"),
+        };
+        let colour = if full { "\x1b[31m" } else { "" };
+        out.push_str(&format!(
+            "  {}{}{:<8}\x1b[0m {} on, {} waiting  {}\r\n",
+            highlight,
+            colour,
+            format!("{:?}", station),
+            working,
+            waiting,
+            average
+        ));
+    }
+    out.push_str("\r\n  EXPO\r\n");
+    for line in restaurant.kitchen.expo() {
+        let state = if line.is_ready() {
+            String::from("\x1b[32mREADY\x1b[0m")
+        } else {
+            let stations: Vec<String> = line.waiting_on.iter().map(|station| format!("{:?}", station)).collect();
+            format!("waiting on {}", stations.join(", "))
+        };
+        out.push_str(&format!(
+            "  order {:<4} table {:<3} {:<10} {}\r\n",
+            line.order_id,
+            line.table,
+            format!("{:?}", line.course),
+            state
+        ));
+    }
+    out.push_str("\r\nj/k pick station  b bump station  e expedite\r\n");
+}
+
+fn main() -> io::Result<()> {
+    let path = env::args().nth(1).map_or_else(|| PathBuf::from(MENU_FILE), PathBuf::from);
+    let floor = Floor::new(vec![
+        Table::new(1, 2),
+        Table::new(2, 2),
+        Table::new(3, 4),
+        Table::new(4, 4),
+        Table::new(5, 4),
+        Table::new(6, 6),
+        Table::new(7, 8),
+    ]);
+    let mut restaurant = Restaurant::new(floor);
+    restaurant.menu = Menu::load(&path)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {}", path.display(), error)))?;
+    let mut app = App::new(Service::new(restaurant));
+
+    let _raw = RawMode::enable()?;
+    let mut keys = Keys::new(io::stdin().lock());
+    loop {
+        print!("{}", app.render());
+        io::stdout().flush()?;
+        let key = keys.next()?;
+        if !app.handle(key, &mut keys)? {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,217 @@
+// guests we know by name, what they like, and their loyalty points
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+
+use crate::error::RestaurantError;
+use crate::front_of_house::serving::{Order, Tender};
+use crate::persistence::{self, Record};
+
+// guests earn this many points for every whole dollar they pay
+pub const POINTS_PER_DOLLAR: u32 = 1;
+// and every point is worth this much when they spend it
+pub const CENTS_PER_POINT: u32 = 5;
+
+// points needed to cover this many cents, rounded up
+pub fn points_for(cents: u32) -> u32 {
+  cents.div_ceil(CENTS_PER_POINT)
+}
+
+// what points are worth in cents, None when that's more than a u32 holds
+pub fn points_value(points: u32) -> Option<u32> {
+  points.checked_mul(CENTS_PER_POINT)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Visit {
+  pub order_id: u32,
+  pub items: Vec<String>,
+  pub spent: u32, // in cents
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Customer {
+  pub id: u32,
+  pub name: String,
+  pub contact: String, // phone number or email
+  pub allergies: Vec<String>,
+  pub points: u32,
+  pub visits: Vec<Visit>,
+}
+
+impl Customer {
+  // the dishes they order most, most ordered first
+  pub fn favourite_items(&self, count: usize) -> Vec<(String, usize)> {
+    let mut tally: HashMap<&str, usize> = HashMap::new();
+    for item in self.visits.iter().flat_map(|visit| visit.items.iter()) {
+      *tally.entry(item.as_str()).or_insert(0) += 1;
+    }
+    let mut favourites: Vec<(String, usize)> = tally.into_iter().map(|(item, n)| (item.to_string(), n)).collect();
+    favourites.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    favourites.truncate(count);
+    favourites
+  }
+
+  pub fn total_spent(&self) -> u32 {
+    self.visits.iter().map(|visit| visit.spent).sum()
+  }
+}
+
+// saved on its own line, visits go in a separate file
+impl Record for Customer {
+  fn to_fields(&self) -> Vec<String> {
+    vec![
+      self.id.to_string(),
+      self.name.clone(),
+      self.contact.clone(),
+      self.allergies.join(","),
+      self.points.to_string(),
+    ]
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<Customer> {
+    match fields {
+      [id, name, contact, allergies, points] => Some(Customer {
+        id: id.parse().ok()?,
+        name: name.to_string(),
+        contact: contact.to_string(),
+        allergies: allergies.split(',').filter(|a| !a.is_empty()).map(String::from).collect(),
+        points: points.parse().ok()?,
+        visits: Vec::new(),
+      }),
+      _ => None,
+    }
+  }
+}
+
+// a visit and whose it is
+struct VisitRecord(u32, Visit);
+
+impl Record for VisitRecord {
+  fn to_fields(&self) -> Vec<String> {
+    let VisitRecord(customer, visit) = self;
+    vec![
+      customer.to_string(),
+      visit.order_id.to_string(),
+      visit.spent.to_string(),
+      visit.items.join(";"),
+    ]
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<VisitRecord> {
+    match fields {
+      [customer, order_id, spent, items] => Some(VisitRecord(
+        customer.parse().ok()?,
+        Visit {
+          order_id: order_id.parse().ok()?,
+          spent: spent.parse().ok()?,
+          items: items.split(';').filter(|i| !i.is_empty()).map(String::from).collect(),
+        },
+      )),
+      _ => None,
+    }
+  }
+}
+
+const CUSTOMERS_FILE: &str = "customers.tsv";
+const VISITS_FILE: &str = "customer_visits.tsv";
+
+#[derive(Debug, Default)]
+pub struct Customers {
+  customers: BTreeMap<u32, Customer>,
+  next_id: u32,
+}
+
+impl Customers {
+  pub fn new() -> Customers {
+    Customers::default()
+  }
+
+  // returns the new customer's id
+  pub fn add(&mut self, name: &str, contact: &str) -> u32 {
+    self.next_id += 1;
+    let customer = Customer {
+      id: self.next_id,
+      name: String::from(name),
+      contact: String::from(contact),
+      allergies: Vec::new(),
+      points: 0,
+      visits: Vec::new(),
+    };
+    self.customers.insert(customer.id, customer);
+    self.next_id
+  }
+
+  pub fn get(&self, id: u32) -> Result<&Customer, RestaurantError> {
+    self.customers.get(&id).ok_or(RestaurantError::CustomerNotFound(id))
+  }
+
+  pub fn get_mut(&mut self, id: u32) -> Result<&mut Customer, RestaurantError> {
+    self.customers.get_mut(&id).ok_or(RestaurantError::CustomerNotFound(id))
+  }
+
+  pub fn find_by_contact(&self, contact: &str) -> Option<&Customer> {
+    self.customers.values().find(|customer| customer.contact == contact)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Customer> {
+    self.customers.values()
+  }
+
+  // take points off their balance, returns what they are worth in cents
+  pub fn redeem_points(&mut self, id: u32, points: u32) -> Result<u32, RestaurantError> {
+    let customer = self.get_mut(id)?;
+    if points > customer.points {
+      return Err(RestaurantError::NotEnoughPoints(id, customer.points));
+    }
+    let value = points_value(points).ok_or(RestaurantError::TooManyPoints(points))?;
+    customer.points -= points;
+    Ok(value)
+  }
+
+  // the check is paid: remember the visit and give them points for what
+  // they paid with real money, points spent don't earn more points
+  pub fn record_visit(&mut self, id: u32, order: &Order) -> Result<u32, RestaurantError> {
+    let customer = self.get_mut(id)?;
+    let earning: u32 = order
+      .payments
+      .iter()
+      .filter(|payment| !matches!(payment.tender, Tender::Points(..)))
+      .map(|payment| payment.applied)
+      .sum();
+    let earned = earning / 100 * POINTS_PER_DOLLAR;
+    customer.points += earned;
+    customer.visits.push(Visit {
+      order_id: order.id,
+      items: order.lines.iter().map(|line| line.item()).collect(),
+      spent: order.paid(),
+    });
+    Ok(earned)
+  }
+
+  pub fn save(&self, dir: &Path) -> io::Result<()> {
+    let customers: Vec<Customer> = self.customers.values().cloned().collect();
+    let visits: Vec<VisitRecord> = customers
+      .iter()
+      .flat_map(|customer| customer.visits.iter().map(|visit| VisitRecord(customer.id, visit.clone())))
+      .collect();
+    persistence::save(&dir.join(CUSTOMERS_FILE), &customers)?;
+    persistence::save(&dir.join(VISITS_FILE), &visits)
+  }
+
+  pub fn load(dir: &Path) -> io::Result<Customers> {
+    let mut customers: BTreeMap<u32, Customer> = persistence::load::<Customer>(&dir.join(CUSTOMERS_FILE))?
+      .into_iter()
+      .map(|customer| (customer.id, customer))
+      .collect();
+    for VisitRecord(id, visit) in persistence::load::<VisitRecord>(&dir.join(VISITS_FILE))? {
+      if let Some(customer) = customers.get_mut(&id) {
+        customer.visits.push(visit);
+      }
+    }
+    Ok(Customers {
+      next_id: customers.keys().max().copied().unwrap_or(0),
+      customers,
+    })
+  }
+}
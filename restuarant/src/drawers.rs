@@ -0,0 +1,164 @@
+// cash drawers: every cash sale lands in the drawer of whoever took it,
+// and at the end of the shift the drawer is counted blind and reconciled
+use std::collections::BTreeMap;
+
+use crate::error::RestaurantError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawerEntry {
+  Float(u32),           // the cash it started with
+  Sale(u32, u32),       // order id, cash kept after change
+  Drop(u32),            // cash moved to the safe mid-shift
+  PaidOut(u32, String), // cash paid out of the drawer, and what for
+}
+
+// what the count came to against what the drawer should hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reconciliation {
+  pub expected: u32,
+  pub counted: u32,
+}
+
+impl Reconciliation {
+  // positive when the drawer is over, negative when it's short
+  pub fn variance(&self) -> i64 {
+    self.counted as i64 - self.expected as i64
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Drawer {
+  pub id: u32,
+  pub staff: String,
+  pub entries: Vec<DrawerEntry>,
+  pub closed: Option<Reconciliation>,
+}
+
+impl Drawer {
+  // what should be in the drawer right now
+  pub fn expected_cash(&self) -> u32 {
+    self.entries.iter().fold(0, |cash, entry| match entry {
+      DrawerEntry::Float(amount) | DrawerEntry::Sale(_, amount) => cash + amount,
+      DrawerEntry::Drop(amount) | DrawerEntry::PaidOut(amount, _) => cash - amount,
+    })
+  }
+
+  pub fn cash_sales(&self) -> u32 {
+    self.entries
+      .iter()
+      .map(|entry| match entry {
+        DrawerEntry::Sale(_, amount) => *amount,
+        _ => 0,
+      })
+      .sum()
+  }
+
+  pub fn is_open(&self) -> bool {
+    self.closed.is_none()
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct Drawers {
+  drawers: BTreeMap<u32, Drawer>,
+  next_id: u32,
+}
+
+impl Drawers {
+  pub fn new() -> Drawers {
+    Drawers::default()
+  }
+
+  // hand a drawer with its opening float to someone, one drawer each
+  pub fn open(&mut self, staff: &str, float: u32) -> Result<u32, RestaurantError> {
+    if self.open_drawer(staff).is_some() {
+      return Err(RestaurantError::DrawerAlreadyOpen(staff.to_string()));
+    }
+    self.next_id += 1;
+    let drawer = Drawer {
+      id: self.next_id,
+      staff: String::from(staff),
+      entries: vec![DrawerEntry::Float(float)],
+      closed: None,
+    };
+    self.drawers.insert(drawer.id, drawer);
+    Ok(self.next_id)
+  }
+
+  pub fn open_drawer(&self, staff: &str) -> Option<&Drawer> {
+    self.drawers.values().find(|drawer| drawer.staff == staff && drawer.is_open())
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Drawer> {
+    self.drawers.values()
+  }
+
+  pub fn record_sale(&mut self, staff: &str, order_id: u32, amount: u32) -> Result<(), RestaurantError> {
+    self.drawer_mut(staff)?.entries.push(DrawerEntry::Sale(order_id, amount));
+    Ok(())
+  }
+
+  pub fn drop_cash(&mut self, staff: &str, amount: u32) -> Result<(), RestaurantError> {
+    let drawer = self.drawer_with(staff, amount)?;
+    drawer.entries.push(DrawerEntry::Drop(amount));
+    Ok(())
+  }
+
+  pub fn pay_out(&mut self, staff: &str, amount: u32, reason: &str) -> Result<(), RestaurantError> {
+    let drawer = self.drawer_with(staff, amount)?;
+    drawer.entries.push(DrawerEntry::PaidOut(amount, String::from(reason)));
+    Ok(())
+  }
+
+  // the staff member counts the drawer without being told what it should
+  // hold, then we compare
+  pub fn close(&mut self, staff: &str, counted: u32) -> Result<Reconciliation, RestaurantError> {
+    let drawer = self.drawer_mut(staff)?;
+    let reconciliation = Reconciliation {
+      expected: drawer.expected_cash(),
+      counted,
+    };
+    drawer.closed = Some(reconciliation);
+    Ok(reconciliation)
+  }
+
+  // every closed drawer and how far over or short it was
+  pub fn over_short_report(&self) -> String {
+    let mut report = String::from("drawer  staff                expected   counted  over/short\n");
+    for drawer in self.drawers.values() {
+      if let Some(reconciliation) = drawer.closed {
+        let variance = reconciliation.variance();
+        let sign = if variance < 0 { "-" } else { "+" };
+        report.push_str(&format!(
+          "{:>6}  {:<18} {:>10} {:>9} {:>10}\n",
+          drawer.id,
+          drawer.staff,
+          dollars(reconciliation.expected),
+          dollars(reconciliation.counted),
+          format!("{}{}", sign, dollars(variance.unsigned_abs() as u32)),
+        ));
+      }
+    }
+    report
+  }
+
+  fn drawer_mut(&mut self, staff: &str) -> Result<&mut Drawer, RestaurantError> {
+    self.drawers
+      .values_mut()
+      .find(|drawer| drawer.staff == staff && drawer.is_open())
+      .ok_or_else(|| RestaurantError::NoOpenDrawer(staff.to_string()))
+  }
+
+  // an open drawer that has at least this much cash in it
+  fn drawer_with(&mut self, staff: &str, amount: u32) -> Result<&mut Drawer, RestaurantError> {
+    let drawer = self.drawer_mut(staff)?;
+    if amount > drawer.expected_cash() {
+      return Err(RestaurantError::NotEnoughCash(staff.to_string()));
+    }
+    Ok(drawer)
+  }
+}
+
+fn dollars(cents: u32) -> String {
+  format!("{}.{:02}", cents / 100, cents % 100)
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+
+use crate::front_of_house::serving::Course;
+
+// everything that can go wrong while running the restaurant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestaurantError {
+  TableNotFound(u32),
+  TableNotOpen(u32),
+  TableNotSeated(u32),
+  NoPartyFits(u32),
+  OrderNotFound(u32),
+  OrderNotReady(u32),
+  EmptyOrder,
+  OrderNotDelivered(u32),
+  CourseNotHeld(u32, Course),
+  Overtendered(u32),
+  GiftCardNotFound(String),
+  GiftCardFull(String),
+  AccountNotFound(String),
+  AccountExists(String),
+  InsufficientBalance(String, u32),
+  CreditLimitExceeded(String),
+  CustomerNotFound(u32),
+  NotEnoughPoints(u32, u32),
+  TooManyPoints(u32),
+  NoCustomerOnOrder(u32),
+  NotTheirCheck(u32, u32),
+  ReservationNotFound(String),
+  DrawerAlreadyOpen(String),
+  NoOpenDrawer(String),
+  NotEnoughCash(String),
+  UnknownModifier(String, String),
+  UnknownIngredient(String),
+  NoRecipe(String),
+  InvalidSmoothing(String),
+  NotEnoughStock(String),
+  InvalidQuantity(String),
+  MenuItemNotFound(usize),
+  SessionNotFound,
+  CartLineNotFound(usize),
+  EmptyCart,
+  SubmissionNotFound(u32),
+  OrderInKitchen(u32),
+  SameCheck(u32),
+  CoursesOutOfStep(u32, u32),
+  DifferentCustomers(u32, u32),
+  CheckHasPayments(u32),
+  InvalidSplit(u32),
+  NoServerOnOrder(u32),
+  InvalidStaffRatio(String),
+}
+
+impl fmt::Display for RestaurantError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      RestaurantError::TableNotFound(table) => write!(f, "there is no table {}", table),
+      RestaurantError::TableNotOpen(table) => write!(f, "table {} is not open", table),
+      RestaurantError::TableNotSeated(table) => write!(f, "nobody is seated at table {}", table),
+      RestaurantError::NoPartyFits(table) => {
+        write!(f, "no party on the waitlist fits at table {}", table)
+      }
+      RestaurantError::OrderNotFound(order) => write!(f, "there is no order {}", order),
+      RestaurantError::OrderNotReady(order) => {
+        write!(f, "order {} has not come out of the kitchen yet", order)
+      }
+      RestaurantError::EmptyOrder => write!(f, "an order needs at least one dish"),
+      RestaurantError::OrderNotDelivered(order) => {
+        write!(f, "order {} has not been delivered yet", order)
+      }
+      RestaurantError::CourseNotHeld(order, course) => {
+        write!(f, "order {} has no {:?} course waiting to be fired", order, course)
+      }
+      RestaurantError::Overtendered(order) => write!(f, "that is more than order {} owes", order),
+      RestaurantError::GiftCardNotFound(card) => write!(f, "there is no gift card {}", card),
+      RestaurantError::GiftCardFull(card) => write!(f, "gift card {} cannot hold that much", card),
+      RestaurantError::AccountNotFound(account) => write!(f, "there is no house account {}", account),
+      RestaurantError::AccountExists(account) => write!(f, "house account {} already exists", account),
+      RestaurantError::InsufficientBalance(id, balance) => {
+        write!(f, "{} only has {}.{:02} available", id, balance / 100, balance % 100)
+      }
+      RestaurantError::CreditLimitExceeded(account) => {
+        write!(f, "house account {} would go over its limit", account)
+      }
+      RestaurantError::CustomerNotFound(customer) => write!(f, "there is no customer {}", customer),
+      RestaurantError::NotEnoughPoints(customer, points) => {
+        write!(f, "customer {} only has {} points", customer, points)
+      }
+      RestaurantError::TooManyPoints(points) => write!(f, "{} points is more than can be spent at once", points),
+      RestaurantError::NoCustomerOnOrder(order) => write!(f, "order {} is not linked to a customer", order),
+      RestaurantError::NotTheirCheck(customer, order) => {
+        write!(f, "customer {}'s points cannot pay order {}, it is not their check", customer, order)
+      }
+      RestaurantError::ReservationNotFound(name) => write!(f, "there is no reservation for {}", name),
+      RestaurantError::DrawerAlreadyOpen(staff) => write!(f, "{} already has a drawer open", staff),
+      RestaurantError::NoOpenDrawer(staff) => write!(f, "{} has no drawer open", staff),
+      RestaurantError::UnknownModifier(dish, modifier) => write!(f, "{} can't be ordered with {}", dish, modifier),
+      RestaurantError::UnknownIngredient(ingredient) => write!(f, "there is no ingredient called {}", ingredient),
+      RestaurantError::NoRecipe(item) => write!(f, "there is no recipe for {}", item),
+      RestaurantError::InvalidSmoothing(alpha) => {
+        write!(f, "a smoothing factor of {} has to be more than 0 and at most 1", alpha)
+      }
+      RestaurantError::NotEnoughStock(ingredient) => write!(f, "there isn't enough {} in stock", ingredient),
+      RestaurantError::InvalidQuantity(quantity) => write!(f, "{} isn't a quantity that can be used up", quantity),
+      RestaurantError::MenuItemNotFound(index) => write!(f, "there is no menu item {}", index),
+      RestaurantError::SessionNotFound => write!(f, "that table session has ended"),
+      RestaurantError::CartLineNotFound(index) => write!(f, "there is no line {} in the cart", index),
+      RestaurantError::EmptyCart => write!(f, "the cart is empty"),
+      RestaurantError::SubmissionNotFound(id) => write!(f, "there is no pending submission {}", id),
+      RestaurantError::OrderInKitchen(order) => write!(f, "order {} is still in the kitchen", order),
+      RestaurantError::SameCheck(order) => write!(f, "order {} can't be merged into itself", order),
+      RestaurantError::CoursesOutOfStep(from, into) => {
+        write!(f, "orders {} and {} have fired different courses", from, into)
+      }
+      RestaurantError::DifferentCustomers(from, into) => {
+        write!(f, "orders {} and {} belong to different customers", from, into)
+      }
+      RestaurantError::CheckHasPayments(order) => {
+        write!(f, "order {} already has payments or discounts on it", order)
+      }
+      RestaurantError::InvalidSplit(order) => write!(f, "those lines can't be split off order {}", order),
+      RestaurantError::NoServerOnOrder(order) => write!(f, "nobody is serving order {}", order),
+      RestaurantError::InvalidStaffRatio(role) => {
+        write!(f, "a {} has to be able to look after more than 0 covers", role)
+      }
+      RestaurantError::NotEnoughCash(staff) => write!(f, "{}'s drawer doesn't have that much cash", staff),
+    }
+  }
+}
+
+impl std::error::Error for RestaurantError {}
@@ -4,8 +4,7 @@
 pub mod hosting;
 
 // serving is a sibling of hosting
-mod serving {
-  fn take_order() {}
-  fn serve_order() {}
-  fn take_payment() {}
-}
\ No newline at end of file
+pub mod serving;
+
+// guests ordering from their own devices, approved by their server
+pub mod table_sessions;
@@ -1,2 +1,208 @@
-pub fn add_to_waitlist() {} // make public
-fn seat_at_table() {}
\ No newline at end of file
+use std::collections::VecDeque;
+
+use crate::error::RestaurantError;
+
+// how long a table is usually held by one party
+pub const TABLE_TURN_MINUTES: u32 = 45;
+
+// a group of guests who came in together
+#[derive(Debug, Clone, PartialEq)]
+pub struct Party {
+  pub name: String,
+  pub size: u32,
+  pub customer: Option<u32>, // set when a regular is with the party
+}
+
+impl Party {
+  pub fn new(name: &str, size: u32) -> Party {
+    Party {
+      name: String::from(name),
+      size,
+      customer: None,
+    }
+  }
+
+  pub fn customer(mut self, id: u32) -> Party {
+    self.customer = Some(id);
+    self
+  }
+}
+
+// a party that booked ahead
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reservation {
+  pub party: Party,
+  pub time: u32, // minutes after we open
+}
+
+#[derive(Debug, Default)]
+pub struct Reservations {
+  booked: Vec<Reservation>,
+}
+
+impl Reservations {
+  pub fn new() -> Reservations {
+    Reservations::default()
+  }
+
+  pub fn book(&mut self, party: Party, time: u32) {
+    self.booked.push(Reservation { party, time });
+    self.booked.sort_by_key(|reservation| reservation.time);
+  }
+
+  // earliest first
+  pub fn iter(&self) -> impl Iterator<Item = &Reservation> {
+    self.booked.iter()
+  }
+
+  pub fn for_customer(&self, id: u32) -> impl Iterator<Item = &Reservation> {
+    self.booked.iter().filter(move |reservation| reservation.party.customer == Some(id))
+  }
+}
+
+// a party with a reservation showed up, they skip to the front of the waitlist
+pub fn check_in(reservations: &mut Reservations, waitlist: &mut Waitlist, name: &str) -> Result<(), RestaurantError> {
+  let index = reservations
+    .booked
+    .iter()
+    .position(|reservation| reservation.party.name == name)
+    .ok_or_else(|| RestaurantError::ReservationNotFound(name.to_string()))?;
+  let reservation = reservations.booked.remove(index);
+  waitlist.parties.push_front(reservation.party);
+  Ok(())
+}
+
+// parties wait in the order they arrived
+#[derive(Debug, Default)]
+pub struct Waitlist {
+  parties: VecDeque<Party>,
+}
+
+impl Waitlist {
+  pub fn new() -> Waitlist {
+    Waitlist::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.parties.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.parties.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Party> {
+    self.parties.iter()
+  }
+
+  // rough quote for the party at this position: every table they fit at
+  // turns over once per TABLE_TURN_MINUTES, and the parties ahead go first
+  pub fn quote_wait(&self, floor: &Floor, position: usize) -> Option<u32> {
+    let party = self.parties.get(position)?;
+    let fitting: Vec<&Table> = floor.tables().iter().filter(|table| table.seats >= party.size).collect();
+    if fitting.is_empty() {
+      return None;
+    }
+    let open = fitting.iter().filter(|table| table.status == TableStatus::Open).count();
+    let ahead = self.parties.iter().take(position).filter(|other| other.size <= party.size).count();
+    if ahead < open {
+      return Some(0);
+    }
+    let turns = (ahead - open) / fitting.len() + 1;
+    Some(turns as u32 * TABLE_TURN_MINUTES)
+  }
+
+  // take out the first party that fits at a table with this many seats
+  fn take_first_fitting(&mut self, seats: u32) -> Option<Party> {
+    let index = self.parties.iter().position(|party| party.size <= seats)?;
+    self.parties.remove(index)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStatus {
+  Open,
+  Seated,
+  Dirty,
+}
+
+#[derive(Debug, Clone)]
+pub struct Table {
+  pub number: u32,
+  pub seats: u32,
+  pub status: TableStatus,
+  pub party: Option<Party>,
+  pub server: Option<String>, // whose section it's in
+}
+
+impl Table {
+  pub fn new(number: u32, seats: u32) -> Table {
+    Table {
+      number,
+      seats,
+      status: TableStatus::Open,
+      party: None,
+      server: None,
+    }
+  }
+}
+
+// every table in the dining room
+#[derive(Debug, Default)]
+pub struct Floor {
+  tables: Vec<Table>,
+}
+
+impl Floor {
+  pub fn new(tables: Vec<Table>) -> Floor {
+    Floor { tables }
+  }
+
+  pub fn tables(&self) -> &[Table] {
+    &self.tables
+  }
+
+  pub fn table(&self, number: u32) -> Result<&Table, RestaurantError> {
+    self.tables
+      .iter()
+      .find(|table| table.number == number)
+      .ok_or(RestaurantError::TableNotFound(number))
+  }
+
+  pub fn table_mut(&mut self, number: u32) -> Result<&mut Table, RestaurantError> {
+    self.tables
+      .iter_mut()
+      .find(|table| table.number == number)
+      .ok_or(RestaurantError::TableNotFound(number))
+  }
+}
+
+pub fn add_to_waitlist(waitlist: &mut Waitlist, party: Party) {
+  waitlist.parties.push_back(party);
+}
+
+// seat the longest-waiting party that fits at this table
+pub fn seat_at_table(waitlist: &mut Waitlist, table: &mut Table) -> Result<Party, RestaurantError> {
+  if table.status != TableStatus::Open {
+    return Err(RestaurantError::TableNotOpen(table.number));
+  }
+  let party = waitlist
+    .take_first_fitting(table.seats)
+    .ok_or(RestaurantError::NoPartyFits(table.number))?;
+  table.status = TableStatus::Seated;
+  table.party = Some(party.clone());
+  Ok(party)
+}
+
+// the party left, the table needs bussing before it can be seated again
+pub fn clear_table(table: &mut Table) -> Result<Party, RestaurantError> {
+  let party = table.party.take().ok_or(RestaurantError::TableNotSeated(table.number))?;
+  table.status = TableStatus::Dirty;
+  Ok(party)
+}
+
+pub fn bus_table(table: &mut Table) {
+  if table.status == TableStatus::Dirty {
+    table.status = TableStatus::Open;
+  }
+}
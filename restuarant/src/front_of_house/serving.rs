@@ -0,0 +1,455 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::accounts::{GiftCards, HouseAccounts};
+use crate::back_of_house::{Kitchen, Station, Ticket};
+use crate::customers::{self, Customers};
+use crate::error::RestaurantError;
+use crate::menu::{MenuItem, Modifier};
+
+// courses come out in this order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Course {
+  Appetizer,
+  Main,
+  Dessert,
+}
+
+// one dish on an order, with whatever the guest changed about it
+#[derive(Debug, Clone)]
+pub struct OrderLine {
+  pub dish: Arc<dyn MenuItem>,
+  pub course: Course,
+  pub modifiers: Vec<Modifier>,
+}
+
+impl OrderLine {
+  // goes out with the dish's usual course
+  pub fn new(dish: impl MenuItem + 'static) -> OrderLine {
+    OrderLine::shared(Arc::new(dish))
+  }
+
+  // a dish straight off the menu
+  pub fn shared(dish: Arc<dyn MenuItem>) -> OrderLine {
+    OrderLine {
+      course: dish.course(),
+      dish,
+      modifiers: Vec::new(),
+    }
+  }
+
+  pub fn course(mut self, course: Course) -> OrderLine {
+    self.course = course;
+    self
+  }
+
+  // only modifiers the dish offers can be added
+  pub fn modifier(mut self, name: &str) -> Result<OrderLine, RestaurantError> {
+    let modifier = self
+      .dish
+      .modifiers()
+      .into_iter()
+      .find(|modifier| modifier.name == name)
+      .ok_or_else(|| RestaurantError::UnknownModifier(self.dish.name(), name.to_string()))?;
+    self.modifiers.push(modifier);
+    Ok(self)
+  }
+
+  // the dish as the kitchen and the receipt show it, details and all
+  pub fn item(&self) -> String {
+    let name = match self.dish.details() {
+      Some(details) => format!("{}, {}", self.dish.name(), details),
+      None => self.dish.name(),
+    };
+    if self.modifiers.is_empty() {
+      return name;
+    }
+    let modifiers: Vec<&str> = self.modifiers.iter().map(|modifier| modifier.name.as_str()).collect();
+    format!("{} ({})", name, modifiers.join(", "))
+  }
+
+  pub fn price(&self) -> u32 {
+    self.dish.price() + self.modifiers.iter().map(|modifier| modifier.price).sum::<u32>()
+  }
+
+  pub fn station(&self) -> Station {
+    self.dish.station()
+  }
+
+  pub fn prep_minutes(&self) -> u32 {
+    self.dish.prep_minutes()
+  }
+}
+
+// status of the course that was fired last
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+  Sent,      // the ticket is in the kitchen
+  Ready,     // the kitchen bumped the ticket
+  Delivered, // the food is on the table
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+  pub id: u32,
+  pub table: u32,
+  pub lines: Vec<OrderLine>,
+  pub status: OrderStatus,
+  pub remakes: u32,
+  pub fired: Vec<Course>, // in the order they were sent to the kitchen
+  pub payments: Vec<Payment>,
+  pub discounts: Vec<Discount>,
+  pub customer: Option<u32>, // the regular this check belongs to, if we know them
+  pub server: Option<String>, // whoever has the table
+  pub tips: Vec<Tip>,
+}
+
+// a tip goes to whoever had the check when the guest left it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tip {
+  pub server: String,
+  pub amount: u32,
+}
+
+// money taken off the check before it's paid
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discount {
+  pub reason: String,
+  pub amount: u32,
+}
+
+impl Order {
+  pub fn subtotal(&self) -> u32 {
+    self.lines.iter().map(|line| line.price()).sum()
+  }
+
+  pub fn total(&self) -> u32 {
+    self.subtotal() - self.discounts.iter().map(|discount| discount.amount).sum::<u32>()
+  }
+
+  pub fn paid(&self) -> u32 {
+    self.payments.iter().map(|payment| payment.applied).sum()
+  }
+
+  pub fn balance_due(&self) -> u32 {
+    self.total() - self.paid()
+  }
+
+  // nothing left owing, a check can be paid off by discounts alone
+  pub fn is_paid(&self) -> bool {
+    self.balance_due() == 0
+  }
+
+  // courses on this order, earliest first
+  pub fn courses(&self) -> Vec<Course> {
+    let mut courses: Vec<Course> = self.lines.iter().map(|line| line.course).collect();
+    courses.sort();
+    courses.dedup();
+    courses
+  }
+
+  // courses still waiting for the server to fire them
+  pub fn held_courses(&self) -> Vec<Course> {
+    self.courses().into_iter().filter(|course| !self.fired.contains(course)).collect()
+  }
+
+  pub(crate) fn ticket(&self, course: Course) -> Ticket {
+    Ticket {
+      order_id: self.id,
+      table: self.table,
+      course,
+      lines: self.lines.iter().filter(|line| line.course == course).cloned().collect(),
+    }
+  }
+
+  pub(crate) fn fire(&mut self, kitchen: &mut Kitchen, course: Course) {
+    self.fired.push(course);
+    self.status = OrderStatus::Sent;
+    kitchen.send(self.ticket(course));
+  }
+}
+
+// every order taken today, keyed by order id
+#[derive(Debug, Default)]
+pub struct Orders {
+  orders: BTreeMap<u32, Order>,
+  next_id: u32,
+}
+
+impl Orders {
+  pub fn new() -> Orders {
+    Orders::default()
+  }
+
+  pub fn len(&self) -> usize {
+    self.orders.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.orders.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Order> {
+    self.orders.values()
+  }
+
+  pub fn get(&self, id: u32) -> Result<&Order, RestaurantError> {
+    self.orders.get(&id).ok_or(RestaurantError::OrderNotFound(id))
+  }
+
+  pub fn get_mut(&mut self, id: u32) -> Result<&mut Order, RestaurantError> {
+    self.orders.get_mut(&id).ok_or(RestaurantError::OrderNotFound(id))
+  }
+}
+
+// ring in an order and fire its first course, the rest are held for the server
+// returns the new order id. an empty order would never send a ticket, so it
+// could never come out of the kitchen
+pub fn take_order(
+  orders: &mut Orders,
+  kitchen: &mut Kitchen,
+  table: u32,
+  lines: Vec<OrderLine>,
+) -> Result<u32, RestaurantError> {
+  if lines.is_empty() {
+    return Err(RestaurantError::EmptyOrder);
+  }
+  orders.next_id += 1;
+  let mut order = Order {
+    id: orders.next_id,
+    table,
+    lines,
+    status: OrderStatus::Sent,
+    remakes: 0,
+    fired: Vec::new(),
+    payments: Vec::new(),
+    discounts: Vec::new(),
+    customer: None,
+    server: None,
+    tips: Vec::new(),
+  };
+  if let Some(first) = order.courses().first() {
+    order.fire(kitchen, *first);
+  }
+  orders.orders.insert(order.id, order);
+  Ok(orders.next_id)
+}
+
+// the table finished the last course, send the next one to the kitchen
+pub fn fire_course(orders: &mut Orders, kitchen: &mut Kitchen, id: u32, course: Course) -> Result<(), RestaurantError> {
+  let order = orders.get_mut(id)?;
+  if !order.held_courses().contains(&course) {
+    return Err(RestaurantError::CourseNotHeld(id, course));
+  }
+  if order.status != OrderStatus::Delivered {
+    return Err(RestaurantError::OrderNotDelivered(id));
+  }
+  order.fire(kitchen, course);
+  Ok(())
+}
+
+pub fn serve_order(orders: &mut Orders, id: u32) -> Result<(), RestaurantError> {
+  crate::deliver_order(orders.get_mut(id)?)
+}
+
+// how the guest is paying, with the amount they hand over
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tender {
+  Cash(u32),
+  Card(u32),
+  GiftCard(String, u32),     // card number
+  HouseAccount(String, u32), // account id
+  Points(u32, u32),          // customer id, paid for with their loyalty points
+}
+
+impl Tender {
+  pub fn amount(&self) -> u32 {
+    match self {
+      Tender::Cash(amount) | Tender::Card(amount) => *amount,
+      Tender::GiftCard(_, amount) | Tender::HouseAccount(_, amount) => *amount,
+      Tender::Points(_, amount) => *amount,
+    }
+  }
+
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Tender::Cash(_) => "cash",
+      Tender::Card(_) => "card",
+      Tender::GiftCard(..) => "gift card",
+      Tender::HouseAccount(..) => "house account",
+      Tender::Points(..) => "loyalty points",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payment {
+  pub tender: Tender,
+  pub applied: u32, // what went towards the check
+  pub change: u32,  // handed back, only ever for cash
+}
+
+// put a tender towards an order, several tenders can split one check
+// only cash can be more than what's owed, the rest comes back as change
+pub fn take_payment(
+  orders: &mut Orders,
+  gift_cards: &mut GiftCards,
+  house_accounts: &mut HouseAccounts,
+  customers: &mut Customers,
+  id: u32,
+  tender: Tender,
+) -> Result<Payment, RestaurantError> {
+  let order = orders.get_mut(id)?;
+  let due = order.balance_due();
+  let amount = tender.amount();
+  let applied = amount.min(due);
+  match &tender {
+    Tender::Cash(_) => {}
+    _ if amount > due => return Err(RestaurantError::Overtendered(id)),
+    Tender::Card(_) => {}
+    Tender::GiftCard(number, _) => {
+      gift_cards.redeem(number, amount)?;
+    }
+    Tender::HouseAccount(account, _) => house_accounts.charge(account, id, amount)?,
+    // points only pay the check of the customer who earned them
+    Tender::Points(customer, _) if order.customer != Some(*customer) => {
+      return Err(RestaurantError::NotTheirCheck(*customer, id));
+    }
+    Tender::Points(customer, _) => {
+      customers.redeem_points(*customer, customers::points_for(amount))?;
+    }
+  }
+  let payment = Payment {
+    tender,
+    applied,
+    change: amount - applied,
+  };
+  order.payments.push(payment.clone());
+  Ok(payment)
+}
+
+// take money off the check, never more than is still owed
+pub fn apply_discount(orders: &mut Orders, id: u32, discount: Discount) -> Result<(), RestaurantError> {
+  let order = orders.get_mut(id)?;
+  if discount.amount > order.balance_due() {
+    return Err(RestaurantError::Overtendered(id));
+  }
+  order.discounts.push(discount);
+  Ok(())
+}
+
+// moving food around is only safe once the kitchen is done with it
+fn not_in_kitchen(order: &Order) -> Result<(), RestaurantError> {
+  if order.status == OrderStatus::Sent {
+    return Err(RestaurantError::OrderInKitchen(order.id));
+  }
+  Ok(())
+}
+
+// the party moved, their check goes with them
+pub fn transfer_order(orders: &mut Orders, id: u32, table: u32) -> Result<u32, RestaurantError> {
+  let order = orders.get_mut(id)?;
+  let from = order.table;
+  order.table = table;
+  Ok(from)
+}
+
+// one check for two tables, everything on `from` ends up on `into`
+// a course that's on both checks has to have been fired on both or neither,
+// or we'd lose track of what still needs to go to the kitchen. two regulars'
+// checks stay apart, the merged check could only earn points for one of them
+pub fn merge_checks(orders: &mut Orders, from: u32, into: u32) -> Result<(), RestaurantError> {
+  if from == into {
+    return Err(RestaurantError::SameCheck(from));
+  }
+  let source = orders.get(from)?;
+  let target = orders.get(into)?;
+  not_in_kitchen(source)?;
+  not_in_kitchen(target)?;
+  if let (Some(a), Some(b)) = (source.customer, target.customer) {
+    if a != b {
+      return Err(RestaurantError::DifferentCustomers(from, into));
+    }
+  }
+  let shared = source.courses().into_iter().filter(|course| target.courses().contains(course));
+  for course in shared {
+    if source.fired.contains(&course) != target.fired.contains(&course) {
+      return Err(RestaurantError::CoursesOutOfStep(from, into));
+    }
+  }
+
+  let source = orders.orders.remove(&from).ok_or(RestaurantError::OrderNotFound(from))?;
+  let target = orders.get_mut(into)?;
+  target.lines.extend(source.lines);
+  for course in source.fired {
+    if !target.fired.contains(&course) {
+      target.fired.push(course);
+    }
+  }
+  // anything still out there for either table has to reach it before the
+  // merged check counts as delivered
+  if source.status == OrderStatus::Ready {
+    target.status = OrderStatus::Ready;
+  }
+  target.remakes += source.remakes;
+  target.payments.extend(source.payments);
+  target.discounts.extend(source.discounts);
+  target.tips.extend(source.tips);
+  target.customer = target.customer.or(source.customer);
+  Ok(())
+}
+
+// the lines at these positions go onto a new check for the same table and
+// the same regular, returns its id
+pub fn split_check(orders: &mut Orders, id: u32, lines: &[usize]) -> Result<u32, RestaurantError> {
+  let order = orders.get(id)?;
+  not_in_kitchen(order)?;
+  if !order.payments.is_empty() || !order.discounts.is_empty() {
+    return Err(RestaurantError::CheckHasPayments(id));
+  }
+  let mut moving: Vec<usize> = lines.to_vec();
+  moving.sort();
+  moving.dedup();
+  if moving.is_empty() || moving.len() >= order.lines.len() || moving.iter().any(|&line| line >= order.lines.len()) {
+    return Err(RestaurantError::InvalidSplit(id));
+  }
+
+  let new_id = orders.next_id + 1;
+  let order = orders.get_mut(id)?;
+  let mut split = Vec::new();
+  for index in moving.into_iter().rev() {
+    split.insert(0, order.lines.remove(index));
+  }
+  let mut new_order = Order {
+    id: new_id,
+    lines: split,
+    payments: Vec::new(),
+    discounts: Vec::new(),
+    tips: Vec::new(),
+    remakes: 0,
+    ..order.clone()
+  };
+  // each check only remembers firing the courses it still has
+  let lines = &order.lines;
+  order.fired.retain(|course| lines.iter().any(|line| line.course == *course));
+  let lines = &new_order.lines;
+  new_order.fired.retain(|course| lines.iter().any(|line| line.course == *course));
+  orders.next_id = new_id;
+  orders.orders.insert(new_id, new_order);
+  Ok(new_id)
+}
+
+pub fn add_tip(orders: &mut Orders, id: u32, amount: u32) -> Result<(), RestaurantError> {
+  let order = orders.get_mut(id)?;
+  let server = order.server.clone().ok_or(RestaurantError::NoServerOnOrder(id))?;
+  order.tips.push(Tip { server, amount });
+  Ok(())
+}
+
+pub fn tips_by_server(orders: &Orders) -> BTreeMap<String, u32> {
+  let mut tips = BTreeMap::new();
+  for tip in orders.iter().flat_map(|order| order.tips.iter()) {
+    *tips.entry(tip.server.clone()).or_insert(0) += tip.amount;
+  }
+  tips
+}
@@ -0,0 +1,150 @@
+// guests ordering from their own phones: every seated table gets a session
+// token, builds up a cart, and their server approves it before it's rung in
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+use std::time::SystemTime;
+
+use super::serving::OrderLine;
+use crate::error::RestaurantError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuestRequest {
+  CallServer,
+  RequestCheck,
+}
+
+// a cart the guests sent, waiting for their server
+#[derive(Debug, Clone)]
+pub struct Submission {
+  pub id: u32,
+  pub table: u32,
+  pub lines: Vec<OrderLine>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableSession {
+  pub token: String,
+  pub table: u32,
+  pub cart: Vec<OrderLine>,
+  pub requests: Vec<GuestRequest>, // waiting for the server to see them
+}
+
+#[derive(Debug, Default)]
+pub struct TableSessions {
+  sessions: BTreeMap<String, TableSession>,
+  submissions: BTreeMap<u32, Submission>,
+  next_submission: u32,
+}
+
+impl TableSessions {
+  pub fn new() -> TableSessions {
+    TableSessions::default()
+  }
+
+  // a fresh token for the table, any older session for it stops working
+  pub fn open(&mut self, table: u32) -> String {
+    self.sessions.retain(|_, session| session.table != table);
+    let token = new_token(table);
+    let session = TableSession {
+      token: token.clone(),
+      table,
+      cart: Vec::new(),
+      requests: Vec::new(),
+    };
+    self.sessions.insert(token.clone(), session);
+    token
+  }
+
+  // the party left, whatever they sent and nobody approved goes with them
+  pub fn close(&mut self, table: u32) {
+    self.sessions.retain(|_, session| session.table != table);
+    self.submissions.retain(|_, submission| submission.table != table);
+  }
+
+  pub fn get(&self, token: &str) -> Result<&TableSession, RestaurantError> {
+    self.sessions.get(token).ok_or(RestaurantError::SessionNotFound)
+  }
+
+  pub fn add_to_cart(&mut self, token: &str, line: OrderLine) -> Result<(), RestaurantError> {
+    self.session_mut(token)?.cart.push(line);
+    Ok(())
+  }
+
+  pub fn remove_from_cart(&mut self, token: &str, index: usize) -> Result<OrderLine, RestaurantError> {
+    let cart = &mut self.session_mut(token)?.cart;
+    if index >= cart.len() {
+      return Err(RestaurantError::CartLineNotFound(index));
+    }
+    Ok(cart.remove(index))
+  }
+
+  // hand the cart to the server, returns the submission id
+  pub fn submit(&mut self, token: &str) -> Result<u32, RestaurantError> {
+    let session = self.session_mut(token)?;
+    if session.cart.is_empty() {
+      return Err(RestaurantError::EmptyCart);
+    }
+    let table = session.table;
+    let lines = std::mem::take(&mut session.cart);
+    self.next_submission += 1;
+    let id = self.next_submission;
+    self.submissions.insert(id, Submission { id, table, lines });
+    Ok(id)
+  }
+
+  pub fn pending(&self) -> impl Iterator<Item = &Submission> {
+    self.submissions.values()
+  }
+
+  // the server took it (to ring in) or turned it down, either way it's no
+  // longer pending
+  pub fn take_submission(&mut self, id: u32) -> Result<Submission, RestaurantError> {
+    self.submissions.remove(&id).ok_or(RestaurantError::SubmissionNotFound(id))
+  }
+
+  pub fn request(&mut self, token: &str, request: GuestRequest) -> Result<(), RestaurantError> {
+    let session = self.session_mut(token)?;
+    if !session.requests.contains(&request) {
+      session.requests.push(request);
+    }
+    Ok(())
+  }
+
+  // every table that wants something from its server
+  pub fn requests(&self) -> Vec<(u32, GuestRequest)> {
+    self.sessions
+      .values()
+      .flat_map(|session| session.requests.iter().map(move |request| (session.table, *request)))
+      .collect()
+  }
+
+  // the server went over, clear what they asked for
+  pub fn acknowledge(&mut self, table: u32) {
+    for session in self.sessions.values_mut().filter(|session| session.table == table) {
+      session.requests.clear();
+    }
+  }
+
+  fn session_mut(&mut self, token: &str) -> Result<&mut TableSession, RestaurantError> {
+    self.sessions.get_mut(token).ok_or(RestaurantError::SessionNotFound)
+  }
+}
+
+// hard to guess, without pulling in a random number crate: std seeds its
+// hasher keys from the OS once per thread and then just bumps them for each
+// new RandomState, so that per-thread seed is the secret and the clock only
+// adds a little more
+fn new_token(table: u32) -> String {
+  let mut token = String::new();
+  for round in 0..2u32 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(table);
+    hasher.write_u32(round);
+    if let Ok(now) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+      hasher.write_u128(now.as_nanos());
+    }
+    token.push_str(&format!("{:016x}", hasher.finish()));
+  }
+  token
+}
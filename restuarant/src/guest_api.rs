@@ -0,0 +1,348 @@
+// a small HTTP API for guests ordering from their phones, plain std::net so
+// there is nothing extra to install on the back-office box
+//
+// GET    /menu?lang=fr                    names and descriptions in the guest's language
+// GET    /session/{token}                 the cart and anything waiting on the server
+// POST   /session/{token}/cart            body: item=<menu index>&modifier=<name>
+// DELETE /session/{token}/cart/{line}
+// POST   /session/{token}/submit          hand the cart to the server for approval
+// POST   /session/{token}/call-server
+// POST   /session/{token}/request-check
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::RestaurantError;
+use crate::front_of_house::table_sessions::GuestRequest;
+use crate::locale::{Locale, DEFAULT_LANGUAGE};
+use crate::menu::{Menu, MenuItem};
+use crate::service::Service;
+
+// nobody's order needs more than this
+const MAX_BODY: usize = 64 * 1024;
+// a phone that connects and then goes quiet doesn't get to hold a thread
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+// nor does one that trickles in a byte every few seconds
+const REQUEST_DEADLINE: Duration = Duration::from_secs(30);
+// a full dining room is nowhere near this, anything past it gets a 503
+pub const MAX_CONNECTIONS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+  pub method: String,
+  pub path: String,
+  pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+  pub status: u16,
+  pub body: String, // always JSON
+}
+
+impl Response {
+  fn ok(body: String) -> Response {
+    Response { status: 200, body }
+  }
+
+  fn error(status: u16, message: &str) -> Response {
+    Response {
+      status,
+      body: format!("{{\"error\":{}}}", json_string(message)),
+    }
+  }
+}
+
+impl From<RestaurantError> for Response {
+  fn from(error: RestaurantError) -> Response {
+    let status = match error {
+      RestaurantError::SessionNotFound
+      | RestaurantError::MenuItemNotFound(_)
+      | RestaurantError::CartLineNotFound(_) => 404,
+      _ => 400,
+    };
+    Response::error(status, &error.to_string())
+  }
+}
+
+// answer requests until the listener is closed, one thread per connection
+// up to MAX_CONNECTIONS at once
+pub fn serve(listener: TcpListener, service: Service) -> io::Result<()> {
+  let open = Arc::new(AtomicUsize::new(0));
+  for stream in listener.incoming() {
+    let mut stream = stream?;
+    if open.load(Ordering::SeqCst) >= MAX_CONNECTIONS {
+      let _ = stream.set_write_timeout(Some(READ_TIMEOUT));
+      // best effort, whatever they already sent is never read
+      let _ = write_response(&mut stream, &Response::error(503, "too many connections, try again"));
+      let _ = stream.shutdown(Shutdown::Write);
+      continue;
+    }
+    // counted here rather than in the thread so a burst can't overshoot the cap
+    let slot = Slot::take(&open);
+    let service = service.clone();
+    thread::spawn(move || {
+      let _slot = slot;
+      let _ = handle_connection(stream, &service);
+    });
+  }
+  Ok(())
+}
+
+// one open connection, given back when its thread is done even if it panics
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+  fn take(open: &Arc<AtomicUsize>) -> Slot {
+    open.fetch_add(1, Ordering::SeqCst);
+    Slot(Arc::clone(open))
+  }
+}
+
+impl Drop for Slot {
+  fn drop(&mut self) {
+    self.0.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+fn handle_connection(mut stream: TcpStream, service: &Service) -> io::Result<()> {
+  stream.set_write_timeout(Some(READ_TIMEOUT))?;
+  let reader = Deadline {
+    stream: &stream,
+    until: Instant::now() + REQUEST_DEADLINE,
+  };
+  let response = match read_request(&mut BufReader::new(reader)) {
+    Ok(request) => route(service, &request),
+    Err(error) => Response::error(400, &error.to_string()),
+  };
+  write_response(&mut stream, &response)
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+  let reason = match response.status {
+    200 => "OK",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    503 => "Service Unavailable",
+    _ => "Bad Request",
+  };
+  write!(
+    stream,
+    "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    response.status,
+    reason,
+    response.body.len(),
+    response.body
+  )?;
+  stream.flush()
+}
+
+// reads that each wait at most READ_TIMEOUT and together stop at the deadline
+struct Deadline<'a> {
+  stream: &'a TcpStream,
+  until: Instant,
+}
+
+impl Read for Deadline<'_> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let left = self.until.saturating_duration_since(Instant::now());
+    if left.is_zero() {
+      return Err(io::Error::new(io::ErrorKind::TimedOut, "request took too long"));
+    }
+    self.stream.set_read_timeout(Some(left.min(READ_TIMEOUT)))?;
+    self.stream.read(buf)
+  }
+}
+
+fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+  let mut line = String::new();
+  reader.read_line(&mut line)?;
+  let mut parts = line.split_whitespace();
+  let (method, path) = match (parts.next(), parts.next()) {
+    (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+    _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "bad request line")),
+  };
+
+  let mut length = 0;
+  loop {
+    let mut header = String::new();
+    if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+      break;
+    }
+    if let Some((name, value)) = header.split_once(':') {
+      if name.trim().eq_ignore_ascii_case("content-length") {
+        length = value.trim().parse().map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "bad length"))?;
+      }
+    }
+  }
+  if length > MAX_BODY {
+    return Err(io::Error::new(io::ErrorKind::InvalidData, "body too large"));
+  }
+  let mut body = vec![0; length];
+  reader.read_exact(&mut body)?;
+  Ok(Request {
+    method,
+    path,
+    body: String::from_utf8_lossy(&body).into_owned(),
+  })
+}
+
+pub fn route(service: &Service, request: &Request) -> Response {
+  let (path, query) = request.path.split_once('?').unwrap_or((&request.path, ""));
+  let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+  let result = match (request.method.as_str(), segments.as_slice()) {
+    ("GET", ["menu"]) => {
+      let lang = parse_form(query).into_iter().find(|(key, _)| key == "lang");
+      let locale = Locale::new(lang.as_ref().map_or(DEFAULT_LANGUAGE, |(_, lang)| lang.as_str()));
+      Ok(menu_json(service, &locale))
+    }
+    ("GET", ["session", token]) => session_json(service, token),
+    ("POST", ["session", token, "cart"]) => add_to_cart(service, token, &request.body),
+    ("DELETE", ["session", token, "cart", line]) => match line.parse() {
+      Ok(line) => service.with(|r| r.sessions.remove_from_cart(token, line)).and_then(|_| session_json(service, token)),
+      Err(_) => Err(RestaurantError::CartLineNotFound(usize::MAX)),
+    },
+    ("POST", ["session", token, "submit"]) => service
+      .with(|r| r.sessions.submit(token))
+      .map(|id| format!("{{\"submission\":{}}}", id)),
+    ("POST", ["session", token, "call-server"]) => guest_request(service, token, GuestRequest::CallServer),
+    ("POST", ["session", token, "request-check"]) => guest_request(service, token, GuestRequest::RequestCheck),
+    (_, ["menu"]) | (_, ["session", ..]) => return Response::error(405, "method not allowed"),
+    _ => return Response::error(404, "not found"),
+  };
+  match result {
+    Ok(body) => Response::ok(body),
+    Err(error) => error.into(),
+  }
+}
+
+fn add_to_cart(service: &Service, token: &str, body: &str) -> Result<String, RestaurantError> {
+  let form = parse_form(body);
+  let item = form
+    .iter()
+    .find(|(key, _)| key == "item")
+    .and_then(|(_, value)| value.parse().ok())
+    .ok_or(RestaurantError::MenuItemNotFound(usize::MAX))?;
+  let modifiers: Vec<&str> = form
+    .iter()
+    .filter(|(key, _)| key == "modifier")
+    .map(|(_, value)| value.as_str())
+    .collect();
+  service.with(|r| r.add_to_cart(token, item, &modifiers))?;
+  session_json(service, token)
+}
+
+fn guest_request(service: &Service, token: &str, request: GuestRequest) -> Result<String, RestaurantError> {
+  service.with(|r| r.sessions.request(token, request))?;
+  session_json(service, token)
+}
+
+fn menu_json(service: &Service, locale: &Locale) -> String {
+  service.with(|r| {
+    let items: Vec<String> = r
+      .menu
+      .items()
+      .iter()
+      .enumerate()
+      .map(|(index, item)| dish_json(&r.menu, locale, index, item.as_ref()))
+      .collect();
+    format!("[{}]", items.join(","))
+  })
+}
+
+fn dish_json(menu: &Menu, locale: &Locale, index: usize, item: &dyn MenuItem) -> String {
+  let allergens: Vec<String> = item.allergens().iter().map(|a| json_string(&format!("{:?}", a))).collect();
+  let modifiers: Vec<String> = item
+    .modifiers()
+    .iter()
+    .map(|m| format!("{{\"name\":{},\"price\":{}}}", json_string(&menu.name_in(&m.name, locale)), m.price))
+    .collect();
+  format!(
+    "{{\"item\":{},\"name\":{},\"description\":{},\"price\":{},\"course\":{},\"allergens\":[{}],\"modifiers\":[{}]}}",
+    index,
+    json_string(&menu.dish_in(item, locale)),
+    json_string(&menu.description_in(&item.name(), locale).unwrap_or_default()),
+    item.price(),
+    json_string(&format!("{:?}", item.course())),
+    allergens.join(","),
+    modifiers.join(",")
+  )
+}
+
+fn session_json(service: &Service, token: &str) -> Result<String, RestaurantError> {
+  service.with(|r| {
+    let session = r.sessions.get(token)?;
+    let cart: Vec<String> = session
+      .cart
+      .iter()
+      .enumerate()
+      .map(|(index, line)| {
+        format!("{{\"line\":{},\"item\":{},\"price\":{}}}", index, json_string(&line.item()), line.price())
+      })
+      .collect();
+    let requests: Vec<String> = session.requests.iter().map(|req| json_string(&format!("{:?}", req))).collect();
+    Ok(format!(
+      "{{\"table\":{},\"cart\":[{}],\"total\":{},\"requests\":[{}]}}",
+      session.table,
+      cart.join(","),
+      session.cart.iter().map(|line| line.price()).sum::<u32>(),
+      requests.join(",")
+    ))
+  })
+}
+
+pub fn json_string(text: &str) -> String {
+  let mut out = String::from("\"");
+  for c in text.chars() {
+    match c {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      '\r' => out.push_str("\\r"),
+      '\t' => out.push_str("\\t"),
+      c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+      c => out.push(c),
+    }
+  }
+  out.push('"');
+  out
+}
+
+// application/x-www-form-urlencoded, keys can repeat
+fn parse_form(body: &str) -> Vec<(String, String)> {
+  body
+    .split('&')
+    .filter(|pair| !pair.is_empty())
+    .map(|pair| {
+      let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+      (percent_decode(key), percent_decode(value))
+    })
+    .collect()
+}
+
+fn percent_decode(text: &str) -> String {
+  let bytes = text.as_bytes();
+  let mut out = Vec::new();
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'+' => out.push(b' '),
+      b'%' if i + 2 < bytes.len() => {
+        let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+          Some(byte) => {
+            out.push(byte);
+            i += 2;
+          }
+          None => out.push(b'%'),
+        }
+      }
+      byte => out.push(byte),
+    }
+    i += 1;
+  }
+  String::from_utf8_lossy(&out).into_owned()
+}
@@ -3,26 +3,58 @@
 // if we replace the semicolon with brackets, this is the same thing as saying, import ALL code from front_of_house.rs
 mod front_of_house;
 
-fn deliver_order() {}
+pub mod accounts;
+pub mod audit;
+pub mod customers;
+pub mod drawers;
+#[macro_use]
+pub mod menu;
+pub mod error;
+pub mod guest_api;
+pub mod locale;
+pub mod persistence;
+pub mod reports;
+pub mod scheduling;
+pub mod service;
+
+use crate::error::RestaurantError;
+use crate::front_of_house::serving::{Order, OrderStatus};
+use crate::locale::Locale;
+use crate::menu::Menu;
+
+// only food that came out of the kitchen can go to the table
+fn deliver_order(order: &mut Order) -> Result<(), RestaurantError> {
+    if order.status != OrderStatus::Ready {
+        return Err(RestaurantError::OrderNotReady(order.id));
+    }
+    order.status = OrderStatus::Delivered;
+    Ok(())
+}
 
 pub mod back_of_house;
 
 pub use crate::front_of_house::hosting; // create a namespace for hosting
+pub use crate::front_of_house::serving;
+pub use crate::front_of_house::table_sessions;
 use crate::front_of_house::hosting::add_to_waitlist; // this is also valid
+use crate::front_of_house::hosting::{Party, Waitlist};
 
 // mark this fn as public
-pub fn eat_at_restaurant() {
+pub fn eat_at_restaurant(locale: &Locale, menu: &Menu) {
+    let mut waitlist = Waitlist::new();
+
     // Absolute path:
-    crate::front_of_house::hosting::add_to_waitlist();
+    crate::front_of_house::hosting::add_to_waitlist(&mut waitlist, Party::new("Ada", 2));
 
     // Relative path:
-    front_of_house::hosting::add_to_waitlist();
+    front_of_house::hosting::add_to_waitlist(&mut waitlist, Party::new("Grace", 4));
 
     // order a breakfast
     let mut meal = back_of_house::Breakfast::summer("Rye");
     // change our mind about what bread we want
     meal.toast = String::from("Wheat");
-    println!("I'd like {} toast please", meal.toast);
+    // say it in the guest's language, with the dish's name from the menu
+    println!("{} {}", locale.label("order"), menu.dish_in(&meal, locale));
 
     // this line wont run bc seasonal_fruit is private
     // meal.seasonal_fruit = String::from("blueberries");
@@ -31,8 +63,8 @@ pub fn eat_at_restaurant() {
     let order2 = back_of_house::Appetizer::Salad;
 
     // we can directly use hosting because of the "use" keyword
-    hosting::add_to_waitlist();
+    hosting::add_to_waitlist(&mut waitlist, Party::new("Linus", 3));
 
     // we can also call this when this function is added to "use"
-    add_to_waitlist();
+    add_to_waitlist(&mut waitlist, Party::new("Barbara", 1));
 }
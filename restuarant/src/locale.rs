@@ -0,0 +1,272 @@
+// printing for guests who don't read English: which language to look for
+// first, and how money and dates are written where they come from
+use std::fmt;
+use std::str::FromStr;
+
+// the language everything is written in to begin with
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// a calendar day, enough for the top of a receipt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+  pub year: u32,
+  pub month: u32, // 1 to 12
+  pub day: u32,
+}
+
+impl Date {
+  pub fn new(year: u32, month: u32, day: u32) -> Date {
+    Date { year, month, day }
+  }
+}
+
+// the same layout as ISO 8601, for files and anywhere we don't know better
+impl fmt::Display for Date {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+  }
+}
+
+// what the restaurant charges in, whoever is reading the receipt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Currency {
+  #[default]
+  Dollar,
+  Euro,
+  Pound,
+}
+
+impl Currency {
+  pub fn symbol(&self) -> &'static str {
+    match self {
+      Currency::Dollar => "$",
+      Currency::Euro => "€",
+      Currency::Pound => "£",
+    }
+  }
+}
+
+// the ISO 4217 code, like "EUR"
+impl FromStr for Currency {
+  type Err = String;
+
+  fn from_str(code: &str) -> Result<Currency, String> {
+    match code {
+      "USD" => Ok(Currency::Dollar),
+      "EUR" => Ok(Currency::Euro),
+      "GBP" => Ok(Currency::Pound),
+      _ => Err(format!("unknown currency {}", code)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOrder {
+  MonthDayYear,
+  DayMonthYear,
+  YearMonthDay,
+}
+
+// how one place writes numbers, money and dates, the symbol itself comes from
+// the restaurant's currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Conventions {
+  decimal: char,
+  thousands: &'static str,
+  symbol_first: bool,
+  symbol_space: bool,
+  date_order: DateOrder,
+  date_separator: char,
+}
+
+const US: Conventions = Conventions {
+  decimal: '.',
+  thousands: ",",
+  symbol_first: true,
+  symbol_space: false,
+  date_order: DateOrder::MonthDayYear,
+  date_separator: '/',
+};
+
+const EURO: Conventions = Conventions {
+  decimal: ',',
+  thousands: "\u{a0}",
+  symbol_first: false,
+  symbol_space: true,
+  date_order: DateOrder::DayMonthYear,
+  date_separator: '/',
+};
+
+// language tags we know how to print for, a bare language means the place
+// most of our guests who speak it come from
+fn conventions(tag: &str) -> Option<Conventions> {
+  let conventions = match tag {
+    "en" | "en-US" => US,
+    "en-GB" => Conventions {
+      date_order: DateOrder::DayMonthYear,
+      ..US
+    },
+    "en-CA" => Conventions {
+      date_order: DateOrder::YearMonthDay,
+      date_separator: '-',
+      ..US
+    },
+    "fr" | "fr-FR" => EURO,
+    "fr-CA" => Conventions {
+      date_order: DateOrder::YearMonthDay,
+      date_separator: '-',
+      ..EURO
+    },
+    "de" | "de-DE" => Conventions {
+      thousands: ".",
+      date_separator: '.',
+      ..EURO
+    },
+    "es" | "es-ES" => Conventions {
+      thousands: ".",
+      ..EURO
+    },
+    "es-MX" => Conventions {
+      date_order: DateOrder::DayMonthYear,
+      ..US
+    },
+    "it" | "it-IT" => Conventions {
+      thousands: ".",
+      ..EURO
+    },
+    _ => return None,
+  };
+  Some(conventions)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+  pub tag: String,        // like "fr-CA"
+  fallbacks: Vec<String>, // tried in order once the tag and its language miss
+}
+
+impl Locale {
+  pub fn new(tag: &str) -> Locale {
+    Locale {
+      tag: String::from(tag),
+      fallbacks: Vec::new(),
+    }
+  }
+
+  // try this language before giving up and printing English, like Spanish
+  // for a Catalan speaker
+  pub fn fallback(mut self, tag: &str) -> Locale {
+    self.fallbacks.push(String::from(tag));
+    self
+  }
+
+  // every tag to look for a translation under, best match first:
+  // fr-CA, fr, whatever fallbacks were asked for, then English
+  pub fn chain(&self) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+    for tag in std::iter::once(&self.tag).chain(&self.fallbacks) {
+      let language = tag.split('-').next().unwrap_or(tag);
+      for tag in [tag.as_str(), language] {
+        if !chain.iter().any(|seen| seen == tag) {
+          chain.push(String::from(tag));
+        }
+      }
+    }
+    if !chain.iter().any(|seen| seen == DEFAULT_LANGUAGE) {
+      chain.push(String::from(DEFAULT_LANGUAGE));
+    }
+    chain
+  }
+
+  // the first text in the chain that exists
+  pub fn pick<'a>(&self, lookup: impl Fn(&str) -> Option<&'a str>) -> Option<&'a str> {
+    self.chain().iter().find_map(|tag| lookup(tag))
+  }
+
+  fn conventions(&self) -> Conventions {
+    self.chain().iter().find_map(|tag| conventions(tag)).unwrap_or(US)
+  }
+
+  // an amount in the restaurant's currency, written the way the guest would
+  pub fn format_money(&self, cents: u32, currency: Currency) -> String {
+    let conventions = self.conventions();
+    let dollars = (cents / 100).to_string();
+    // group the whole part in threes from the right
+    let mut whole = String::new();
+    for (i, digit) in dollars.chars().enumerate() {
+      if i > 0 && (dollars.len() - i).is_multiple_of(3) {
+        whole.push_str(conventions.thousands);
+      }
+      whole.push(digit);
+    }
+    let amount = format!("{}{}{:02}", whole, conventions.decimal, cents % 100);
+    let space = if conventions.symbol_space { "\u{a0}" } else { "" };
+    if conventions.symbol_first {
+      format!("{}{}{}", currency.symbol(), space, amount)
+    } else {
+      format!("{}{}{}", amount, space, currency.symbol())
+    }
+  }
+
+  pub fn format_date(&self, date: Date) -> String {
+    let conventions = self.conventions();
+    let sep = conventions.date_separator;
+    match conventions.date_order {
+      DateOrder::MonthDayYear => format!("{:02}{sep}{:02}{sep}{:04}", date.month, date.day, date.year),
+      DateOrder::DayMonthYear => format!("{:02}{sep}{:02}{sep}{:04}", date.day, date.month, date.year),
+      DateOrder::YearMonthDay => format!("{:04}{sep}{:02}{sep}{:02}", date.year, date.month, date.day),
+    }
+  }
+
+  // the fixed words on a receipt, falls back to the key itself
+  pub fn label(&self, key: &str) -> String {
+    self.pick(|tag| label(tag, key)).unwrap_or(key).to_string()
+  }
+}
+
+fn label(tag: &str, key: &str) -> Option<&'static str> {
+  let text = match (tag, key) {
+    ("en", "table") => "Table",
+    ("en", "check") => "Check",
+    ("en", "subtotal") => "Subtotal",
+    ("en", "discount") => "Discount",
+    ("en", "total") => "Total",
+    ("en", "paid") => "Paid",
+    ("en", "change") => "Change",
+    ("en", "balance") => "Balance due",
+    ("en", "thanks") => "Thank you!",
+    ("en", "order") => "I'd like",
+    ("fr", "table") => "Table",
+    ("fr", "check") => "Addition",
+    ("fr", "subtotal") => "Sous-total",
+    ("fr", "discount") => "Remise",
+    ("fr", "total") => "Total",
+    ("fr", "paid") => "Payé",
+    ("fr", "change") => "Monnaie rendue",
+    ("fr", "balance") => "Reste à payer",
+    ("fr", "thanks") => "Merci !",
+    ("fr", "order") => "Je voudrais",
+    ("es", "table") => "Mesa",
+    ("es", "check") => "Cuenta",
+    ("es", "subtotal") => "Subtotal",
+    ("es", "discount") => "Descuento",
+    ("es", "total") => "Total",
+    ("es", "paid") => "Pagado",
+    ("es", "change") => "Cambio",
+    ("es", "balance") => "Saldo pendiente",
+    ("es", "thanks") => "¡Gracias!",
+    ("es", "order") => "Quisiera",
+    ("de", "table") => "Tisch",
+    ("de", "check") => "Rechnung",
+    ("de", "subtotal") => "Zwischensumme",
+    ("de", "discount") => "Rabatt",
+    ("de", "total") => "Gesamt",
+    ("de", "paid") => "Bezahlt",
+    ("de", "change") => "Rückgeld",
+    ("de", "balance") => "Offener Betrag",
+    ("de", "thanks") => "Vielen Dank!",
+    ("de", "order") => "Ich hätte gern",
+    _ => return None,
+  };
+  Some(text)
+}
@@ -0,0 +1,427 @@
+// what every dish on the menu has in common, so one order can hold
+// breakfasts, appetizers and whatever we add next
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::back_of_house::Station;
+use crate::front_of_house::serving::{Course, OrderLine};
+use crate::locale::{Currency, Locale, DEFAULT_LANGUAGE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Allergen {
+  Gluten,
+  Dairy,
+  Egg,
+  Nut,
+  Soy,
+  Fish,
+  Shellfish,
+}
+
+// a change a guest can ask for, like extra bacon
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modifier {
+  pub name: String,
+  pub price: u32, // added to the dish, in cents
+}
+
+impl Modifier {
+  pub fn new(name: &str, price: u32) -> Modifier {
+    Modifier {
+      name: String::from(name),
+      price,
+    }
+  }
+}
+
+pub trait MenuItem: fmt::Debug + Send + Sync {
+  fn name(&self) -> String;
+  fn price(&self) -> u32; // in cents
+  fn station(&self) -> Station;
+  fn prep_minutes(&self) -> u32;
+
+  fn course(&self) -> Course {
+    Course::Main
+  }
+
+  fn allergens(&self) -> Vec<Allergen> {
+    Vec::new()
+  }
+
+  // the modifiers a guest can pick from
+  fn modifiers(&self) -> Vec<Modifier> {
+    Vec::new()
+  }
+
+  // what sets this plate apart from others of the same dish, like the toast
+  // on a breakfast. it's printed after the name, but recipes, costing, waste
+  // and translations only ever go by the name
+  fn details(&self) -> Option<String> {
+    None
+  }
+}
+
+// implement MenuItem in a few lines:
+//
+// menu_item!(Appetizer, |appetizer| {
+//     name: match appetizer {
+//         Appetizer::Soup => "Soup",
+//         Appetizer::Salad => "Salad",
+//     },
+//     price: 650,
+//     station: Station::Saute,
+//     prep_minutes: 6,
+//     course: Course::Appetizer,
+//     allergens: [Allergen::Dairy],
+//     modifiers: [("extra bread", 150)],
+// });
+//
+// every field is the body of the trait method of the same name. the name
+// between the bars is whatever you like to call the dish, it's bound to
+// &self in each of them so a field can match on the dish or read from it,
+// like Breakfast does for its details. name and details can be a &str or a
+// String. the fields have to come in this order, and course, allergens,
+// modifiers and details can be left out to keep the trait's defaults
+//
+// this is a macro_rules! macro and not #[derive(MenuItem)] because a derive
+// needs its own proc-macro crate, and the fields would have to go in
+// attributes anyway since a price or a station can't be worked out from the
+// struct
+#[macro_export]
+macro_rules! menu_item {
+  ($dish:ty, |$this:ident| {
+    name: $name:expr,
+    price: $price:expr,
+    station: $station:expr,
+    prep_minutes: $prep:expr
+    $(, course: $course:expr)?
+    $(, allergens: [$($allergen:expr),* $(,)?])?
+    $(, modifiers: [$(($modifier:expr, $modifier_price:expr)),* $(,)?])?
+    $(, details: $details:expr)?
+    $(,)?
+  }) => {
+    #[allow(unused_variables)]
+    impl $crate::menu::MenuItem for $dish {
+      fn name(&self) -> String {
+        let $this = self;
+        String::from($name)
+      }
+
+      fn price(&self) -> u32 {
+        let $this = self;
+        $price
+      }
+
+      fn station(&self) -> $crate::back_of_house::Station {
+        let $this = self;
+        $station
+      }
+
+      fn prep_minutes(&self) -> u32 {
+        let $this = self;
+        $prep
+      }
+
+      $(
+        fn course(&self) -> $crate::serving::Course {
+          let $this = self;
+          $course
+        }
+      )?
+
+      $(
+        fn allergens(&self) -> Vec<$crate::menu::Allergen> {
+          let $this = self;
+          vec![$($allergen),*]
+        }
+      )?
+
+      $(
+        fn modifiers(&self) -> Vec<$crate::menu::Modifier> {
+          let $this = self;
+          vec![$($crate::menu::Modifier::new($modifier, $modifier_price)),*]
+        }
+      )?
+
+      $(
+        fn details(&self) -> Option<String> {
+          let $this = self;
+          Some(String::from($details))
+        }
+      )?
+    }
+  };
+}
+
+// a dish that doesn't need its own type, like a side of toast
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dish {
+  pub name: String,
+  pub price: u32,
+  pub station: Station,
+  pub prep_minutes: u32,
+  pub course: Course,
+  pub allergens: Vec<Allergen>,
+  pub modifiers: Vec<Modifier>,
+}
+
+impl Dish {
+  // a main, cooked on the line
+  pub fn new(name: &str, price: u32) -> Dish {
+    Dish {
+      name: String::from(name),
+      price,
+      station: Station::Line,
+      prep_minutes: 0,
+      course: Course::Main,
+      allergens: Vec::new(),
+      modifiers: Vec::new(),
+    }
+  }
+
+  pub fn cooked_at(mut self, station: Station, prep_minutes: u32) -> Dish {
+    self.station = station;
+    self.prep_minutes = prep_minutes;
+    self
+  }
+
+  pub fn course(mut self, course: Course) -> Dish {
+    self.course = course;
+    self
+  }
+
+  pub fn allergen(mut self, allergen: Allergen) -> Dish {
+    self.allergens.push(allergen);
+    self
+  }
+
+  pub fn modifier(mut self, name: &str, price: u32) -> Dish {
+    self.modifiers.push(Modifier::new(name, price));
+    self
+  }
+}
+
+impl MenuItem for Dish {
+  fn name(&self) -> String {
+    self.name.clone()
+  }
+
+  fn price(&self) -> u32 {
+    self.price
+  }
+
+  fn station(&self) -> Station {
+    self.station
+  }
+
+  fn prep_minutes(&self) -> u32 {
+    self.prep_minutes
+  }
+
+  fn course(&self) -> Course {
+    self.course
+  }
+
+  fn allergens(&self) -> Vec<Allergen> {
+    self.allergens.clone()
+  }
+
+  fn modifiers(&self) -> Vec<Modifier> {
+    self.modifiers.clone()
+  }
+}
+
+// everything guests can order, in the order it's printed, with whatever
+// translations the menu file had for it
+#[derive(Debug, Clone, Default)]
+pub struct Menu {
+  items: Vec<Arc<dyn MenuItem>>,
+  texts: BTreeMap<(String, String, String), String>, // (item, field, language tag)
+  currency: Currency,                                // every price is in this
+}
+
+impl Menu {
+  pub fn new() -> Menu {
+    Menu::default()
+  }
+
+  pub fn add(&mut self, item: impl MenuItem + 'static) {
+    self.items.push(Arc::new(item));
+  }
+
+  pub fn items(&self) -> &[Arc<dyn MenuItem>] {
+    &self.items
+  }
+
+  pub fn get(&self, index: usize) -> Option<&Arc<dyn MenuItem>> {
+    self.items.get(index)
+  }
+
+  pub fn currency(&self) -> Currency {
+    self.currency
+  }
+
+  pub fn set_currency(&mut self, currency: Currency) {
+    self.currency = currency;
+  }
+
+  // field is "name" or "description", item is the English name of a dish or
+  // modifier
+  pub fn translate(&mut self, item: &str, field: &str, tag: &str, text: &str) {
+    let key = (String::from(item), String::from(field), String::from(tag));
+    self.texts.insert(key, String::from(text));
+  }
+
+  fn text(&self, item: &str, field: &str, locale: &Locale) -> Option<&str> {
+    locale.pick(|tag| {
+      self
+        .texts
+        .get(&(item.to_string(), field.to_string(), tag.to_string()))
+        .map(|text| text.as_str())
+    })
+  }
+
+  // the name as written in the menu when nobody translated it
+  pub fn name_in(&self, item: &str, locale: &Locale) -> String {
+    self.text(item, "name", locale).unwrap_or(item).to_string()
+  }
+
+  pub fn description_in(&self, item: &str, locale: &Locale) -> Option<String> {
+    self.text(item, "description", locale).map(String::from)
+  }
+
+  // the dish's name in the guest's language, followed by its details
+  pub fn dish_in(&self, dish: &dyn MenuItem, locale: &Locale) -> String {
+    let name = self.name_in(&dish.name(), locale);
+    match dish.details() {
+      Some(details) => format!("{}, {}", name, details),
+      None => name,
+    }
+  }
+
+  // OrderLine::item, in the guest's language
+  pub fn line_in(&self, line: &OrderLine, locale: &Locale) -> String {
+    let name = self.dish_in(line.dish.as_ref(), locale);
+    if line.modifiers.is_empty() {
+      return name;
+    }
+    let modifiers: Vec<String> = line.modifiers.iter().map(|modifier| self.name_in(&modifier.name, locale)).collect();
+    format!("{} ({})", name, modifiers.join(", "))
+  }
+
+  // reads a menu file, one section per dish after an optional currency
+  // (dollars if it's left out):
+  //
+  // currency = USD
+  //
+  // [Tomato Soup]
+  // price = 650
+  // station = Saute
+  // prep_minutes = 10
+  // course = Appetizer
+  // allergen = Dairy
+  // modifier = croutons, 50
+  // description = Made fresh every morning
+  // name.fr = Soupe à la tomate
+  // description.fr = Préparée chaque matin
+  //
+  // a section without a price only adds translations, for modifiers and for
+  // dishes that are written in code
+  pub fn load(path: &Path) -> io::Result<Menu> {
+    let text = fs::read_to_string(path)?;
+    let invalid = |number: usize, problem: &str| {
+      io::Error::new(
+        ErrorKind::InvalidData,
+        format!("{}: line {}: {}", path.display(), number + 1, problem),
+      )
+    };
+    let mut menu = Menu::new();
+    let mut section: Option<(String, Option<Dish>)> = None;
+    for (number, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+        if let Some((_, Some(dish))) = section.take() {
+          menu.add(dish);
+        }
+        section = Some((name.trim().to_string(), None));
+        continue;
+      }
+      let (key, value) = line.split_once('=').ok_or_else(|| invalid(number, "expected key = value"))?;
+      let (key, value) = (key.trim(), value.trim());
+      let Some((name, dish)) = section.as_mut() else {
+        if key != "currency" {
+          return Err(invalid(number, "a setting before the first [dish]"));
+        }
+        menu.currency = value.parse().map_err(|problem: String| invalid(number, &problem))?;
+        continue;
+      };
+
+      if let Some((field, tag)) = key.split_once('.') {
+        if field != "name" && field != "description" {
+          return Err(invalid(number, &format!("{} can't be translated", field)));
+        }
+        menu.translate(name, field, tag, value);
+        continue;
+      }
+      if key == "description" {
+        menu.translate(name, key, DEFAULT_LANGUAGE, value);
+        continue;
+      }
+      if key == "price" {
+        let price = value.parse().map_err(|_| invalid(number, "price should be in cents"))?;
+        *dish = Some(Dish::new(name, price));
+        continue;
+      }
+      let dish = dish.as_mut().ok_or_else(|| invalid(number, "the price has to come first"))?;
+      match key {
+        "station" => dish.station = parse_station(value).ok_or_else(|| invalid(number, "unknown station"))?,
+        "prep_minutes" => dish.prep_minutes = value.parse().map_err(|_| invalid(number, "bad prep time"))?,
+        "course" => dish.course = parse_course(value).ok_or_else(|| invalid(number, "unknown course"))?,
+        "allergen" => dish.allergens.push(parse_allergen(value).ok_or_else(|| invalid(number, "unknown allergen"))?),
+        "modifier" => {
+          let (modifier, price) = value.rsplit_once(',').ok_or_else(|| invalid(number, "expected name, price"))?;
+          let price = price.trim().parse().map_err(|_| invalid(number, "price should be in cents"))?;
+          dish.modifiers.push(Modifier::new(modifier.trim(), price));
+        }
+        _ => return Err(invalid(number, &format!("unknown setting {}", key))),
+      }
+    }
+    if let Some((_, Some(dish))) = section {
+      menu.add(dish);
+    }
+    Ok(menu)
+  }
+}
+
+fn parse_station(text: &str) -> Option<Station> {
+  [Station::Line, Station::Grill, Station::Fryer, Station::Saute, Station::Pantry, Station::Pastry]
+    .into_iter()
+    .find(|station| format!("{:?}", station) == text)
+}
+
+fn parse_course(text: &str) -> Option<Course> {
+  [Course::Appetizer, Course::Main, Course::Dessert]
+    .into_iter()
+    .find(|course| format!("{:?}", course) == text)
+}
+
+fn parse_allergen(text: &str) -> Option<Allergen> {
+  [
+    Allergen::Gluten,
+    Allergen::Dairy,
+    Allergen::Egg,
+    Allergen::Nut,
+    Allergen::Soy,
+    Allergen::Fish,
+    Allergen::Shellfish,
+  ]
+  .into_iter()
+  .find(|allergen| format!("{:?}", allergen) == text)
+}
@@ -0,0 +1,73 @@
+// saving restaurant records to plain text files, one record per line with
+// tab separated fields, so they are easy to read and fix by hand
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+pub trait Record: Sized {
+  fn to_fields(&self) -> Vec<String>;
+  fn from_fields(fields: &[&str]) -> Option<Self>;
+}
+
+pub fn save<T: Record>(path: &Path, records: &[T]) -> io::Result<()> {
+  let mut text = String::new();
+  for record in records {
+    let fields: Vec<String> = record.to_fields().iter().map(|field| escape(field)).collect();
+    text.push_str(&fields.join("\t"));
+    text.push('\n');
+  }
+  // write next to the real file first, so a crash never leaves half a file behind
+  let temp = path.with_extension("tmp");
+  fs::write(&temp, text)?;
+  fs::rename(&temp, path)
+}
+
+// a missing file just means nothing has been saved yet
+pub fn load<T: Record>(path: &Path) -> io::Result<Vec<T>> {
+  let text = match fs::read_to_string(path) {
+    Ok(text) => text,
+    Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(error) => return Err(error),
+  };
+  let mut records = Vec::new();
+  for (number, line) in text.lines().enumerate().filter(|(_, line)| !line.is_empty()) {
+    let fields: Vec<String> = line.split('\t').map(unescape).collect();
+    let fields: Vec<&str> = fields.iter().map(|field| field.as_str()).collect();
+    let record = T::from_fields(&fields).ok_or_else(|| {
+      io::Error::new(
+        ErrorKind::InvalidData,
+        format!("{}: line {} is not a valid record", path.display(), number + 1),
+      )
+    })?;
+    records.push(record);
+  }
+  Ok(records)
+}
+
+// tabs and line breaks inside a field would break the line format
+fn escape(field: &str) -> String {
+  field
+    .replace('\\', "\\\\")
+    .replace('\t', "\\t")
+    .replace('\n', "\\n")
+    .replace('\r', "\\r")
+}
+
+fn unescape(field: &str) -> String {
+  let mut out = String::new();
+  let mut chars = field.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      out.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('t') => out.push('\t'),
+      Some('n') => out.push('\n'),
+      Some('r') => out.push('\r'),
+      Some(other) => out.push(other),
+      None => out.push('\\'),
+    }
+  }
+  out
+}
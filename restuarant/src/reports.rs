@@ -0,0 +1,124 @@
+// the numbers the manager looks at after close
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::front_of_house::serving::{self, Order};
+use crate::locale::{Date, Locale};
+use crate::menu::Menu;
+use crate::service::Restaurant;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndOfDay {
+  pub orders: usize,
+  pub gross_sales: u32, // before discounts
+  pub discounts: u32,
+  pub net_sales: u32,
+  pub collected: u32,
+  pub outstanding: u32, // checks nobody has paid yet
+  pub tenders: BTreeMap<String, u32>,
+  pub tips: BTreeMap<String, u32>, // by server
+  pub remakes: u32,
+  pub waste_cost: f64,
+  pub waste_by_reason: BTreeMap<String, f64>,
+  pub drawers: Vec<(String, Option<i64>)>, // over/short, None while still open
+}
+
+pub fn end_of_day(restaurant: &Restaurant) -> EndOfDay {
+  let orders = &restaurant.orders;
+  let mut tenders = BTreeMap::new();
+  for payment in orders.iter().flat_map(|order| order.payments.iter()) {
+    *tenders.entry(payment.tender.kind().to_string()).or_insert(0) += payment.applied;
+  }
+  EndOfDay {
+    orders: orders.len(),
+    gross_sales: orders.iter().map(|order| order.subtotal()).sum(),
+    discounts: orders.iter().map(|order| order.subtotal() - order.total()).sum(),
+    net_sales: orders.iter().map(|order| order.total()).sum(),
+    collected: orders.iter().map(|order| order.paid()).sum(),
+    outstanding: orders.iter().map(|order| order.balance_due()).sum(),
+    tenders,
+    tips: serving::tips_by_server(orders),
+    remakes: orders.iter().map(|order| order.remakes).sum(),
+    waste_cost: restaurant.waste.total_cost(),
+    waste_by_reason: restaurant.waste.cost_by_reason(),
+    drawers: restaurant
+      .drawers
+      .iter()
+      .map(|drawer| (drawer.staff.clone(), drawer.closed.map(|count| count.variance())))
+      .collect(),
+  }
+}
+
+fn dollars(cents: f64) -> String {
+  format!("{:.2}", cents / 100.0)
+}
+
+impl fmt::Display for EndOfDay {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "END OF DAY")?;
+    writeln!(f, "orders        {:>10}", self.orders)?;
+    writeln!(f, "gross sales   {:>10}", dollars(self.gross_sales as f64))?;
+    writeln!(f, "discounts     {:>10}", dollars(self.discounts as f64))?;
+    writeln!(f, "net sales     {:>10}", dollars(self.net_sales as f64))?;
+    writeln!(f, "collected     {:>10}", dollars(self.collected as f64))?;
+    writeln!(f, "outstanding   {:>10}", dollars(self.outstanding as f64))?;
+    for (tender, amount) in &self.tenders {
+      writeln!(f, "  {:<12}{:>10}", tender, dollars(*amount as f64))?;
+    }
+    for (server, amount) in &self.tips {
+      writeln!(f, "tips {:<9}{:>10}", server, dollars(*amount as f64))?;
+    }
+    writeln!(f, "remakes       {:>10}", self.remakes)?;
+    writeln!(f, "waste         {:>10}", dollars(self.waste_cost))?;
+    for (reason, cost) in &self.waste_by_reason {
+      writeln!(f, "  {:<12}{:>10}", reason, dollars(*cost))?;
+    }
+    for (staff, variance) in &self.drawers {
+      match variance {
+        Some(variance) => writeln!(f, "drawer {:<7}{:>10}", staff, dollars(*variance as f64))?,
+        None => writeln!(f, "drawer {:<7}{:>10}", staff, "open")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+// width of a printed receipt, in characters
+const RECEIPT_WIDTH: usize = 40;
+
+fn receipt_line(out: &mut String, left: &str, right: &str) {
+  let width = RECEIPT_WIDTH.saturating_sub(right.chars().count() + 1);
+  out.push_str(&format!("{:<width$} {}\n", left, right, width = width));
+}
+
+// the guest's copy of the check, in their language and their way of writing
+// money and dates
+pub fn receipt(order: &Order, menu: &Menu, locale: &Locale, date: Date) -> String {
+  let money = |cents| locale.format_money(cents, menu.currency());
+  let mut out = String::new();
+  let heading = format!("{} {}, {} {}", locale.label("check"), order.id, locale.label("table"), order.table);
+  receipt_line(&mut out, &heading, &locale.format_date(date));
+  out.push_str(&format!("{}\n", "-".repeat(RECEIPT_WIDTH)));
+  for line in &order.lines {
+    receipt_line(&mut out, &menu.line_in(line, locale), &money(line.price()));
+  }
+  out.push_str(&format!("{}\n", "-".repeat(RECEIPT_WIDTH)));
+  receipt_line(&mut out, &locale.label("subtotal"), &money(order.subtotal()));
+  for discount in &order.discounts {
+    let label = format!("{} ({})", locale.label("discount"), discount.reason);
+    receipt_line(&mut out, &label, &format!("-{}", money(discount.amount)));
+  }
+  receipt_line(&mut out, &locale.label("total"), &money(order.total()));
+  for payment in &order.payments {
+    receipt_line(&mut out, &locale.label("paid"), &money(payment.applied + payment.change));
+    if payment.change > 0 {
+      receipt_line(&mut out, &locale.label("change"), &money(payment.change));
+    }
+  }
+  if order.balance_due() > 0 {
+    receipt_line(&mut out, &locale.label("balance"), &money(order.balance_due()));
+  }
+  out.push_str(&locale.label("thanks"));
+  out.push('\n');
+  out
+}
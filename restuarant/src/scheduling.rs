@@ -0,0 +1,412 @@
+// building the weekly roster from who can work when, and checking any roster,
+// built or edited by hand, against our labor rules
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::back_of_house::forecast::Weekday;
+use crate::error::RestaurantError;
+use crate::persistence::{self, Record};
+
+const HOURS_IN_WEEK: u32 = 7 * 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+  Host,
+  Server,
+  Cook,
+  Dishwasher,
+}
+
+impl Role {
+  pub const ALL: [Role; 4] = [Role::Host, Role::Server, Role::Cook, Role::Dishwasher];
+
+  fn parse(text: &str) -> Option<Role> {
+    Role::ALL.into_iter().find(|role| format!("{:?}", role) == text)
+  }
+}
+
+// hours are 0-24 on the clock, end is when they have to leave
+#[derive(Debug, Clone, PartialEq)]
+pub struct Availability {
+  pub weekday: Weekday,
+  pub start: u32,
+  pub end: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaffMember {
+  pub name: String,
+  pub roles: Vec<Role>,
+  pub max_hours: u32, // a week
+  pub availability: Vec<Availability>,
+}
+
+impl StaffMember {
+  pub fn new(name: &str, max_hours: u32) -> StaffMember {
+    StaffMember {
+      name: String::from(name),
+      roles: Vec::new(),
+      max_hours,
+      availability: Vec::new(),
+    }
+  }
+
+  pub fn role(mut self, role: Role) -> StaffMember {
+    self.roles.push(role);
+    self
+  }
+
+  pub fn available(mut self, weekday: Weekday, start: u32, end: u32) -> StaffMember {
+    self.availability.push(Availability { weekday, start, end });
+    self
+  }
+
+  pub fn is_available(&self, weekday: Weekday, start: u32, end: u32) -> bool {
+    self.availability
+      .iter()
+      .any(|slot| slot.weekday == weekday && slot.start <= start && end <= slot.end)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shift {
+  pub staff: String,
+  pub role: Role,
+  pub weekday: Weekday,
+  pub start: u32,
+  pub end: u32,
+}
+
+impl Shift {
+  pub fn hours(&self) -> u32 {
+    self.end.saturating_sub(self.start)
+  }
+
+  // hours since Monday midnight, so shifts on different days can be compared
+  fn week_start(&self) -> u32 {
+    self.weekday.index() * 24 + self.start
+  }
+
+  fn week_end(&self) -> u32 {
+    self.weekday.index() * 24 + self.end
+  }
+
+  fn covers(&self, weekday: Weekday, hour: u32) -> bool {
+    self.weekday == weekday && self.start <= hour && hour < self.end
+  }
+}
+
+impl Record for Shift {
+  fn to_fields(&self) -> Vec<String> {
+    vec![
+      self.staff.clone(),
+      format!("{:?}", self.role),
+      format!("{:?}", self.weekday),
+      self.start.to_string(),
+      self.end.to_string(),
+    ]
+  }
+
+  fn from_fields(fields: &[&str]) -> Option<Shift> {
+    match fields {
+      [staff, role, weekday, start, end] => Some(Shift {
+        staff: staff.to_string(),
+        role: Role::parse(role)?,
+        weekday: Weekday::parse(weekday)?,
+        start: start.parse().ok()?,
+        end: end.parse().ok()?,
+      }),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rules {
+  pub max_shift_hours: u32,
+  pub min_rest_hours: u32, // between the end of one shift and the start of the next
+  pub overtime_after: u32, // weekly hours before overtime pay kicks in
+  pub allow_overtime: bool,
+}
+
+impl Default for Rules {
+  fn default() -> Rules {
+    Rules {
+      max_shift_hours: 10,
+      min_rest_hours: 10,
+      overtime_after: 40,
+      allow_overtime: false,
+    }
+  }
+}
+
+// how many people in a role we need on for one hour
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+  pub weekday: Weekday,
+  pub hour: u32,
+  pub role: Role,
+  pub count: u32,
+}
+
+// turn forecast covers per hour into people per hour, `ratios` says how many
+// covers one person in that role can handle
+pub fn requirements_from_covers(
+  covers: &BTreeMap<(Weekday, u32), f64>,
+  ratios: &[(Role, f64)],
+) -> Result<Vec<Requirement>, RestaurantError> {
+  if let Some((role, _)) = ratios.iter().find(|(_, per_person)| !(*per_person > 0.0 && per_person.is_finite())) {
+    return Err(RestaurantError::InvalidStaffRatio(format!("{:?}", role)));
+  }
+  let mut requirements = Vec::new();
+  for ((weekday, hour), covers) in covers {
+    for (role, per_person) in ratios {
+      let count = (covers / per_person).ceil() as u32;
+      if count > 0 {
+        requirements.push(Requirement {
+          weekday: *weekday,
+          hour: *hour,
+          role: *role,
+          count,
+        });
+      }
+    }
+  }
+  Ok(requirements)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+  UnknownStaff(String),
+  BadHours(String, Weekday),
+  WrongRole(String, Role),
+  Unavailable(String, Weekday, u32, u32),
+  Overlap(String, Weekday),
+  ShiftTooLong(String, Weekday, u32),
+  NotEnoughRest(String, Weekday, u32),
+  OverMaxHours(String, u32, u32),
+  Overtime(String, u32),
+}
+
+impl fmt::Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Violation::UnknownStaff(name) => write!(f, "{} is not on the staff list", name),
+      Violation::BadHours(name, day) => write!(f, "{}'s {:?} shift doesn't have valid hours", name, day),
+      Violation::WrongRole(name, role) => write!(f, "{} doesn't work as a {:?}", name, role),
+      Violation::Unavailable(name, day, start, end) => {
+        write!(f, "{} isn't available {:?} {}:00-{}:00", name, day, start, end)
+      }
+      Violation::Overlap(name, day) => write!(f, "{} has overlapping shifts on {:?}", name, day),
+      Violation::ShiftTooLong(name, day, hours) => write!(f, "{}'s {:?} shift is {} hours long", name, day, hours),
+      Violation::NotEnoughRest(name, day, hours) => {
+        write!(f, "{} only gets {} hours of rest before {:?}", name, hours, day)
+      }
+      Violation::OverMaxHours(name, hours, max) => write!(f, "{} is scheduled {} hours, max is {}", name, hours, max),
+      Violation::Overtime(name, hours) => write!(f, "{} would work {} hours of overtime", name, hours),
+    }
+  }
+}
+
+// everything wrong with one person's shifts
+fn check_staff(member: &StaffMember, shifts: &[&Shift], rules: &Rules) -> Vec<Violation> {
+  let name = &member.name;
+  let mut violations = Vec::new();
+  let mut sorted: Vec<&Shift> = shifts.to_vec();
+  sorted.sort_by_key(|shift| shift.week_start());
+
+  for shift in &sorted {
+    if shift.start >= shift.end || shift.end > 24 {
+      violations.push(Violation::BadHours(name.clone(), shift.weekday));
+      continue;
+    }
+    if !member.roles.contains(&shift.role) {
+      violations.push(Violation::WrongRole(name.clone(), shift.role));
+    }
+    if !member.is_available(shift.weekday, shift.start, shift.end) {
+      violations.push(Violation::Unavailable(name.clone(), shift.weekday, shift.start, shift.end));
+    }
+    if shift.hours() > rules.max_shift_hours {
+      violations.push(Violation::ShiftTooLong(name.clone(), shift.weekday, shift.hours()));
+    }
+  }
+  for pair in sorted.windows(2) {
+    let (before, after) = (pair[0], pair[1]);
+    if after.week_start() < before.week_end() {
+      violations.push(Violation::Overlap(name.clone(), after.weekday));
+    } else if after.week_start() - before.week_end() < rules.min_rest_hours {
+      let rest = after.week_start() - before.week_end();
+      violations.push(Violation::NotEnoughRest(name.clone(), after.weekday, rest));
+    }
+  }
+  // the roster repeats, so Sunday's close comes right before Monday's open
+  if let [first, .., last] = sorted[..] {
+    if let Some(rest) = (first.week_start() + HOURS_IN_WEEK).checked_sub(last.week_end()) {
+      if rest < rules.min_rest_hours {
+        violations.push(Violation::NotEnoughRest(name.clone(), first.weekday, rest));
+      }
+    }
+  }
+
+  let hours: u32 = sorted.iter().map(|shift| shift.hours()).sum();
+  if hours > member.max_hours {
+    violations.push(Violation::OverMaxHours(name.clone(), hours, member.max_hours));
+  }
+  if !rules.allow_overtime && hours > rules.overtime_after {
+    violations.push(Violation::Overtime(name.clone(), hours - rules.overtime_after));
+  }
+  violations
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schedule {
+  pub shifts: Vec<Shift>,
+}
+
+impl Schedule {
+  pub fn new() -> Schedule {
+    Schedule::default()
+  }
+
+  pub fn hours_for(&self, staff: &str) -> u32 {
+    self.shifts.iter().filter(|shift| shift.staff == staff).map(|shift| shift.hours()).sum()
+  }
+
+  // how many people are on in this role at this hour
+  pub fn staffed(&self, weekday: Weekday, hour: u32, role: Role) -> u32 {
+    self.shifts
+      .iter()
+      .filter(|shift| shift.role == role && shift.covers(weekday, hour))
+      .count() as u32
+  }
+
+  // every rule the roster breaks, run this after editing it by hand
+  pub fn validate(&self, staff: &[StaffMember], rules: &Rules) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = self
+      .shifts
+      .iter()
+      .filter(|shift| !staff.iter().any(|member| member.name == shift.staff))
+      .map(|shift| Violation::UnknownStaff(shift.staff.clone()))
+      .collect();
+    violations.dedup();
+    for member in staff {
+      let shifts: Vec<&Shift> = self.shifts.iter().filter(|shift| shift.staff == member.name).collect();
+      violations.extend(check_staff(member, &shifts, rules));
+    }
+    violations
+  }
+
+  // the hours we still need people for, with how many are missing
+  pub fn unfilled(&self, requirements: &[Requirement]) -> Vec<Requirement> {
+    requirements
+      .iter()
+      .filter_map(|requirement| {
+        let staffed = self.staffed(requirement.weekday, requirement.hour, requirement.role);
+        (staffed < requirement.count).then(|| Requirement {
+          count: requirement.count - staffed,
+          ..requirement.clone()
+        })
+      })
+      .collect()
+  }
+
+  // who goes past the overtime line, and by how many hours
+  pub fn overtime(&self, rules: &Rules) -> Vec<(String, u32)> {
+    let mut hours: BTreeMap<&str, u32> = BTreeMap::new();
+    for shift in &self.shifts {
+      *hours.entry(shift.staff.as_str()).or_insert(0) += shift.hours();
+    }
+    hours
+      .into_iter()
+      .filter(|(_, hours)| *hours > rules.overtime_after)
+      .map(|(staff, hours)| (staff.to_string(), hours - rules.overtime_after))
+      .collect()
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    persistence::save(path, &self.shifts)
+  }
+
+  pub fn load(path: &Path) -> io::Result<Schedule> {
+    Ok(Schedule {
+      shifts: persistence::load(path)?,
+    })
+  }
+}
+
+// fill the requirements hour by hour: stretch someone's shift if they are
+// already on, otherwise start a new one with whoever has the fewest hours,
+// and only ever make changes that pass the same rules as `validate`
+pub fn build_schedule(staff: &[StaffMember], requirements: &[Requirement], rules: &Rules) -> Schedule {
+  let mut schedule = Schedule::new();
+  let mut requirements = requirements.to_vec();
+  requirements.sort_by_key(|requirement| (requirement.weekday, requirement.hour, requirement.role));
+
+  for requirement in &requirements {
+    let (weekday, hour, role) = (requirement.weekday, requirement.hour, requirement.role);
+    while schedule.staffed(weekday, hour, role) < requirement.count {
+      let mut best: Option<(bool, u32, usize, Vec<Shift>)> = None;
+      for (index, member) in staff.iter().enumerate() {
+        if !member.roles.contains(&role) || !member.is_available(weekday, hour, hour + 1) {
+          continue;
+        }
+        let Some((extended, shifts)) = try_assign(&schedule, member, weekday, hour, role, rules) else {
+          continue;
+        };
+        let hours = schedule.hours_for(&member.name);
+        // extensions first, then whoever has worked the least
+        let better = match &best {
+          None => true,
+          Some((best_extended, best_hours, _, _)) => (!extended, hours) < (!best_extended, *best_hours),
+        };
+        if better {
+          best = Some((extended, hours, index, shifts));
+        }
+      }
+      match best {
+        Some((_, _, index, shifts)) => {
+          schedule.shifts.retain(|shift| shift.staff != staff[index].name);
+          schedule.shifts.extend(shifts);
+        }
+        None => break, // nobody can take it, it shows up in unfilled()
+      }
+    }
+  }
+  schedule.shifts.sort_by_key(|shift| (shift.weekday, shift.start, shift.role));
+  schedule
+}
+
+// this member's shifts with one more hour added, or None if that breaks a rule
+fn try_assign(
+  schedule: &Schedule,
+  member: &StaffMember,
+  weekday: Weekday,
+  hour: u32,
+  role: Role,
+  rules: &Rules,
+) -> Option<(bool, Vec<Shift>)> {
+  let mut shifts: Vec<Shift> = schedule.shifts.iter().filter(|shift| shift.staff == member.name).cloned().collect();
+  let extended = match shifts
+    .iter_mut()
+    .find(|shift| shift.weekday == weekday && shift.role == role && shift.end == hour)
+  {
+    Some(shift) => {
+      shift.end += 1;
+      true
+    }
+    None => {
+      shifts.push(Shift {
+        staff: member.name.clone(),
+        role,
+        weekday,
+        start: hour,
+        end: hour + 1,
+      });
+      false
+    }
+  };
+  let refs: Vec<&Shift> = shifts.iter().collect();
+  check_staff(member, &refs, rules).is_empty().then_some((extended, shifts))
+}
@@ -0,0 +1,355 @@
+// the whole restaurant behind one lock, so hosts, servers and the kitchen
+// can all work on it from their own threads
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::accounts::{GiftCards, HouseAccounts};
+use crate::audit::{Action, AuditTrail};
+use crate::back_of_house::costing::CostBook;
+use crate::back_of_house::inventory::Inventory;
+use crate::back_of_house::stations::StationTicket;
+use crate::back_of_house::waste::{WasteLog, WasteReason, Wasted};
+use crate::back_of_house::{self, Kitchen, Station, Ticket};
+use crate::customers::{self, Customers};
+use crate::drawers::Drawers;
+use crate::error::RestaurantError;
+use crate::front_of_house::hosting::{self, Floor, Party, Reservations, TableStatus, Waitlist};
+use crate::front_of_house::serving::{self, Course, Discount, OrderLine, Orders, Payment, Tender};
+use crate::front_of_house::table_sessions::TableSessions;
+use crate::menu::Menu;
+
+// everything the staff share during service
+#[derive(Debug, Default)]
+pub struct Restaurant {
+  pub waitlist: Waitlist,
+  pub floor: Floor,
+  pub orders: Orders,
+  pub kitchen: Kitchen,
+  pub gift_cards: GiftCards,
+  pub house_accounts: HouseAccounts,
+  pub customers: Customers,
+  pub reservations: Reservations,
+  pub drawers: Drawers,
+  pub inventory: Inventory,
+  pub cost_book: CostBook,
+  pub waste: WasteLog,
+  pub menu: Menu,
+  pub sessions: TableSessions,
+  pub audit: AuditTrail,
+}
+
+impl Restaurant {
+  pub fn new(floor: Floor) -> Restaurant {
+    Restaurant {
+      floor,
+      ..Restaurant::default()
+    }
+  }
+
+  pub fn add_to_waitlist(&mut self, party: Party) {
+    hosting::add_to_waitlist(&mut self.waitlist, party);
+  }
+
+  pub fn check_in(&mut self, name: &str) -> Result<(), RestaurantError> {
+    hosting::check_in(&mut self.reservations, &mut self.waitlist, name)
+  }
+
+  pub fn seat_at_table(&mut self, table: u32) -> Result<Party, RestaurantError> {
+    let table = self.floor.table_mut(table)?;
+    hosting::seat_at_table(&mut self.waitlist, table)
+  }
+
+  // the party left, so their phones can't order for the next one
+  pub fn clear_table(&mut self, table: u32) -> Result<Party, RestaurantError> {
+    let party = hosting::clear_table(self.floor.table_mut(table)?)?;
+    self.sessions.close(table);
+    Ok(party)
+  }
+
+  // a session token for guests to order from their own devices
+  pub fn open_session(&mut self, table: u32) -> Result<String, RestaurantError> {
+    if self.floor.table(table)?.status != TableStatus::Seated {
+      return Err(RestaurantError::TableNotSeated(table));
+    }
+    Ok(self.sessions.open(table))
+  }
+
+  pub fn add_to_cart(&mut self, token: &str, menu_item: usize, modifiers: &[&str]) -> Result<(), RestaurantError> {
+    let dish = self.menu.get(menu_item).ok_or(RestaurantError::MenuItemNotFound(menu_item))?;
+    let mut line = OrderLine::shared(dish.clone());
+    for modifier in modifiers {
+      line = line.modifier(modifier)?;
+    }
+    self.sessions.add_to_cart(token, line)
+  }
+
+  // the server looked over what the guests sent and rings it in
+  pub fn approve_submission(&mut self, id: u32) -> Result<u32, RestaurantError> {
+    let submission = self
+      .sessions
+      .pending()
+      .find(|submission| submission.id == id)
+      .ok_or(RestaurantError::SubmissionNotFound(id))?;
+    let order_id = self.take_order(submission.table, submission.lines.clone())?;
+    self.sessions.take_submission(id)?;
+    Ok(order_id)
+  }
+
+  pub fn reject_submission(&mut self, id: u32) -> Result<(), RestaurantError> {
+    self.sessions.take_submission(id).map(|_| ())
+  }
+
+  // only seated tables can order, the check goes to the regular at the table
+  pub fn take_order(&mut self, table: u32, lines: Vec<OrderLine>) -> Result<u32, RestaurantError> {
+    if lines.is_empty() {
+      return Err(RestaurantError::EmptyOrder);
+    }
+    let table = self.floor.table(table)?;
+    let customer = match (&table.party, table.status) {
+      (Some(party), TableStatus::Seated) => party.customer,
+      _ => return Err(RestaurantError::TableNotSeated(table.number)),
+    };
+    let server = table.server.clone();
+    let id = serving::take_order(&mut self.orders, &mut self.kitchen, table.number, lines)?;
+    let order = self.orders.get_mut(id)?;
+    order.customer = customer;
+    order.server = server;
+    Ok(id)
+  }
+
+  // put a table in someone's section, before anyone sits there
+  pub fn assign_server(&mut self, table: u32, server: &str) -> Result<(), RestaurantError> {
+    self.floor.table_mut(table)?.server = Some(server.to_string());
+    Ok(())
+  }
+
+  // another server takes over the table along with every check on it that
+  // isn't paid off yet, tips left from now on are theirs
+  pub fn transfer_table(&mut self, staff: &str, table: u32, server: &str) -> Result<(), RestaurantError> {
+    let table = self.floor.table_mut(table)?;
+    let from = table.server.replace(server.to_string());
+    let number = table.number;
+    let open: Vec<u32> = self
+      .orders
+      .iter()
+      .filter(|order| order.table == number && order.balance_due() > 0)
+      .map(|order| order.id)
+      .collect();
+    for id in open {
+      self.orders.get_mut(id)?.server = Some(server.to_string());
+    }
+    let to = server.to_string();
+    self.audit.record(staff, Action::TransferTable { table: number, from, to });
+    Ok(())
+  }
+
+  // the party moved tables, the check goes to the new table's server
+  pub fn transfer_order(&mut self, staff: &str, order_id: u32, table: u32) -> Result<(), RestaurantError> {
+    let target = self.floor.table(table)?;
+    if target.status != TableStatus::Seated {
+      return Err(RestaurantError::TableNotSeated(table));
+    }
+    let server = target.server.clone();
+    let from = serving::transfer_order(&mut self.orders, order_id, table)?;
+    if server.is_some() {
+      self.orders.get_mut(order_id)?.server = server;
+    }
+    self.kitchen.move_order(order_id, table);
+    self.audit.record(staff, Action::TransferOrder { order: order_id, from, to: table });
+    Ok(())
+  }
+
+  pub fn merge_checks(&mut self, staff: &str, from: u32, into: u32) -> Result<(), RestaurantError> {
+    serving::merge_checks(&mut self.orders, from, into)?;
+    self.audit.record(staff, Action::MergeChecks { from, into });
+    Ok(())
+  }
+
+  // returns the new check
+  pub fn split_check(&mut self, staff: &str, order_id: u32, lines: &[usize]) -> Result<u32, RestaurantError> {
+    let new_order = serving::split_check(&mut self.orders, order_id, lines)?;
+    let lines = self.orders.get(new_order)?.lines.len();
+    self.audit.record(staff, Action::SplitCheck { order: order_id, new_order, lines });
+    Ok(new_order)
+  }
+
+  pub fn add_tip(&mut self, order_id: u32, amount: u32) -> Result<(), RestaurantError> {
+    serving::add_tip(&mut self.orders, order_id, amount)
+  }
+
+  pub fn fire_course(&mut self, order_id: u32, course: Course) -> Result<(), RestaurantError> {
+    serving::fire_course(&mut self.orders, &mut self.kitchen, order_id, course)
+  }
+
+  pub fn bump_ticket(&mut self) -> Option<Ticket> {
+    back_of_house::cook_order(&mut self.orders, &mut self.kitchen)
+  }
+
+  pub fn bump_station(&mut self, station: Station) -> Option<StationTicket> {
+    self.kitchen.bump_station(station)
+  }
+
+  pub fn expedite(&mut self) -> Option<Ticket> {
+    back_of_house::expedite(&mut self.orders, &mut self.kitchen)
+  }
+
+  pub fn serve_order(&mut self, order_id: u32) -> Result<(), RestaurantError> {
+    serving::serve_order(&mut self.orders, order_id)
+  }
+
+  // the plates that came back are logged as waste before the remake goes
+  // in, so when the stock can't cover them nothing is sent or logged
+  pub fn fix_incorrect_order(&mut self, staff: &str, order_id: u32) -> Result<(), RestaurantError> {
+    let ticket = back_of_house::remake_ticket(self.orders.get(order_id)?)?;
+    let mut plates: Vec<(Wasted, f64)> = Vec::new();
+    for line in &ticket.lines {
+      let wasted = Wasted::Item(line.dish.name());
+      match plates.iter_mut().find(|(dish, _)| *dish == wasted) {
+        Some((_, count)) => *count += 1.0,
+        None => plates.push((wasted, 1.0)),
+      }
+    }
+    self.waste.log_all(plates, WasteReason::Remake, staff, &self.cost_book, &mut self.inventory)?;
+    back_of_house::fix_incorrect_order(&mut self.orders, &mut self.kitchen, order_id)?;
+    Ok(())
+  }
+
+  pub fn log_waste(&mut self, wasted: Wasted, quantity: f64, reason: WasteReason, staff: &str) -> Result<(), RestaurantError> {
+    self.waste.log(wasted, quantity, reason, staff, &self.cost_book, &mut self.inventory)?;
+    Ok(())
+  }
+
+  // cash goes into the drawer of whoever took it, and once the check is paid
+  // off a regular gets the visit and points on their profile
+  pub fn take_payment(&mut self, staff: &str, order_id: u32, tender: Tender) -> Result<Payment, RestaurantError> {
+    if let Tender::Cash(_) = tender {
+      if self.drawers.open_drawer(staff).is_none() {
+        return Err(RestaurantError::NoOpenDrawer(staff.to_string()));
+      }
+    }
+    let payment = serving::take_payment(
+      &mut self.orders,
+      &mut self.gift_cards,
+      &mut self.house_accounts,
+      &mut self.customers,
+      order_id,
+      tender,
+    )?;
+    if let Tender::Cash(_) = payment.tender {
+      self.drawers.record_sale(staff, order_id, payment.applied)?;
+    }
+    let order = self.orders.get(order_id)?;
+    if let (Some(customer), 0) = (order.customer, order.balance_due()) {
+      self.customers.record_visit(customer, order)?;
+    }
+    Ok(payment)
+  }
+
+  // spend the regular's points as money off their check
+  pub fn redeem_points(&mut self, order_id: u32, points: u32) -> Result<(), RestaurantError> {
+    let order = self.orders.get(order_id)?;
+    let customer = order.customer.ok_or(RestaurantError::NoCustomerOnOrder(order_id))?;
+    let amount = customers::points_value(points).ok_or(RestaurantError::TooManyPoints(points))?;
+    if amount > order.balance_due() {
+      return Err(RestaurantError::Overtendered(order_id));
+    }
+    self.customers.redeem_points(customer, points)?;
+    let discount = Discount {
+      reason: format!("{} loyalty points", points),
+      amount,
+    };
+    serving::apply_discount(&mut self.orders, order_id, discount)
+  }
+}
+
+// a cheap handle to the shared restaurant, clone one for every thread
+#[derive(Debug, Clone, Default)]
+pub struct Service {
+  state: Arc<Mutex<Restaurant>>,
+}
+
+impl Service {
+  pub fn new(restaurant: Restaurant) -> Service {
+    Service {
+      state: Arc::new(Mutex::new(restaurant)),
+    }
+  }
+
+  // hold the lock for the whole closure, so several steps happen as one update
+  pub fn with<T>(&self, f: impl FnOnce(&mut Restaurant) -> T) -> T {
+    f(&mut self.lock())
+  }
+
+  pub fn add_to_waitlist(&self, party: Party) {
+    self.lock().add_to_waitlist(party);
+  }
+
+  pub fn seat_at_table(&self, table: u32) -> Result<Party, RestaurantError> {
+    self.lock().seat_at_table(table)
+  }
+
+  pub fn take_order(&self, table: u32, lines: Vec<OrderLine>) -> Result<u32, RestaurantError> {
+    self.lock().take_order(table, lines)
+  }
+
+  pub fn fire_course(&self, order_id: u32, course: Course) -> Result<(), RestaurantError> {
+    self.lock().fire_course(order_id, course)
+  }
+
+  pub fn bump_ticket(&self) -> Option<Ticket> {
+    self.lock().bump_ticket()
+  }
+
+  pub fn bump_station(&self, station: Station) -> Option<StationTicket> {
+    self.lock().bump_station(station)
+  }
+
+  pub fn expedite(&self) -> Option<Ticket> {
+    self.lock().expedite()
+  }
+
+  pub fn serve_order(&self, order_id: u32) -> Result<(), RestaurantError> {
+    self.lock().serve_order(order_id)
+  }
+
+  pub fn fix_incorrect_order(&self, staff: &str, order_id: u32) -> Result<(), RestaurantError> {
+    self.lock().fix_incorrect_order(staff, order_id)
+  }
+
+  pub fn log_waste(&self, wasted: Wasted, quantity: f64, reason: WasteReason, staff: &str) -> Result<(), RestaurantError> {
+    self.lock().log_waste(wasted, quantity, reason, staff)
+  }
+
+  pub fn take_payment(&self, staff: &str, order_id: u32, tender: Tender) -> Result<Payment, RestaurantError> {
+    self.lock().take_payment(staff, order_id, tender)
+  }
+
+  pub fn redeem_points(&self, order_id: u32, points: u32) -> Result<(), RestaurantError> {
+    self.lock().redeem_points(order_id, points)
+  }
+
+  pub fn transfer_table(&self, staff: &str, table: u32, server: &str) -> Result<(), RestaurantError> {
+    self.lock().transfer_table(staff, table, server)
+  }
+
+  pub fn transfer_order(&self, staff: &str, order_id: u32, table: u32) -> Result<(), RestaurantError> {
+    self.lock().transfer_order(staff, order_id, table)
+  }
+
+  pub fn merge_checks(&self, staff: &str, from: u32, into: u32) -> Result<(), RestaurantError> {
+    self.lock().merge_checks(staff, from, into)
+  }
+
+  pub fn split_check(&self, staff: &str, order_id: u32, lines: &[usize]) -> Result<u32, RestaurantError> {
+    self.lock().split_check(staff, order_id, lines)
+  }
+
+  pub fn add_tip(&self, order_id: u32, amount: u32) -> Result<(), RestaurantError> {
+    self.lock().add_tip(order_id, amount)
+  }
+
+  // a thread that panicked mid-update can't leave the state half-written,
+  // every update above is a single call, so keep serving
+  fn lock(&self) -> MutexGuard<'_, Restaurant> {
+    self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+}
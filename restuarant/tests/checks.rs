@@ -0,0 +1,194 @@
+use std::env;
+use std::fs;
+
+use restuarant::audit::{Action, AuditTrail};
+use restuarant::customers;
+use restuarant::back_of_house::Station;
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Dish;
+use restuarant::reports;
+use restuarant::serving::{Course, OrderLine, OrderStatus, Tender};
+use restuarant::service::Restaurant;
+
+fn soup() -> OrderLine {
+    OrderLine::new(Dish::new("Soup", 650).course(Course::Appetizer))
+}
+
+fn steak() -> OrderLine {
+    OrderLine::new(Dish::new("Steak", 2400).cooked_at(Station::Grill, 15))
+}
+
+// tables 1 and 2 in Sam's section, 3 in Kit's, all seated
+fn seated() -> Restaurant {
+    let floor = Floor::new((1..=3).map(|number| Table::new(number, 4)).collect());
+    let mut restaurant = Restaurant::new(floor);
+    for (table, server) in [(1, "Sam"), (2, "Sam"), (3, "Kit")] {
+        restaurant.assign_server(table, server).unwrap();
+        restaurant.add_to_waitlist(Party::new(&format!("party {}", table), 2));
+        restaurant.seat_at_table(table).unwrap();
+    }
+    restaurant
+}
+
+fn cook_and_serve(restaurant: &mut Restaurant) {
+    while let Some(ticket) = restaurant.bump_ticket() {
+        restaurant.serve_order(ticket.order_id).unwrap();
+    }
+}
+
+#[test]
+fn an_order_follows_its_party_to_a_new_table() {
+    let mut restaurant = seated();
+    let id = restaurant.take_order(1, vec![steak()]).unwrap();
+    assert_eq!(restaurant.orders.get(id).unwrap().server.as_deref(), Some("Sam"));
+
+    restaurant.transfer_order("Ana", id, 3).unwrap();
+    let order = restaurant.orders.get(id).unwrap();
+    assert_eq!((order.table, order.server.as_deref()), (3, Some("Kit")));
+    assert_eq!(restaurant.kitchen.tickets().next().unwrap().table, 3, "the food goes to the new table");
+    assert_eq!(restaurant.kitchen.expo()[0].table, 3);
+
+    restaurant.clear_table(2).unwrap();
+    assert_eq!(restaurant.transfer_order("Ana", id, 2), Err(RestaurantError::TableNotSeated(2)));
+    assert_eq!(
+        restaurant.audit.entries()[0].action,
+        Action::TransferOrder { order: id, from: 1, to: 3 }
+    );
+}
+
+#[test]
+fn two_tables_can_share_one_check() {
+    let mut restaurant = seated();
+    let first = restaurant.take_order(1, vec![soup(), steak()]).unwrap();
+    let second = restaurant.take_order(2, vec![steak()]).unwrap();
+    assert_eq!(restaurant.merge_checks("Sam", second, first), Err(RestaurantError::OrderInKitchen(second)));
+
+    cook_and_serve(&mut restaurant);
+    // table 1 is between courses, table 2 already has its mains
+    assert_eq!(
+        restaurant.merge_checks("Sam", second, first),
+        Err(RestaurantError::CoursesOutOfStep(second, first))
+    );
+    restaurant.fire_course(first, Course::Main).unwrap();
+    cook_and_serve(&mut restaurant);
+
+    restaurant.take_payment("Sam", second, Tender::Card(1000)).unwrap();
+    restaurant.merge_checks("Sam", second, first).unwrap();
+    assert!(restaurant.orders.get(second).is_err());
+    let merged = restaurant.orders.get(first).unwrap();
+    assert_eq!(merged.lines.len(), 3);
+    assert_eq!(merged.total(), 650 + 2400 * 2);
+    assert_eq!(merged.balance_due(), merged.total() - 1000);
+    assert_eq!(merged.status, OrderStatus::Delivered);
+    assert_eq!(restaurant.merge_checks("Sam", first, first), Err(RestaurantError::SameCheck(first)));
+}
+
+#[test]
+fn a_table_can_split_its_check_mid_meal() {
+    let mut restaurant = seated();
+    let id = restaurant.take_order(1, vec![soup(), steak(), steak()]).unwrap();
+    cook_and_serve(&mut restaurant);
+
+    assert_eq!(restaurant.split_check("Sam", id, &[0, 1, 2]), Err(RestaurantError::InvalidSplit(id)));
+    assert_eq!(restaurant.split_check("Sam", id, &[7]), Err(RestaurantError::InvalidSplit(id)));
+    let other = restaurant.split_check("Sam", id, &[2]).unwrap();
+
+    let (order, split) = (restaurant.orders.get(id).unwrap(), restaurant.orders.get(other).unwrap());
+    assert_eq!((order.subtotal(), split.subtotal()), (650 + 2400, 2400));
+    assert_eq!(split.server.as_deref(), Some("Sam"));
+    assert_eq!(split.held_courses(), vec![Course::Main]);
+
+    // the mains still come out together for both checks
+    restaurant.fire_course(id, Course::Main).unwrap();
+    restaurant.fire_course(other, Course::Main).unwrap();
+    cook_and_serve(&mut restaurant);
+    restaurant.take_payment("Sam", other, Tender::Card(2400)).unwrap();
+    assert_eq!(restaurant.split_check("Sam", other, &[0]), Err(RestaurantError::CheckHasPayments(other)));
+    assert!(matches!(restaurant.audit.entries()[0].action, Action::SplitCheck { lines: 1, .. }));
+}
+
+#[test]
+fn regulars_keep_their_own_checks() {
+    let floor = Floor::new((1..=2).map(|number| Table::new(number, 4)).collect());
+    let mut restaurant = Restaurant::new(floor);
+    let ana = restaurant.customers.add("Ana", "555-0101");
+    let bo = restaurant.customers.add("Bo", "555-0102");
+    for (table, customer) in [(1, ana), (2, bo)] {
+        restaurant.assign_server(table, "Sam").unwrap();
+        restaurant.add_to_waitlist(Party::new("regular", 2).customer(customer));
+        restaurant.seat_at_table(table).unwrap();
+    }
+    let first = restaurant.take_order(1, vec![soup(), steak()]).unwrap();
+    let second = restaurant.take_order(2, vec![steak()]).unwrap();
+    cook_and_serve(&mut restaurant);
+
+    // splitting Ana's check still earns her points on both halves
+    let split = restaurant.split_check("Sam", first, &[1]).unwrap();
+    assert_eq!(restaurant.orders.get(split).unwrap().customer, Some(ana));
+
+    assert_eq!(
+        restaurant.merge_checks("Sam", second, first),
+        Err(RestaurantError::DifferentCustomers(second, first))
+    );
+    assert_eq!(restaurant.orders.get(second).unwrap().customer, Some(bo));
+    restaurant.merge_checks("Sam", split, first).unwrap();
+    assert_eq!(restaurant.orders.get(first).unwrap().customer, Some(ana));
+}
+
+#[test]
+fn tips_go_to_whoever_had_the_table() {
+    let mut restaurant = seated();
+    let paid = restaurant.take_order(1, vec![steak()]).unwrap();
+    cook_and_serve(&mut restaurant);
+    restaurant.take_payment("Sam", paid, Tender::Card(2400)).unwrap();
+    restaurant.add_tip(paid, 500).unwrap();
+    let open = restaurant.take_order(1, vec![soup()]).unwrap();
+
+    // Sam goes home, Kit takes the table and the check still open on it
+    restaurant.transfer_table("Manager", 1, "Kit").unwrap();
+    assert_eq!(restaurant.orders.get(paid).unwrap().server.as_deref(), Some("Sam"));
+    assert_eq!(restaurant.orders.get(open).unwrap().server.as_deref(), Some("Kit"));
+    restaurant.add_tip(open, 150).unwrap();
+
+    let day = reports::end_of_day(&restaurant);
+    assert_eq!(day.tips.get("Sam"), Some(&500));
+    assert_eq!(day.tips.get("Kit"), Some(&150));
+
+    let entry = &restaurant.audit.entries()[0];
+    assert_eq!(entry.to_string(), "#1 Manager: handed table 1 from Sam to Kit");
+}
+
+#[test]
+fn a_check_settled_with_points_stays_with_its_server() {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4)]));
+    let ana = restaurant.customers.add("Ana", "555-0101");
+    restaurant.customers.get_mut(ana).unwrap().points = 1000;
+    restaurant.assign_server(1, "Sam").unwrap();
+    restaurant.add_to_waitlist(Party::new("Ana", 2).customer(ana));
+    restaurant.seat_at_table(1).unwrap();
+    let settled = restaurant.take_order(1, vec![soup()]).unwrap();
+    restaurant.redeem_points(settled, 650 / customers::CENTS_PER_POINT).unwrap();
+    assert!(restaurant.orders.get(settled).unwrap().payments.is_empty());
+
+    restaurant.transfer_table("Manager", 1, "Kit").unwrap();
+    assert_eq!(restaurant.orders.get(settled).unwrap().server.as_deref(), Some("Sam"));
+}
+
+#[test]
+fn the_audit_trail_survives_a_restart() {
+    let mut restaurant = seated();
+    let id = restaurant.take_order(1, vec![soup(), steak()]).unwrap();
+    cook_and_serve(&mut restaurant);
+    restaurant.transfer_order("Sam", id, 2).unwrap();
+    let other = restaurant.split_check("Sam", id, &[1]).unwrap();
+    restaurant.merge_checks("Kit", other, id).unwrap();
+    restaurant.transfer_table("Manager", 2, "Kit").unwrap();
+
+    let path = env::temp_dir().join(format!("restuarant-audit-{}.tsv", std::process::id()));
+    restaurant.audit.save(&path).unwrap();
+    let loaded = AuditTrail::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.entries(), restaurant.audit.entries());
+    assert_eq!(loaded.for_order(id).count(), 3);
+}
@@ -0,0 +1,83 @@
+use restuarant::back_of_house::costing::{self, CostBook, Ingredient, ItemCost, Recipe};
+use restuarant::back_of_house::inventory::{Counts, Unit};
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Dish;
+use restuarant::serving::{OrderLine, Tender};
+use restuarant::service::Restaurant;
+
+// flour at 0.074 cents a gram, butter at 1 cent a gram
+fn book() -> CostBook {
+    let mut book = CostBook::new();
+    book.add_ingredient(Ingredient::new("flour", Unit::Gram, 25000.0, 1850));
+    book.add_ingredient(Ingredient::new("butter", Unit::Gram, 500.0, 500));
+    book.add_recipe(Recipe::new("Tart").uses("flour", 100.0).uses("butter", 50.0)).unwrap();
+    book
+}
+
+fn counts(entries: &[(&str, f64)]) -> Counts {
+    entries.iter().map(|(name, quantity)| (name.to_string(), *quantity)).collect()
+}
+
+#[test]
+fn plates_are_costed_from_pack_prices() {
+    let book = book();
+    assert!((book.plate_cost("Tart").unwrap() - 57.4).abs() < 1e-9);
+
+    let cost = book.item_cost("Tart", 574).unwrap();
+    assert!((cost.food_cost_percent() - 10.0).abs() < 1e-9);
+    assert!((cost.margin_percent() - 90.0).abs() < 1e-9);
+}
+
+#[test]
+fn recipes_need_known_ingredients() {
+    let mut book = book();
+    assert_eq!(
+        book.add_recipe(Recipe::new("Bread").uses("yeast", 7.0)),
+        Err(RestaurantError::UnknownIngredient(String::from("yeast")))
+    );
+    assert_eq!(book.plate_cost("Bread"), Err(RestaurantError::NoRecipe(String::from("Bread"))));
+}
+
+#[test]
+fn only_paid_checks_count_as_sales() {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4), Table::new(2, 4)]));
+    for table in [1, 2] {
+        restaurant.add_to_waitlist(Party::new("guests", 2));
+        restaurant.seat_at_table(table).unwrap();
+    }
+    let paid = restaurant.take_order(1, vec![OrderLine::new(Dish::new("Tart", 900))]).unwrap();
+    restaurant.take_payment("Sam", paid, Tender::Card(900)).unwrap();
+    let part_paid = restaurant.take_order(2, vec![OrderLine::new(Dish::new("Tart", 900))]).unwrap();
+    restaurant.take_payment("Sam", part_paid, Tender::Card(400)).unwrap();
+    restaurant.take_order(2, vec![OrderLine::new(Dish::new("Tart", 900))]).unwrap();
+
+    let sold = costing::sales(&restaurant.orders);
+    assert_eq!(sold.get("Tart"), Some(&(1, 900)));
+
+    // 25 kg of flour and 1 kg of butter on the shelf, 1 kg of butter came in
+    let opening = counts(&[("flour", 25000.0), ("butter", 1000.0)]);
+    let purchases = counts(&[("butter", 1000.0)]);
+    let closing = counts(&[("flour", 24800.0), ("butter", 1900.0)]);
+    let report = book().food_cost(&restaurant.orders, &opening, &purchases, &closing).unwrap();
+    assert_eq!(report.revenue, 900);
+    assert!((report.theoretical_cost - 57.4).abs() < 1e-9);
+    assert!((report.actual_cost - 114.8).abs() < 1e-9);
+    assert!((report.variance() - 57.4).abs() < 1e-9);
+}
+
+#[test]
+fn the_worst_margins_are_flagged_first_and_exported() {
+    let costs = vec![
+        ItemCost { item: String::from("Tart"), price: 900, plate_cost: 300.0 },
+        ItemCost { item: String::from("Steak, frites"), price: 2400, plate_cost: 1200.0 },
+        ItemCost { item: String::from("Soup"), price: 650, plate_cost: 390.0 },
+    ];
+    let flagged: Vec<&str> = costing::below_margin(&costs, 60.0).iter().map(|cost| cost.item.as_str()).collect();
+    assert_eq!(flagged, vec!["Soup", "Steak, frites"]);
+
+    let csv = costing::costs_csv(&costs, 60.0);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "item,price,plate_cost,food_cost_percent,margin_percent,below_margin");
+    assert_eq!(lines[2], "\"Steak, frites\",24.00,12.00,50.0,50.0,true");
+}
@@ -0,0 +1,135 @@
+use std::env;
+use std::fs;
+
+use restuarant::customers::{self, Customers};
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Dish;
+use restuarant::serving::{OrderLine, Tender};
+use restuarant::service::Restaurant;
+
+// Ana is customer 1 and is seated at table 1 with a 20.00 steak in front of her,
+// table 2 is free
+fn regular_with_order() -> (Restaurant, u32, u32) {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4), Table::new(2, 4)]));
+    let ana = restaurant.customers.add("Ana", "555-0101");
+    restaurant.add_to_waitlist(Party::new("Ana", 2).customer(ana));
+    restaurant.seat_at_table(1).unwrap();
+    let order = restaurant.take_order(1, vec![OrderLine::new(Dish::new("Steak", 2000))]).unwrap();
+    (restaurant, ana, order)
+}
+
+#[test]
+fn paying_off_a_check_earns_points_and_records_the_visit() {
+    let (mut restaurant, ana, order) = regular_with_order();
+    restaurant.take_payment("Sam", order, Tender::Card(2000)).unwrap();
+
+    let customer = restaurant.customers.get(ana).unwrap();
+    assert_eq!(customer.points, 20 * customers::POINTS_PER_DOLLAR);
+    assert_eq!(customer.total_spent(), 2000);
+    assert_eq!(customer.favourite_items(1), vec![(String::from("Steak"), 1)]);
+}
+
+#[test]
+fn points_come_off_the_check_and_do_not_earn_more_points() {
+    let (mut restaurant, ana, order) = regular_with_order();
+    restaurant.customers.get_mut(ana).unwrap().points = 100;
+
+    restaurant.redeem_points(order, 100).unwrap();
+    assert_eq!(restaurant.orders.get(order).unwrap().balance_due(), 2000 - 100 * customers::CENTS_PER_POINT);
+    assert_eq!(
+        restaurant.redeem_points(order, 1),
+        Err(RestaurantError::NotEnoughPoints(ana, 0))
+    );
+
+    restaurant.take_payment("Sam", order, Tender::Card(1500)).unwrap();
+    assert_eq!(restaurant.customers.get(ana).unwrap().points, 15);
+}
+
+#[test]
+fn points_can_pay_as_a_tender() {
+    let (mut restaurant, ana, order) = regular_with_order();
+    restaurant.customers.get_mut(ana).unwrap().points = 50;
+
+    let payment = restaurant.take_payment("Sam", order, Tender::Points(ana, 245)).unwrap();
+    assert_eq!(payment.applied, 245);
+    // rounded up to whole points
+    assert_eq!(restaurant.customers.get(ana).unwrap().points, 50 - customers::points_for(245));
+}
+
+#[test]
+fn points_only_pay_their_own_customers_check() {
+    let (mut restaurant, ana, _) = regular_with_order();
+    restaurant.customers.get_mut(ana).unwrap().points = 50;
+    let bo = restaurant.customers.add("Bo", "555-0102");
+    restaurant.add_to_waitlist(Party::new("walk-in", 2));
+    restaurant.seat_at_table(2).unwrap();
+    let walk_in = restaurant.take_order(2, vec![OrderLine::new(Dish::new("Soup", 650))]).unwrap();
+
+    assert_eq!(
+        restaurant.take_payment("Sam", walk_in, Tender::Points(ana, 100)),
+        Err(RestaurantError::NotTheirCheck(ana, walk_in))
+    );
+    assert_eq!(
+        restaurant.take_payment("Sam", walk_in, Tender::Points(bo, 100)),
+        Err(RestaurantError::NotTheirCheck(bo, walk_in))
+    );
+    assert_eq!(restaurant.customers.get(ana).unwrap().points, 50);
+    assert_eq!(restaurant.orders.get(walk_in).unwrap().balance_due(), 650);
+}
+
+#[test]
+fn too_many_points_to_count_are_refused() {
+    let (mut restaurant, ana, order) = regular_with_order();
+    restaurant.customers.get_mut(ana).unwrap().points = u32::MAX;
+
+    assert_eq!(
+        restaurant.redeem_points(order, u32::MAX),
+        Err(RestaurantError::TooManyPoints(u32::MAX))
+    );
+    assert_eq!(
+        restaurant.customers.redeem_points(ana, u32::MAX),
+        Err(RestaurantError::TooManyPoints(u32::MAX))
+    );
+    assert_eq!(restaurant.customers.get(ana).unwrap().points, u32::MAX);
+    assert_eq!(restaurant.orders.get(order).unwrap().balance_due(), 2000);
+}
+
+#[test]
+fn checking_in_a_reservation_jumps_the_waitlist() {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4)]));
+    let ana = restaurant.customers.add("Ana", "555-0101");
+    restaurant.add_to_waitlist(Party::new("walk in", 2));
+    restaurant.reservations.book(Party::new("Ana", 2).customer(ana), 90);
+    restaurant.reservations.book(Party::new("Bo", 4), 30);
+
+    let times: Vec<u32> = restaurant.reservations.iter().map(|reservation| reservation.time).collect();
+    assert_eq!(times, vec![30, 90]);
+    assert_eq!(restaurant.reservations.for_customer(ana).count(), 1);
+
+    restaurant.check_in("Ana").unwrap();
+    assert_eq!(
+        restaurant.check_in("Ana"),
+        Err(RestaurantError::ReservationNotFound(String::from("Ana")))
+    );
+    assert_eq!(restaurant.seat_at_table(1).unwrap().customer, Some(ana));
+}
+
+#[test]
+fn customers_and_their_visits_survive_a_restart() {
+    let dir = env::temp_dir().join(format!("restuarant-customers-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let (mut restaurant, ana, order) = regular_with_order();
+    restaurant.customers.get_mut(ana).unwrap().allergies.push(String::from("peanuts"));
+    restaurant.take_payment("Sam", order, Tender::Card(2000)).unwrap();
+    restaurant.customers.save(&dir).unwrap();
+
+    let mut loaded = Customers::load(&dir).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(loaded.get(ana), restaurant.customers.get(ana));
+    assert_eq!(loaded.find_by_contact("555-0101").map(|customer| customer.id), Some(ana));
+    // new ids carry on after the ones on file
+    assert_eq!(loaded.add("Bo", "555-0102"), ana + 1);
+}
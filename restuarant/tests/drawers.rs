@@ -0,0 +1,85 @@
+use restuarant::drawers::{DrawerEntry, Drawers, Reconciliation};
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Dish;
+use restuarant::serving::{OrderLine, Tender};
+use restuarant::service::Restaurant;
+
+fn seated() -> Restaurant {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4)]));
+    restaurant.add_to_waitlist(Party::new("guests", 2));
+    restaurant.seat_at_table(1).unwrap();
+    restaurant
+}
+
+#[test]
+fn cash_needs_an_open_drawer_and_keeps_only_what_the_check_needs() {
+    let mut restaurant = seated();
+    let order = restaurant.take_order(1, vec![OrderLine::new(Dish::new("Steak", 1750))]).unwrap();
+    assert_eq!(
+        restaurant.take_payment("Sam", order, Tender::Cash(2000)),
+        Err(RestaurantError::NoOpenDrawer(String::from("Sam")))
+    );
+
+    restaurant.drawers.open("Sam", 10000).unwrap();
+    let payment = restaurant.take_payment("Sam", order, Tender::Cash(2000)).unwrap();
+    assert_eq!(payment.change, 250);
+
+    let drawer = restaurant.drawers.open_drawer("Sam").unwrap();
+    assert_eq!(drawer.entries, vec![DrawerEntry::Float(10000), DrawerEntry::Sale(order, 1750)]);
+    assert_eq!(drawer.cash_sales(), 1750);
+    assert_eq!(drawer.expected_cash(), 11750);
+}
+
+#[test]
+fn one_drawer_each_until_it_is_closed() {
+    let mut drawers = Drawers::new();
+    let first = drawers.open("Sam", 5000).unwrap();
+    assert_eq!(
+        drawers.open("Sam", 5000),
+        Err(RestaurantError::DrawerAlreadyOpen(String::from("Sam")))
+    );
+    drawers.open("Kit", 5000).unwrap();
+
+    drawers.close("Sam", 5000).unwrap();
+    let second = drawers.open("Sam", 5000).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(drawers.iter().filter(|drawer| drawer.is_open()).count(), 2);
+}
+
+#[test]
+fn drops_and_paid_outs_come_out_of_the_drawer() {
+    let mut drawers = Drawers::new();
+    drawers.open("Sam", 10000).unwrap();
+    drawers.record_sale("Sam", 1, 4000).unwrap();
+    drawers.drop_cash("Sam", 8000).unwrap();
+    drawers.pay_out("Sam", 1500, "ice delivery").unwrap();
+
+    assert_eq!(
+        drawers.pay_out("Sam", 5000, "more ice"),
+        Err(RestaurantError::NotEnoughCash(String::from("Sam")))
+    );
+    assert_eq!(drawers.open_drawer("Sam").unwrap().expected_cash(), 4500);
+}
+
+#[test]
+fn a_blind_count_is_reconciled_against_the_entries() {
+    let mut drawers = Drawers::new();
+    drawers.open("Sam", 10000).unwrap();
+    drawers.record_sale("Sam", 1, 2500).unwrap();
+    drawers.open("Kit", 10000).unwrap();
+
+    let short = drawers.close("Sam", 12000).unwrap();
+    assert_eq!(short, Reconciliation { expected: 12500, counted: 12000 });
+    assert_eq!(short.variance(), -500);
+    assert_eq!(drawers.close("Kit", 10100).unwrap().variance(), 100);
+    assert_eq!(
+        drawers.record_sale("Sam", 2, 100),
+        Err(RestaurantError::NoOpenDrawer(String::from("Sam")))
+    );
+
+    let report = drawers.over_short_report();
+    assert!(report.contains("Sam"));
+    assert!(report.contains("-5.00"));
+    assert!(report.contains("+1.00"));
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+
+use restuarant::back_of_house::costing::{CostBook, Ingredient, Recipe};
+use restuarant::back_of_house::forecast::{self, ForecastLog, Method, PrepItem, SalesHistory, Weekday};
+use restuarant::back_of_house::inventory::{Inventory, Unit};
+use restuarant::error::RestaurantError;
+
+// three Mondays of soup: 10, 20 then 30 bowls, split over lunch and dinner
+fn mondays() -> SalesHistory {
+    let mut history = SalesHistory::new();
+    for (week, bowls) in [10, 20, 30].into_iter().enumerate() {
+        let day = week as u32 * 7;
+        history.record(day, Weekday::Monday, 12, "Soup", bowls / 2);
+        history.record(day, Weekday::Monday, 19, "Soup", bowls / 2);
+        history.record(day + 1, Weekday::Tuesday, 12, "Soup", 100);
+    }
+    history
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn moving_averages_use_only_the_same_weekday() {
+    let history = mondays();
+    let all = history.forecast_day(Weekday::Monday, Method::MovingAverage(3)).unwrap();
+    assert!(close(all["Soup"], 20.0));
+    let recent = history.forecast_day(Weekday::Monday, Method::MovingAverage(2)).unwrap();
+    assert!(close(recent["Soup"], 25.0));
+
+    let hours = history.forecast_hours(Weekday::Monday, Method::MovingAverage(3)).unwrap();
+    assert!(close(hours[&(12, String::from("Soup"))], 10.0));
+    assert!(close(hours[&(19, String::from("Soup"))], 10.0));
+}
+
+#[test]
+fn smoothing_weights_the_latest_weeks() {
+    let history = mondays();
+    // 10, then 0.5 * 20 + 0.5 * 10, then 0.5 * 30 + 0.5 * 15
+    let half = history.forecast_day(Weekday::Monday, Method::ExponentialSmoothing(0.5)).unwrap();
+    assert!(close(half["Soup"], 22.5));
+    // all the weight on the last week
+    let last = history.forecast_day(Weekday::Monday, Method::ExponentialSmoothing(1.0)).unwrap();
+    assert!(close(last["Soup"], 30.0));
+}
+
+#[test]
+fn smoothing_factors_outside_zero_to_one_are_refused() {
+    let history = mondays();
+    for alpha in [0.0, -0.5, 1.5, f64::NAN] {
+        assert_eq!(
+            history.forecast_day(Weekday::Monday, Method::ExponentialSmoothing(alpha)),
+            Err(RestaurantError::InvalidSmoothing(alpha.to_string()))
+        );
+        assert!(history.forecast_hours(Weekday::Monday, Method::ExponentialSmoothing(alpha)).is_err());
+    }
+}
+
+#[test]
+fn the_prep_list_is_what_the_forecast_needs_minus_the_shelf() {
+    let mut book = CostBook::new();
+    book.add_ingredient(Ingredient::new("tomato", Unit::Gram, 5000.0, 1000));
+    book.add_recipe(Recipe::new("Soup").uses("tomato", 250.0)).unwrap();
+    let mut inventory = Inventory::new();
+    inventory.receive("tomato", 1000.0).unwrap();
+
+    // part plates round up to a whole one
+    let forecast = BTreeMap::from([(String::from("Soup"), 7.5)]);
+    let prep = forecast::prep_list(&forecast, &book, &inventory).unwrap();
+    assert_eq!(
+        prep,
+        vec![PrepItem {
+            ingredient: String::from("tomato"),
+            needed: 2000.0,
+            on_hand: 1000.0,
+            to_prep: 1000.0,
+        }]
+    );
+
+    let unknown = BTreeMap::from([(String::from("Stew"), 1.0)]);
+    assert_eq!(
+        forecast::prep_list(&unknown, &book, &inventory),
+        Err(RestaurantError::NoRecipe(String::from("Stew")))
+    );
+}
+
+#[test]
+fn forecast_errors_are_tracked_day_by_day() {
+    let history = mondays();
+    let mut log = ForecastLog::new();
+    log.record_day(14, &BTreeMap::from([(String::from("Soup"), 24.0)]), &history);
+    log.record(21, "Soup", 20.0, 22.0);
+
+    assert_eq!(log.entries().len(), 2);
+    assert!(close(log.entries()[0].actual, 30.0));
+    assert_eq!(log.mean_absolute_error("Soup"), Some(4.0));
+    assert_eq!(log.bias("Soup"), Some(-4.0));
+    assert_eq!(log.bias("Tart"), None);
+}
+
+#[test]
+fn sales_history_survives_a_restart() {
+    let path = env::temp_dir().join(format!("restuarant-sales-{}.tsv", std::process::id()));
+    let history = mondays();
+    history.save(&path).unwrap();
+    let loaded = SalesHistory::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.records(), history.records());
+}
@@ -0,0 +1,168 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use restuarant::back_of_house::{Appetizer, Breakfast};
+use restuarant::error::RestaurantError;
+use restuarant::guest_api;
+use restuarant::hosting::{self, Floor, Party, Table};
+use restuarant::serving::OrderStatus;
+use restuarant::service::{Restaurant, Service};
+
+// a restaurant with table 1 seated, served on a free local port
+fn start() -> (SocketAddr, Service, String) {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4), Table::new(2, 4)]));
+    restaurant.menu.add(Appetizer::Soup);
+    restaurant.menu.add(Breakfast::summer("Wheat"));
+    restaurant.add_to_waitlist(Party::new("Ana", 2));
+    restaurant.seat_at_table(1).unwrap();
+    let token = restaurant.open_session(1).unwrap();
+    let service = Service::new(restaurant);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let api = service.clone();
+    thread::spawn(move || guest_api::serve(listener, api));
+    (address, service, token)
+}
+
+// a bare-bones HTTP client, the server closes the connection after answering
+fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, body.to_string())
+}
+
+#[test]
+fn guests_order_and_the_server_approves() {
+    let (address, service, token) = start();
+    let session = format!("/session/{}", token);
+
+    let (status, menu) = request(address, "GET", "/menu", "");
+    assert_eq!(status, 200);
+    assert!(menu.contains("\"name\":\"Soup\""));
+    assert!(menu.contains("\"name\":\"extra bacon\""));
+
+    let (status, _) = request(address, "POST", &format!("{}/cart", session), "item=0");
+    assert_eq!(status, 200);
+    let (status, cart) = request(address, "POST", &format!("{}/cart", session), "item=1&modifier=extra+bacon");
+    assert_eq!(status, 200);
+    assert!(cart.contains("\"total\":2150"), "{}", cart);
+
+    // changed their mind about the soup
+    let (status, cart) = request(address, "DELETE", &format!("{}/cart/0", session), "");
+    assert_eq!(status, 200);
+    assert!(cart.contains("\"total\":1500"), "{}", cart);
+
+    let (status, body) = request(address, "POST", &format!("{}/submit", session), "");
+    assert_eq!(status, 200);
+    assert_eq!(body, "{\"submission\":1}");
+    assert!(service.with(|r| r.orders.iter().next().is_none()), "nothing goes to the kitchen until approved");
+
+    let order = service.with(|r| r.approve_submission(1)).unwrap();
+    service.with(|r| {
+        let order = r.orders.get(order).unwrap();
+        assert_eq!(order.table, 1);
+        assert_eq!(order.status, OrderStatus::Sent);
+        assert_eq!(order.subtotal(), 1500);
+        assert_eq!(r.kitchen.len(), 1);
+        assert_eq!(r.sessions.pending().count(), 0);
+    });
+}
+
+#[test]
+fn guests_can_call_their_server_and_ask_for_the_check() {
+    let (address, service, token) = start();
+
+    let (status, _) = request(address, "POST", &format!("/session/{}/call-server", token), "");
+    assert_eq!(status, 200);
+    let (status, session) = request(address, "POST", &format!("/session/{}/request-check", token), "");
+    assert_eq!(status, 200);
+    assert!(session.contains("\"requests\":[\"CallServer\",\"RequestCheck\"]"), "{}", session);
+    assert_eq!(service.with(|r| r.sessions.requests().len()), 2);
+
+    service.with(|r| r.sessions.acknowledge(1));
+    assert!(service.with(|r| r.sessions.requests().is_empty()));
+}
+
+#[test]
+fn bad_requests_are_turned_away() {
+    let (address, service, token) = start();
+
+    assert_eq!(request(address, "GET", "/session/not-a-token", "").0, 404);
+    assert_eq!(request(address, "POST", &format!("/session/{}/cart", token), "item=9").0, 404);
+    assert_eq!(
+        request(address, "POST", &format!("/session/{}/cart", token), "item=0&modifier=gravy").0,
+        400
+    );
+    assert_eq!(request(address, "POST", &format!("/session/{}/submit", token), "").0, 400);
+    assert_eq!(request(address, "DELETE", "/menu", "").0, 405);
+    assert_eq!(request(address, "GET", "/kitchen", "").0, 404);
+
+    // once the party leaves their token stops working
+    service.with(|r| r.clear_table(1)).unwrap();
+    assert_eq!(request(address, "GET", &format!("/session/{}", token), "").0, 404);
+}
+
+#[test]
+fn a_cart_left_behind_is_not_rung_in_for_the_next_party() {
+    let (address, service, token) = start();
+    request(address, "POST", &format!("/session/{}/cart", token), "item=0");
+    request(address, "POST", &format!("/session/{}/submit", token), "");
+    let submission = service.with(|r| r.sessions.pending().next().unwrap().id);
+
+    service.with(|r| {
+        r.clear_table(1).unwrap();
+        hosting::bus_table(r.floor.table_mut(1).unwrap());
+        r.add_to_waitlist(Party::new("Bo", 2));
+        r.seat_at_table(1).unwrap();
+    });
+
+    assert_eq!(
+        service.with(|r| r.approve_submission(submission)),
+        Err(RestaurantError::SubmissionNotFound(submission))
+    );
+    assert!(service.with(|r| r.orders.is_empty()));
+}
+
+#[test]
+fn connections_past_the_cap_are_turned_away() {
+    let (address, _, _) = start();
+
+    // phones that connect and say nothing each hold a connection open
+    let quiet: Vec<TcpStream> = (0..guest_api::MAX_CONNECTIONS)
+        .map(|_| TcpStream::connect(address).unwrap())
+        .collect();
+    // turned away before it's read a thing, so don't send one or the close resets it
+    let mut response = String::new();
+    TcpStream::connect(address).unwrap().read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 503"), "{}", response);
+
+    // once they hang up there's room again
+    drop(quiet);
+    let mut response = String::new();
+    for _ in 0..100 {
+        thread::sleep(Duration::from_millis(20));
+        let mut stream = TcpStream::connect(address).unwrap();
+        response.clear();
+        // a reset means it was turned away again
+        let _ = write!(stream, "GET /menu HTTP/1.1\r\n\r\n").and_then(|_| stream.read_to_string(&mut response));
+        if response.starts_with("HTTP/1.1 200") {
+            break;
+        }
+    }
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+}
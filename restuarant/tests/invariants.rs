@@ -0,0 +1,443 @@
+// random service days thrown at the restaurant, checking after every step
+// that the things which must always hold still do
+//
+// a failure prints the seed and the steps that led to it, run a single day
+// again with INVARIANT_SEED=<seed> to reproduce it
+use std::collections::BTreeMap;
+use std::env;
+
+use restuarant::back_of_house::costing::{Ingredient, Recipe};
+use restuarant::back_of_house::inventory::Unit;
+use restuarant::back_of_house::waste::{WasteReason, Wasted};
+use restuarant::back_of_house::Station;
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{self, Floor, Party, Table, TableStatus};
+use restuarant::menu::Dish;
+use restuarant::serving::{Course, OrderLine, OrderStatus, Tender};
+use restuarant::service::Restaurant;
+
+const DAYS: u64 = 200;
+const STEPS_PER_DAY: usize = 400;
+const SERVERS: [&str; 2] = ["Sam", "Kit"];
+const FLOAT: u32 = 20000;
+const GIFT_CARD: u32 = 5000;
+
+// xorshift, good enough to shuffle a service day and needs no crates
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // never zero, or every number after it is zero too
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // 0 up to but not including n
+    fn below(&mut self, n: u32) -> u32 {
+        (self.next() % n.max(1) as u64) as u32
+    }
+
+    fn chance(&mut self, percent: u32) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.below(items.len() as u32) as usize)
+    }
+}
+
+#[derive(Debug)]
+enum Step {
+    Arrive(u32),
+    Seat(u32),
+    SeatAgain(u32),
+    Order(u32, Vec<(usize, Option<Course>)>),
+    Bump,
+    Serve(u32),
+    FireNext(u32),
+    Remake(u32),
+    Pay(u32, Tender),
+    Waste(Wasted, f64),
+    Receive(String, f64),
+    Clear(u32),
+    Bus(u32),
+}
+
+fn menu() -> Vec<Dish> {
+    vec![
+        Dish::new("Soup", 650).course(Course::Appetizer).cooked_at(Station::Saute, 5),
+        Dish::new("Salad", 900).course(Course::Appetizer).cooked_at(Station::Pantry, 3),
+        Dish::new("Steak", 2400).cooked_at(Station::Grill, 15),
+        Dish::new("Fish", 2100).cooked_at(Station::Saute, 12),
+        Dish::new("Tart", 800).course(Course::Dessert).cooked_at(Station::Pastry, 2),
+    ]
+}
+
+const INGREDIENTS: [&str; 4] = ["tomato", "beef", "cod", "flour"];
+
+fn open_restaurant(gift_card: &mut String) -> Restaurant {
+    let floor = Floor::new(vec![
+        Table::new(1, 2),
+        Table::new(2, 2),
+        Table::new(3, 4),
+        Table::new(4, 4),
+        Table::new(5, 6),
+    ]);
+    let mut restaurant = Restaurant::new(floor);
+    for ingredient in INGREDIENTS {
+        restaurant.cost_book.add_ingredient(Ingredient::new(ingredient, Unit::Gram, 1000.0, 1500));
+        restaurant.inventory.receive(ingredient, 2000.0).unwrap();
+    }
+    let recipes = [
+        Recipe::new("Soup").uses("tomato", 250.0),
+        Recipe::new("Steak").uses("beef", 300.0),
+        Recipe::new("Fish").uses("cod", 200.0).uses("flour", 20.0),
+        Recipe::new("Tart").uses("flour", 80.0),
+    ];
+    for recipe in recipes {
+        restaurant.cost_book.add_recipe(recipe).unwrap();
+    }
+    for server in SERVERS {
+        restaurant.drawers.open(server, FLOAT).unwrap();
+    }
+    *gift_card = restaurant.gift_cards.issue(GIFT_CARD);
+    restaurant
+}
+
+// what the test itself remembers about the day, to hold the restaurant to
+#[derive(Default)]
+struct Tally {
+    arrived: usize,
+    left: usize,
+    cooked: BTreeMap<u32, u32>,    // tickets bumped, by order
+    delivered: BTreeMap<u32, u32>, // times the food reached the table
+}
+
+fn generate(rng: &mut Rng, restaurant: &Restaurant, gift_card: &str, tally: &Tally) -> Step {
+    let tables: Vec<u32> = restaurant.floor.tables().iter().map(|table| table.number).collect();
+    let orders: Vec<u32> = restaurant.orders.iter().map(|order| order.id).collect();
+    let table = *rng.pick(&tables).unwrap();
+    let order = rng.pick(&orders).copied().unwrap_or(1);
+    match rng.below(13) {
+        0 => Step::Arrive(1 + rng.below(6)),
+        1 => Step::Seat(table),
+        2 => Step::SeatAgain(table),
+        3 => {
+            let lines = (0..1 + rng.below(4))
+                .map(|_| {
+                    let course = rng.chance(20).then(|| *rng.pick(&[Course::Appetizer, Course::Main, Course::Dessert]).unwrap());
+                    (rng.below(menu().len() as u32) as usize, course)
+                })
+                .collect();
+            Step::Order(table, lines)
+        }
+        4 | 5 => Step::Bump,
+        6 => Step::Serve(order),
+        7 => Step::FireNext(order),
+        8 => Step::Remake(order),
+        9 => {
+            let due = restaurant.orders.get(order).map(|order| order.balance_due()).unwrap_or(0);
+            // often the whole balance, sometimes more than is owed, which
+            // only cash may do
+            let extra = if rng.chance(20) { 3000 } else { 0 };
+            let amount = if rng.chance(40) { due } else { rng.below(due + 1 + extra) };
+            let tender = match rng.below(3) {
+                0 => Tender::Cash(amount),
+                1 => Tender::Card(amount),
+                _ => Tender::GiftCard(gift_card.to_string(), amount),
+            };
+            Step::Pay(order, tender)
+        }
+        10 => {
+            let quantity = 1.0 + rng.below(700) as f64;
+            if rng.chance(50) {
+                Step::Waste(Wasted::Ingredient(INGREDIENTS[rng.below(4) as usize].to_string()), quantity)
+            } else {
+                let dish = rng.pick(&menu()).unwrap().name.clone();
+                Step::Waste(Wasted::Item(dish), 1.0 + rng.below(3) as f64)
+            }
+        }
+        11 => Step::Receive(INGREDIENTS[rng.below(4) as usize].to_string(), rng.below(500) as f64),
+        _ if rng.chance(50) || tally.arrived == tally.left => Step::Clear(table),
+        _ => Step::Bus(table),
+    }
+}
+
+fn apply(restaurant: &mut Restaurant, step: &Step, tally: &mut Tally, day: u64, server: &str) {
+    match step {
+        Step::Arrive(size) => {
+            tally.arrived += 1;
+            restaurant.add_to_waitlist(Party::new(&format!("party {}-{}", day, tally.arrived), *size));
+        }
+        Step::Seat(table) => {
+            let _ = restaurant.seat_at_table(*table);
+        }
+        Step::SeatAgain(table) => {
+            let before = restaurant.floor.table(*table).unwrap().clone();
+            let waiting = restaurant.waitlist.len();
+            let result = restaurant.seat_at_table(*table);
+            if before.status != TableStatus::Open {
+                assert!(result.is_err(), "table {} was seated while {:?}", table, before.status);
+                let after = restaurant.floor.table(*table).unwrap();
+                assert_eq!(after.party.as_ref().map(|p| &p.name), before.party.as_ref().map(|p| &p.name));
+                assert_eq!(restaurant.waitlist.len(), waiting, "a failed seating took a party off the list");
+            }
+        }
+        Step::Order(table, picks) => {
+            let dishes = menu();
+            let lines = picks
+                .iter()
+                .map(|(dish, course)| {
+                    let line = OrderLine::new(dishes[*dish].clone());
+                    match course {
+                        Some(course) => line.course(*course),
+                        None => line,
+                    }
+                })
+                .collect();
+            let _ = restaurant.take_order(*table, lines);
+        }
+        Step::Bump => {
+            if let Some(ticket) = restaurant.bump_ticket() {
+                *tally.cooked.entry(ticket.order_id).or_insert(0) += 1;
+            }
+        }
+        Step::Serve(order) => {
+            if restaurant.serve_order(*order).is_ok() {
+                *tally.delivered.entry(*order).or_insert(0) += 1;
+            }
+        }
+        Step::FireNext(order) => {
+            let next = restaurant.orders.get(*order).ok().and_then(|order| order.held_courses().first().copied());
+            if let Some(course) = next {
+                let _ = restaurant.fire_course(*order, course);
+            }
+        }
+        Step::Remake(order) => {
+            let _ = restaurant.fix_incorrect_order(server, *order);
+        }
+        Step::Pay(order, tender) => {
+            let due = restaurant.orders.get(*order).map(|order| order.balance_due()).unwrap_or(0);
+            let result = restaurant.take_payment(server, *order, tender.clone());
+            if let (Tender::Card(amount), true) = (tender, restaurant.orders.get(*order).is_ok()) {
+                if *amount > due {
+                    assert!(matches!(result, Err(RestaurantError::Overtendered(_))), "card overtendered: {:?}", result);
+                }
+            }
+        }
+        Step::Waste(wasted, quantity) => {
+            let before = restaurant.inventory.counts();
+            let result = restaurant.log_waste(wasted.clone(), *quantity, WasteReason::Dropped, server);
+            if result.is_err() {
+                assert_eq!(restaurant.inventory.counts(), before, "failed waste log still changed stock");
+            }
+        }
+        Step::Receive(ingredient, quantity) => {
+            let before = restaurant.inventory.counts();
+            if restaurant.inventory.receive(ingredient, *quantity).is_err() {
+                assert_eq!(restaurant.inventory.counts(), before, "refused delivery still changed stock");
+            }
+        }
+        Step::Clear(table) => {
+            if restaurant.clear_table(*table).is_ok() {
+                tally.left += 1;
+            }
+        }
+        Step::Bus(table) => {
+            if let Ok(table) = restaurant.floor.table_mut(*table) {
+                hosting::bus_table(table);
+            }
+        }
+    }
+}
+
+fn check(restaurant: &Restaurant, tally: &Tally, gift_card: &str) {
+    // checks balance: the tenders add up to what was charged, and never more
+    let mut redeemed = 0;
+    for order in restaurant.orders.iter() {
+        assert!(order.total() <= order.subtotal());
+        assert!(order.paid() <= order.total(), "order {} paid {} of {}", order.id, order.paid(), order.total());
+        let tendered: u32 = order.payments.iter().map(|payment| payment.applied).sum();
+        assert_eq!(tendered + order.balance_due(), order.total(), "order {} doesn't balance", order.id);
+        for payment in &order.payments {
+            assert_eq!(payment.applied + payment.change, payment.tender.amount());
+            if !matches!(payment.tender, Tender::Cash(_)) {
+                assert_eq!(payment.change, 0, "{:?} gave change", payment.tender);
+            }
+            if let Tender::GiftCard(_, amount) = payment.tender {
+                redeemed += amount;
+            }
+        }
+    }
+    assert_eq!(restaurant.gift_cards.balance(gift_card).unwrap() + redeemed, GIFT_CARD);
+    let cash: u32 = restaurant
+        .orders
+        .iter()
+        .flat_map(|order| order.payments.iter())
+        .filter(|payment| matches!(payment.tender, Tender::Cash(_)))
+        .map(|payment| payment.applied)
+        .sum();
+    let in_drawers: u32 = restaurant.drawers.iter().map(|drawer| drawer.cash_sales()).sum();
+    assert_eq!(in_drawers, cash, "cash taken doesn't match the drawers");
+    for drawer in restaurant.drawers.iter() {
+        assert_eq!(drawer.expected_cash(), FLOAT + drawer.cash_sales());
+    }
+
+    // nobody sits at two tables, and a seated table always has its party
+    let mut seated = Vec::new();
+    for table in restaurant.floor.tables() {
+        assert_eq!(table.status == TableStatus::Seated, table.party.is_some(), "table {:?}", table);
+        if let Some(party) = &table.party {
+            assert!(party.size <= table.seats, "{} squeezed onto table {}", party.name, table.number);
+            assert!(!seated.contains(&party.name), "{} is at two tables", party.name);
+            seated.push(party.name.clone());
+        }
+    }
+    for party in restaurant.waitlist.iter() {
+        assert!(!seated.contains(&party.name), "{} is seated and still waiting", party.name);
+    }
+    assert_eq!(restaurant.waitlist.len() + seated.len() + tally.left, tally.arrived, "a party went missing");
+
+    // stock never goes below zero
+    for (ingredient, count) in restaurant.inventory.counts() {
+        assert!(count >= 0.0, "{} went negative: {}", ingredient, count);
+    }
+
+    // everything the kitchen cooked went out or is waiting at the pass, and
+    // every ticket sent was cooked or is still on the rail
+    for order in restaurant.orders.iter() {
+        let cooked = tally.cooked.get(&order.id).copied().unwrap_or(0);
+        let delivered = tally.delivered.get(&order.id).copied().unwrap_or(0);
+        let waiting = (order.status == OrderStatus::Ready) as u32;
+        assert_eq!(cooked, delivered + waiting, "order {} cooked {} delivered {}", order.id, cooked, delivered);
+        let on_rail = restaurant.kitchen.tickets().filter(|ticket| ticket.order_id == order.id).count() as u32;
+        assert!(on_rail <= 1, "order {} has {} tickets in the kitchen", order.id, on_rail);
+        assert_eq!(order.fired.len() as u32 + order.remakes, cooked + on_rail, "order {} lost a ticket", order.id);
+    }
+}
+
+// the kitchen finishes up and the servers run every plate
+fn close(restaurant: &mut Restaurant, tally: &mut Tally) {
+    let ready: Vec<u32> = restaurant
+        .orders
+        .iter()
+        .filter(|order| order.status == OrderStatus::Ready)
+        .map(|order| order.id)
+        .collect();
+    for id in ready {
+        restaurant.serve_order(id).unwrap();
+        *tally.delivered.entry(id).or_insert(0) += 1;
+    }
+    while let Some(ticket) = restaurant.bump_ticket() {
+        *tally.cooked.entry(ticket.order_id).or_insert(0) += 1;
+        restaurant.serve_order(ticket.order_id).unwrap();
+        *tally.delivered.entry(ticket.order_id).or_insert(0) += 1;
+    }
+    for order in restaurant.orders.iter() {
+        assert_eq!(order.status, OrderStatus::Delivered, "order {} never reached the table", order.id);
+    }
+}
+
+fn run_day(seed: u64) {
+    let mut rng = Rng::new(seed);
+    let mut gift_card = String::new();
+    let mut restaurant = open_restaurant(&mut gift_card);
+    let mut tally = Tally::default();
+    let mut steps = Vec::new();
+    for _ in 0..STEPS_PER_DAY {
+        let step = generate(&mut rng, &restaurant, &gift_card, &tally);
+        let server = SERVERS[rng.below(SERVERS.len() as u32) as usize];
+        steps.push(format!("{} {:?}", server, step));
+        let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            apply(&mut restaurant, &step, &mut tally, seed, server);
+            check(&restaurant, &tally, &gift_card);
+        }));
+        if let Err(panic) = outcome {
+            eprintln!("seed {} broke after:\n  {}", seed, steps.join("\n  "));
+            std::panic::resume_unwind(panic);
+        }
+    }
+    close(&mut restaurant, &mut tally);
+    check(&restaurant, &tally, &gift_card);
+}
+
+#[test]
+fn random_service_days_keep_every_invariant() {
+    match env::var("INVARIANT_SEED") {
+        Ok(seed) => run_day(seed.parse().expect("INVARIANT_SEED is a number")),
+        Err(_) => (0..DAYS).for_each(run_day),
+    }
+}
+
+// the scenarios below are the ones the random days found worth pinning down
+
+#[test]
+fn a_seated_table_cant_be_seated_again() {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4)]));
+    restaurant.add_to_waitlist(Party::new("Ana", 2));
+    restaurant.add_to_waitlist(Party::new("Bo", 2));
+    restaurant.seat_at_table(1).unwrap();
+    assert!(matches!(restaurant.seat_at_table(1), Err(RestaurantError::TableNotOpen(1))));
+    assert_eq!(restaurant.floor.table(1).unwrap().party.as_ref().unwrap().name, "Ana");
+    assert_eq!(restaurant.waitlist.len(), 1);
+}
+
+#[test]
+fn a_split_check_adds_up_to_the_total() {
+    let mut gift_card = String::new();
+    let mut restaurant = open_restaurant(&mut gift_card);
+    restaurant.add_to_waitlist(Party::new("Ana", 2));
+    restaurant.seat_at_table(1).unwrap();
+    let id = restaurant.take_order(1, vec![OrderLine::new(menu()[2].clone()), OrderLine::new(menu()[4].clone())]).unwrap();
+
+    restaurant.take_payment("Sam", id, Tender::GiftCard(gift_card.clone(), 1200)).unwrap();
+    restaurant.take_payment("Sam", id, Tender::Card(1000)).unwrap();
+    assert!(restaurant.take_payment("Sam", id, Tender::Card(1001)).is_err());
+    let cash = restaurant.take_payment("Kit", id, Tender::Cash(2000)).unwrap();
+
+    let order = restaurant.orders.get(id).unwrap();
+    assert_eq!(cash.change, 2000 - 1000);
+    assert_eq!(order.balance_due(), 0);
+    assert_eq!(order.payments.iter().map(|payment| payment.applied).sum::<u32>(), order.total());
+}
+
+#[test]
+fn wasting_more_than_we_have_leaves_stock_alone() {
+    let mut gift_card = String::new();
+    let mut restaurant = open_restaurant(&mut gift_card);
+    restaurant.inventory.set_count("flour", 50.0);
+    let before = restaurant.inventory.counts();
+    // the fish needs 200 cod, which we have, and 20 flour a plate
+    let result = restaurant.log_waste(Wasted::Item("Fish".to_string()), 3.0, WasteReason::Dropped, "Sam");
+    assert!(matches!(result, Err(RestaurantError::NotEnoughStock(ref name)) if name == "flour"));
+    assert_eq!(restaurant.inventory.counts(), before);
+    assert!(restaurant.waste.entries().is_empty());
+}
+
+#[test]
+fn a_remade_course_is_cooked_and_delivered_again() {
+    let mut gift_card = String::new();
+    let mut restaurant = open_restaurant(&mut gift_card);
+    restaurant.add_to_waitlist(Party::new("Ana", 2));
+    restaurant.seat_at_table(1).unwrap();
+    let id = restaurant.take_order(1, vec![OrderLine::new(menu()[2].clone())]).unwrap();
+    restaurant.bump_ticket().unwrap();
+    restaurant.serve_order(id).unwrap();
+
+    restaurant.fix_incorrect_order("Sam", id).unwrap();
+    assert_eq!(restaurant.orders.get(id).unwrap().status, OrderStatus::Sent);
+    assert!(restaurant.serve_order(id).is_err(), "the remake hasn't been cooked yet");
+    assert_eq!(restaurant.bump_ticket().unwrap().order_id, id);
+    restaurant.serve_order(id).unwrap();
+    let order = restaurant.orders.get(id).unwrap();
+    assert_eq!((order.status, order.remakes), (OrderStatus::Delivered, 1));
+}
@@ -0,0 +1,111 @@
+use restuarant::back_of_house::stations::Routing;
+use restuarant::back_of_house::{Kitchen, Station};
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Dish;
+use restuarant::serving::{OrderLine, OrderStatus};
+use restuarant::service::Restaurant;
+
+fn steak() -> OrderLine {
+    OrderLine::new(Dish::new("Steak", 2400).cooked_at(Station::Grill, 15))
+}
+
+fn fries() -> OrderLine {
+    OrderLine::new(Dish::new("Fries", 500).cooked_at(Station::Fryer, 6))
+}
+
+fn burger() -> OrderLine {
+    OrderLine::new(Dish::new("Burger", 1600).cooked_at(Station::Grill, 10))
+}
+
+fn seated(kitchen: Kitchen, tables: u32) -> Restaurant {
+    let mut restaurant = Restaurant::new(Floor::new((1..=tables).map(|number| Table::new(number, 4)).collect()));
+    restaurant.kitchen = kitchen;
+    for number in 1..=tables {
+        restaurant.add_to_waitlist(Party::new(&format!("party {}", number), 2));
+        restaurant.seat_at_table(number).unwrap();
+    }
+    restaurant
+}
+
+#[test]
+fn expo_waits_for_every_station() {
+    let mut restaurant = seated(Kitchen::new(), 1);
+    let id = restaurant.take_order(1, vec![steak(), fries()]).unwrap();
+
+    let expo = restaurant.kitchen.expo();
+    assert_eq!(expo[0].waiting_on, vec![Station::Grill, Station::Fryer]);
+    assert!(restaurant.expedite().is_none());
+
+    restaurant.bump_station(Station::Fryer).unwrap();
+    assert_eq!(restaurant.kitchen.expo()[0].waiting_on, vec![Station::Grill]);
+    assert!(restaurant.expedite().is_none());
+
+    let grill = restaurant.bump_station(Station::Grill).unwrap();
+    assert_eq!(grill.items, vec!["Steak".to_string()]);
+    assert!(restaurant.kitchen.expo()[0].is_ready());
+    assert_eq!(restaurant.expedite().unwrap().order_id, id);
+    assert_eq!(restaurant.orders.get(id).unwrap().status, OrderStatus::Ready);
+    assert!(restaurant.kitchen.is_empty());
+}
+
+#[test]
+fn a_later_ticket_can_run_before_an_earlier_one() {
+    let mut restaurant = seated(Kitchen::new(), 2);
+    let slow = restaurant.take_order(1, vec![steak()]).unwrap();
+    let quick = restaurant.take_order(2, vec![fries()]).unwrap();
+
+    restaurant.bump_station(Station::Fryer).unwrap();
+    assert_eq!(restaurant.expedite().unwrap().order_id, quick);
+    assert_eq!(restaurant.orders.get(slow).unwrap().status, OrderStatus::Sent);
+}
+
+#[test]
+fn a_full_station_spills_over_to_the_next_route() {
+    let routing = Routing::new().route("Burger", &[Station::Grill, Station::Line]);
+    let mut kitchen = Kitchen::new().with_routing(routing);
+    kitchen.capacity(Station::Grill, 2);
+    let mut restaurant = seated(kitchen, 3);
+    for table in 1..=3 {
+        restaurant.take_order(table, vec![burger()]).unwrap();
+    }
+
+    let grill = restaurant.kitchen.station(Station::Grill).unwrap();
+    assert_eq!(grill.len(), 2);
+    assert!(grill.is_full());
+    let line = restaurant.kitchen.station(Station::Line).unwrap();
+    assert_eq!(line.tickets().next().unwrap().table, 3);
+
+    // with nowhere left to go it queues on the least busy station
+    restaurant.take_order(1, vec![steak()]).unwrap();
+    let grill = restaurant.kitchen.station(Station::Grill).unwrap();
+    assert_eq!((grill.working().count(), grill.waiting().count()), (2, 1));
+}
+
+#[test]
+fn ticket_times_are_averaged_per_station() {
+    let mut restaurant = seated(Kitchen::new(), 2);
+    restaurant.take_order(1, vec![steak(), fries()]).unwrap();
+    restaurant.kitchen.set_time(4);
+    restaurant.take_order(2, vec![steak()]).unwrap();
+
+    restaurant.kitchen.set_time(6);
+    restaurant.bump_station(Station::Fryer).unwrap();
+    restaurant.kitchen.set_time(15);
+    restaurant.bump_station(Station::Grill).unwrap();
+    restaurant.kitchen.set_time(24);
+    restaurant.bump_station(Station::Grill).unwrap();
+
+    let times = restaurant.kitchen.average_ticket_times();
+    assert_eq!(times.get(&Station::Fryer), Some(&6.0));
+    assert_eq!(times.get(&Station::Grill), Some(&17.5));
+    assert_eq!(times.get(&Station::Pastry), None);
+}
+
+#[test]
+fn bumping_from_the_rail_clears_the_stations() {
+    let mut restaurant = seated(Kitchen::new(), 1);
+    restaurant.take_order(1, vec![steak(), fries()]).unwrap();
+    restaurant.bump_ticket().unwrap();
+    assert!(restaurant.kitchen.station(Station::Grill).unwrap().is_empty());
+    assert!(restaurant.kitchen.station(Station::Fryer).unwrap().is_empty());
+}
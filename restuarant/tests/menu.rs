@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use restuarant::back_of_house::costing::{self, CostBook, Ingredient, Recipe};
+use restuarant::back_of_house::inventory::Unit;
+use restuarant::back_of_house::{Appetizer, Breakfast, Station};
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::{Allergen, MenuItem, Modifier};
+use restuarant::menu_item;
+use restuarant::serving::{Course, OrderLine, Tender};
+use restuarant::service::Restaurant;
+
+#[derive(Debug)]
+struct Pancakes {
+    stack: u32,
+}
+
+// only the required fields
+menu_item!(Pancakes, |pancakes| {
+    name: "Pancakes",
+    price: 400 + pancakes.stack * 150,
+    station: Station::Line,
+    prep_minutes: 8,
+});
+
+#[derive(Debug)]
+enum Dessert {
+    Tart,
+    Sorbet,
+}
+
+// every field, with the dish matched on inside them
+menu_item!(Dessert, |dessert| {
+    name: match dessert {
+        Dessert::Tart => "Tart",
+        Dessert::Sorbet => "Sorbet",
+    },
+    price: 900,
+    station: Station::Pastry,
+    prep_minutes: 5,
+    course: Course::Dessert,
+    allergens: [Allergen::Dairy, Allergen::Egg],
+    modifiers: [("cream", 100), ("extra scoop", 250),],
+    details: format!("{:?}, made today", dessert),
+});
+
+#[test]
+fn fields_left_out_keep_the_trait_defaults() {
+    let pancakes = Pancakes { stack: 3 };
+    assert_eq!(pancakes.name(), "Pancakes");
+    assert_eq!(pancakes.price(), 850);
+    assert_eq!(pancakes.course(), Course::Main);
+    assert!(pancakes.allergens().is_empty());
+    assert!(pancakes.modifiers().is_empty());
+    assert_eq!(pancakes.details(), None);
+}
+
+#[test]
+fn every_field_can_read_the_dish() {
+    assert_eq!(Dessert::Tart.name(), "Tart");
+    assert_eq!(Dessert::Sorbet.name(), "Sorbet");
+    assert_eq!(Dessert::Sorbet.station(), Station::Pastry);
+    assert_eq!(Dessert::Sorbet.course(), Course::Dessert);
+    assert_eq!(Dessert::Tart.allergens(), vec![Allergen::Dairy, Allergen::Egg]);
+    assert_eq!(Dessert::Tart.modifiers(), vec![Modifier::new("cream", 100), Modifier::new("extra scoop", 250)]);
+    assert_eq!(Dessert::Sorbet.details(), Some(String::from("Sorbet, made today")));
+}
+
+#[test]
+fn order_lines_hold_any_dish_and_show_its_details() {
+    let line = OrderLine::new(Breakfast::summer("Rye")).modifier("extra bacon").unwrap();
+    assert_eq!(line.item(), "Breakfast, Rye toast and peaches (extra bacon)");
+    assert_eq!(line.price(), 1500);
+
+    let line = OrderLine::new(Appetizer::Soup);
+    assert_eq!(line.item(), "Soup");
+    assert_eq!(line.course, Course::Appetizer);
+}
+
+#[test]
+fn every_breakfast_is_costed_under_one_recipe() {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4)]));
+    restaurant.add_to_waitlist(Party::new("guests", 2));
+    restaurant.seat_at_table(1).unwrap();
+    let lines = vec![
+        OrderLine::new(Breakfast::summer("Rye")),
+        OrderLine::new(Breakfast::summer("Wheat")),
+    ];
+    let order = restaurant.take_order(1, lines).unwrap();
+    restaurant.take_payment("Sam", order, Tender::Card(2400)).unwrap();
+
+    let mut book = CostBook::new();
+    book.add_ingredient(Ingredient::new("eggs", Unit::Each, 30.0, 900));
+    book.add_recipe(Recipe::new(&Breakfast::summer("Rye").name()).uses("eggs", 2.0)).unwrap();
+
+    let sold = costing::sales(&restaurant.orders);
+    assert_eq!(sold, BTreeMap::from([(String::from("Breakfast"), (2, 2400))]));
+    assert_eq!(book.theoretical_usage(&sold).unwrap()["eggs"], 4.0);
+}
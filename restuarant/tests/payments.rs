@@ -0,0 +1,196 @@
+use std::env;
+use std::fs;
+use std::thread;
+
+use restuarant::accounts::{GiftCards, HouseAccounts};
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Dish;
+use restuarant::serving::{OrderLine, Tender};
+use restuarant::service::{Restaurant, Service};
+
+// a seated table with one order on it, returns the order id
+fn order_for(service: &Service, table: u32, price: u32) -> u32 {
+    service.take_order(table, vec![OrderLine::new(Dish::new("Steak", price))]).unwrap()
+}
+
+fn seated_service(tables: u32) -> Service {
+    let floor = Floor::new((1..=tables).map(|number| Table::new(number, 4)).collect());
+    let service = Service::new(Restaurant::new(floor));
+    for number in 1..=tables {
+        service.add_to_waitlist(Party::new("guests", 2));
+        service.seat_at_table(number).unwrap();
+    }
+    service
+}
+
+#[test]
+fn gift_cards_can_pay_part_of_a_check() {
+    let service = seated_service(1);
+    let order = order_for(&service, 1, 4000);
+    let card = service.with(|r| r.gift_cards.issue(2500));
+    service.with(|r| r.drawers.open("Sam", 10000)).unwrap();
+
+    service.take_payment("Sam", order, Tender::GiftCard(card.clone(), 2500)).unwrap();
+    let cash = service.take_payment("Sam", order, Tender::Cash(2000)).unwrap();
+
+    assert_eq!(cash.applied, 1500);
+    assert_eq!(cash.change, 500);
+    service.with(|r| {
+        assert_eq!(r.gift_cards.balance(&card), Ok(0));
+        assert_eq!(r.orders.get(order).unwrap().balance_due(), 0);
+        assert_eq!(r.drawers.open_drawer("Sam").unwrap().expected_cash(), 11500);
+    });
+}
+
+#[test]
+fn gift_cards_cannot_be_over_redeemed() {
+    let service = seated_service(2);
+    let big = order_for(&service, 1, 8000);
+    let small = order_for(&service, 2, 500);
+    let card = service.with(|r| r.gift_cards.issue(3000));
+
+    // more than the card holds
+    assert_eq!(
+        service.take_payment("Sam", big, Tender::GiftCard(card.clone(), 3500)),
+        Err(RestaurantError::InsufficientBalance(card.clone(), 3000))
+    );
+    // more than the check needs
+    assert_eq!(
+        service.take_payment("Sam", small, Tender::GiftCard(card.clone(), 1000)),
+        Err(RestaurantError::Overtendered(small))
+    );
+    service.with(|r| {
+        assert_eq!(r.gift_cards.balance(&card), Ok(3000));
+        assert!(r.orders.get(big).unwrap().payments.is_empty());
+    });
+}
+
+#[test]
+fn concurrent_redemptions_never_spend_more_than_the_balance() {
+    let service = seated_service(10);
+    let card = service.with(|r| r.gift_cards.issue(5000));
+    // ten tables each try to put 10.00 from the same card on ten orders
+    let threads: Vec<_> = (1..=10)
+        .map(|table| {
+            let service = service.clone();
+            let card = card.clone();
+            thread::spawn(move || {
+                (0..10)
+                    .filter(|_| {
+                        let order = order_for(&service, table, 1000);
+                        service.take_payment("Sam", order, Tender::GiftCard(card.clone(), 1000)).is_ok()
+                    })
+                    .count()
+            })
+        })
+        .collect();
+    let redeemed: usize = threads.into_iter().map(|t| t.join().unwrap()).sum();
+
+    assert_eq!(redeemed, 5);
+    service.with(|r| assert_eq!(r.gift_cards.balance(&card), Ok(0)));
+}
+
+#[test]
+fn house_accounts_charge_up_to_their_limit_and_show_on_statements() {
+    let service = seated_service(1);
+    service.with(|r| {
+        r.house_accounts.start_period("2026-10");
+        r.house_accounts.open_account("ACME", "Acme Corp", 10000)
+    })
+    .unwrap();
+
+    let lunch = order_for(&service, 1, 6000);
+    service.take_payment("Sam", lunch, Tender::HouseAccount(String::from("ACME"), 6000)).unwrap();
+    let dinner = order_for(&service, 1, 5000);
+    assert_eq!(
+        service.take_payment("Sam", dinner, Tender::HouseAccount(String::from("ACME"), 5000)),
+        Err(RestaurantError::CreditLimitExceeded(String::from("ACME")))
+    );
+
+    service.with(|r| {
+        r.house_accounts.start_period("2026-11");
+        let october = r.house_accounts.statement("ACME", "2026-10").unwrap();
+        assert_eq!(october.period_total, 6000);
+        assert_eq!(october.balance_due, 6000);
+        assert!(r.house_accounts.statement("ACME", "2026-11").unwrap().charges.is_empty());
+        assert_eq!(r.house_accounts.settle("ACME", 6000), Ok(0));
+    });
+}
+
+#[test]
+fn amounts_too_big_to_add_up_are_refused() {
+    let mut cards = GiftCards::new();
+    let card = cards.issue(u32::MAX - 100);
+    assert_eq!(cards.reload(&card, 101), Err(RestaurantError::GiftCardFull(card.clone())));
+    assert_eq!(cards.balance(&card), Ok(u32::MAX - 100));
+
+    let mut accounts = HouseAccounts::new("2026-10");
+    accounts.open_account("ACME", "Acme Corp", u32::MAX).unwrap();
+    accounts.charge("ACME", 1, 500).unwrap();
+    assert_eq!(
+        accounts.charge("ACME", 2, u32::MAX),
+        Err(RestaurantError::CreditLimitExceeded(String::from("ACME")))
+    );
+    assert_eq!(accounts.balance("ACME"), Ok(500));
+}
+
+#[test]
+fn gift_cards_and_house_accounts_survive_a_restart() {
+    let dir = env::temp_dir().join(format!("restuarant-payments-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut cards = GiftCards::new();
+    let card = cards.issue(2000);
+    cards.reload(&card, 1500).unwrap();
+    cards.save(&dir.join("gift_cards.tsv")).unwrap();
+
+    let mut accounts = HouseAccounts::new("2026-10");
+    accounts.open_account("ACME", "Acme\tCorp", 10000).unwrap();
+    accounts.charge("ACME", 7, 1234).unwrap();
+    accounts.save(&dir).unwrap();
+
+    let cards = GiftCards::load(&dir.join("gift_cards.tsv")).unwrap();
+    let accounts = HouseAccounts::load(&dir, "2026-10").unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(cards.balance(&card), Ok(3500));
+    let statement = accounts.statement("ACME", "2026-10").unwrap();
+    assert_eq!(statement.name, "Acme\tCorp");
+    assert_eq!(statement.period_total, 1234);
+}
+
+#[test]
+fn gift_card_numbers_are_never_reused_after_a_restart() {
+    let dir = env::temp_dir().join(format!("restuarant-card-numbers-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("gift_cards.tsv");
+
+    let mut cards = GiftCards::new();
+    let first = cards.issue(1000);
+    let second = cards.issue(2000);
+    cards.save(&path).unwrap();
+
+    let mut cards = GiftCards::load(&path).unwrap();
+    let third = cards.issue(3000);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(first, "GC000001");
+    assert_eq!(second, "GC000002");
+    assert_eq!(third, "GC000003");
+    assert_eq!(cards.balance(&second), Ok(2000));
+}
+
+#[test]
+fn carriage_returns_in_names_survive_a_restart() {
+    let dir = env::temp_dir().join(format!("restuarant-carriage-returns-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut accounts = HouseAccounts::new("2026-10");
+    accounts.open_account("ACME", "Acme\r\nCorp", 10000).unwrap();
+    accounts.save(&dir).unwrap();
+    let accounts = HouseAccounts::load(&dir, "2026-10").unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(accounts.statement("ACME", "2026-10").unwrap().name, "Acme\r\nCorp");
+}
@@ -0,0 +1,125 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use restuarant::back_of_house::Breakfast;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::locale::{Currency, Date, Locale};
+use restuarant::menu::Menu;
+use restuarant::reports;
+use restuarant::serving::{OrderLine, Tender};
+use restuarant::service::Restaurant;
+
+const MENU: &str = "\
+[Tomato Soup]
+price = 650
+course = Appetizer
+modifier = croutons, 50
+description = Made fresh every morning
+name.fr = Soupe à la tomate
+description.fr = Préparée chaque matin
+
+[Steak Frites]
+price = 2400
+name.fr = Steak frites
+name.fr-CA = Steak-frites
+name.es = Filete con patatas
+
+[croutons]
+name.fr = croûtons
+";
+
+fn load_menu() -> Menu {
+    let path = env::temp_dir().join(format!("restuarant-menu-{}.txt", std::process::id()));
+    fs::write(&path, MENU).unwrap();
+    let menu = Menu::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    menu
+}
+
+#[test]
+fn missing_translations_fall_back_to_the_language_then_english() {
+    let menu = load_menu();
+    assert_eq!(menu.items().len(), 2);
+
+    assert_eq!(menu.name_in("Steak Frites", &Locale::new("fr-CA")), "Steak-frites");
+    assert_eq!(menu.name_in("Tomato Soup", &Locale::new("fr-CA")), "Soupe à la tomate");
+    assert_eq!(menu.name_in("Tomato Soup", &Locale::new("es")), "Tomato Soup");
+    assert_eq!(menu.name_in("Steak Frites", &Locale::new("ca").fallback("es")), "Filete con patatas");
+    assert_eq!(
+        menu.description_in("Tomato Soup", &Locale::new("de")).as_deref(),
+        Some("Made fresh every morning")
+    );
+    assert_eq!(Locale::new("pt-BR").label("total"), "Total");
+}
+
+#[test]
+fn money_and_dates_are_written_the_local_way() {
+    let date = Date::new(2026, 3, 7);
+    assert_eq!(Locale::new("en-US").format_money(123456, Currency::Dollar), "$1,234.56");
+    assert_eq!(Locale::new("fr").format_money(123456, Currency::Euro), "1\u{a0}234,56\u{a0}€");
+    assert_eq!(Locale::new("de-AT").format_money(5, Currency::Euro), "0,05\u{a0}€");
+    // the guest's language only moves the symbol, it never changes the money
+    assert_eq!(Locale::new("fr").format_money(123456, Currency::Dollar), "1\u{a0}234,56\u{a0}$");
+    assert_eq!(Locale::new("en-GB").format_money(123456, Currency::Euro), "€1,234.56");
+    assert_eq!(Locale::new("en-US").format_date(date), "03/07/2026");
+    assert_eq!(Locale::new("en-GB").format_date(date), "07/03/2026");
+    assert_eq!(Locale::new("de").format_date(date), "07.03.2026");
+    assert_eq!(Locale::new("fr-CA").format_date(date), "2026-03-07");
+    assert_eq!(Locale::new("xx").format_date(date), "03/07/2026");
+}
+
+#[test]
+fn receipts_print_in_the_guests_language() {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4)]));
+    restaurant.menu = load_menu();
+    restaurant.add_to_waitlist(Party::new("Ana", 2));
+    restaurant.seat_at_table(1).unwrap();
+
+    let soup = restaurant.menu.get(0).unwrap().clone();
+    assert_eq!(soup.name(), "Tomato Soup");
+    let lines = vec![OrderLine::shared(soup).modifier("croutons").unwrap()];
+    let id = restaurant.take_order(1, lines).unwrap();
+    restaurant.drawers.open("Sam", 10000).unwrap();
+    restaurant.take_payment("Sam", id, Tender::Cash(1000)).unwrap();
+
+    let order = restaurant.orders.get(id).unwrap();
+    let receipt = reports::receipt(order, &restaurant.menu, &Locale::new("fr-FR"), Date::new(2026, 10, 19));
+    assert!(receipt.contains("19/10/2026"), "{}", receipt);
+    assert!(receipt.contains("Soupe à la tomate (croûtons)"), "{}", receipt);
+    // a dollar restaurant still charges dollars to a French guest
+    assert!(receipt.contains("7,00\u{a0}$"), "{}", receipt);
+    assert!(!receipt.contains('€'), "{}", receipt);
+    assert!(receipt.contains("Monnaie rendue"), "{}", receipt);
+    assert!(receipt.ends_with("Merci !\n"), "{}", receipt);
+
+    let receipt = reports::receipt(order, &restaurant.menu, &Locale::new("en-US"), Date::new(2026, 10, 19));
+    assert!(receipt.contains("Tomato Soup (croutons)"), "{}", receipt);
+    assert!(receipt.contains("Change"), "{}", receipt);
+    assert!(receipt.contains("$3.00"), "{}", receipt);
+
+    let receipt = reports::receipt(order, &restaurant.menu, &Locale::new("de"), Date::new(2026, 10, 19));
+    assert!(receipt.contains("3,00\u{a0}$"), "{}", receipt);
+    assert!(!receipt.contains('€'), "{}", receipt);
+
+    // and a euro restaurant charges euros to an American
+    restaurant.menu.set_currency(Currency::Euro);
+    let order = restaurant.orders.get(id).unwrap();
+    let receipt = reports::receipt(order, &restaurant.menu, &Locale::new("en-US"), Date::new(2026, 10, 19));
+    assert!(receipt.contains("€3.00"), "{}", receipt);
+}
+
+#[test]
+fn the_shipped_menu_file_loads_with_its_translations() {
+    let menu = Menu::load(Path::new("menu.txt")).unwrap();
+    let names: Vec<String> = menu.items().iter().map(|item| item.name()).collect();
+    assert_eq!(names, vec!["Tomato Soup", "Steak Frites", "Chocolate Tart"]);
+    assert_eq!(menu.currency(), Currency::Dollar);
+
+    // dishes written in code take their names from the file too, their
+    // details stay as they are
+    let french = Locale::new("fr");
+    assert_eq!(french.label("order"), "Je voudrais");
+    assert_eq!(menu.dish_in(&Breakfast::summer("Rye"), &french), "Petit-déjeuner, Rye toast and peaches");
+    assert_eq!(menu.dish_in(&Breakfast::summer("Rye"), &Locale::new("it")), "Breakfast, Rye toast and peaches");
+}
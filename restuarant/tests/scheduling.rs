@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+
+use restuarant::back_of_house::forecast::Weekday;
+use restuarant::error::RestaurantError;
+use restuarant::scheduling::{self, Requirement, Role, Rules, Schedule, Shift, StaffMember, Violation};
+
+fn shift(staff: &str, role: Role, weekday: Weekday, start: u32, end: u32) -> Shift {
+    Shift {
+        staff: String::from(staff),
+        role,
+        weekday,
+        start,
+        end,
+    }
+}
+
+// a cook who can work any hour of any day
+fn cook(name: &str, max_hours: u32) -> StaffMember {
+    Weekday::ALL
+        .into_iter()
+        .fold(StaffMember::new(name, max_hours).role(Role::Cook), |member, day| member.available(day, 0, 24))
+}
+
+#[test]
+fn covers_become_people_per_hour() {
+    let covers = BTreeMap::from([((Weekday::Friday, 19), 45.0), ((Weekday::Friday, 15), 0.0)]);
+    let requirements = scheduling::requirements_from_covers(&covers, &[(Role::Server, 20.0), (Role::Host, 60.0)]).unwrap();
+    assert_eq!(
+        requirements,
+        vec![
+            Requirement { weekday: Weekday::Friday, hour: 19, role: Role::Server, count: 3 },
+            Requirement { weekday: Weekday::Friday, hour: 19, role: Role::Host, count: 1 },
+        ]
+    );
+}
+
+#[test]
+fn ratios_have_to_be_more_than_zero() {
+    let covers = BTreeMap::from([((Weekday::Friday, 19), 45.0)]);
+    for ratio in [0.0, -5.0, f64::NAN, f64::INFINITY] {
+        assert_eq!(
+            scheduling::requirements_from_covers(&covers, &[(Role::Host, 60.0), (Role::Server, ratio)]),
+            Err(RestaurantError::InvalidStaffRatio(String::from("Server")))
+        );
+    }
+}
+
+#[test]
+fn validate_catches_every_broken_rule() {
+    let staff = vec![StaffMember::new("Ana", 12).role(Role::Host).available(Weekday::Monday, 9, 17)];
+    let schedule = Schedule {
+        shifts: vec![
+            shift("Ana", Role::Cook, Weekday::Monday, 9, 12),
+            shift("Ana", Role::Host, Weekday::Monday, 11, 17),
+            shift("Ana", Role::Host, Weekday::Tuesday, 2, 14),
+            shift("Bo", Role::Host, Weekday::Tuesday, 9, 17),
+        ],
+    };
+    let violations = schedule.validate(&staff, &Rules::default());
+    assert_eq!(
+        violations,
+        vec![
+            Violation::UnknownStaff(String::from("Bo")),
+            Violation::WrongRole(String::from("Ana"), Role::Cook),
+            Violation::Unavailable(String::from("Ana"), Weekday::Tuesday, 2, 14),
+            Violation::ShiftTooLong(String::from("Ana"), Weekday::Tuesday, 12),
+            Violation::Overlap(String::from("Ana"), Weekday::Monday),
+            Violation::NotEnoughRest(String::from("Ana"), Weekday::Tuesday, 9),
+            Violation::OverMaxHours(String::from("Ana"), 21, 12),
+        ]
+    );
+}
+
+#[test]
+fn rest_wraps_around_from_sunday_to_monday() {
+    let staff = vec![cook("Kit", 40)];
+    let rules = Rules::default();
+    let schedule = Schedule {
+        shifts: vec![
+            shift("Kit", Role::Cook, Weekday::Monday, 6, 14),
+            shift("Kit", Role::Cook, Weekday::Sunday, 16, 24),
+        ],
+    };
+    assert_eq!(
+        schedule.validate(&staff, &rules),
+        vec![Violation::NotEnoughRest(String::from("Kit"), Weekday::Monday, 6)]
+    );
+
+    let rested = Schedule {
+        shifts: vec![
+            shift("Kit", Role::Cook, Weekday::Monday, 10, 18),
+            shift("Kit", Role::Cook, Weekday::Sunday, 16, 24),
+        ],
+    };
+    assert!(rested.validate(&staff, &rules).is_empty());
+}
+
+#[test]
+fn built_schedules_pass_validation_and_report_what_is_missing() {
+    let staff = vec![cook("Kit", 40), cook("Lou", 16)];
+    let rules = Rules::default();
+    // two cooks every hour from 10 to 22 on Sunday and from 0 to 8 on Monday
+    let mut requirements = Vec::new();
+    for hour in 10..22 {
+        requirements.push(Requirement { weekday: Weekday::Sunday, hour, role: Role::Cook, count: 2 });
+    }
+    for hour in 0..8 {
+        requirements.push(Requirement { weekday: Weekday::Monday, hour, role: Role::Cook, count: 2 });
+    }
+
+    let schedule = scheduling::build_schedule(&staff, &requirements, &rules);
+    assert!(schedule.validate(&staff, &rules).is_empty());
+    assert!(schedule.hours_for("Lou") <= 16);
+    assert!(schedule.overtime(&rules).is_empty());
+
+    let unfilled = schedule.unfilled(&requirements);
+    assert!(!unfilled.is_empty());
+    for missing in &unfilled {
+        assert_eq!(missing.count + schedule.staffed(missing.weekday, missing.hour, missing.role), 2);
+    }
+}
+
+#[test]
+fn schedules_survive_a_restart() {
+    let path = env::temp_dir().join(format!("restuarant-schedule-{}.tsv", std::process::id()));
+    let schedule = Schedule {
+        shifts: vec![shift("Kit", Role::Cook, Weekday::Friday, 16, 24)],
+    };
+    schedule.save(&path).unwrap();
+    let loaded = Schedule::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, schedule);
+}
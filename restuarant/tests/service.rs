@@ -0,0 +1,105 @@
+use std::thread;
+
+use restuarant::back_of_house::{Appetizer, Kitchen};
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::serving::{self, OrderLine, OrderStatus, Orders};
+use restuarant::service::{Restaurant, Service};
+
+const SERVERS: u32 = 8;
+const ORDERS_PER_SERVER: u32 = 200;
+
+fn seated_service(tables: u32) -> Service {
+    let floor = Floor::new((1..=tables).map(|number| Table::new(number, 4)).collect());
+    let service = Service::new(Restaurant::new(floor));
+    for number in 1..=tables {
+        service.add_to_waitlist(Party::new(&format!("party {}", number), 2));
+        service.seat_at_table(number).unwrap();
+    }
+    service
+}
+
+#[test]
+fn no_orders_are_lost_when_servers_and_kitchen_race() {
+    let service = seated_service(SERVERS);
+    let total = (SERVERS * ORDERS_PER_SERVER) as usize;
+
+    let servers: Vec<_> = (1..=SERVERS)
+        .map(|table| {
+            let service = service.clone();
+            thread::spawn(move || {
+                (0..ORDERS_PER_SERVER)
+                    .map(|_| service.take_order(table, vec![OrderLine::new(Appetizer::Soup)]).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let cooks: Vec<_> = (0..4)
+        .map(|_| {
+            let service = service.clone();
+            thread::spawn(move || {
+                let mut bumped = Vec::new();
+                // keep cooking until every order has come through the kitchen
+                while service.with(|r| r.orders.iter().filter(|o| o.status == OrderStatus::Ready).count()) < total {
+                    if let Some(ticket) = service.bump_ticket() {
+                        bumped.push(ticket.order_id);
+                    } else {
+                        thread::yield_now();
+                    }
+                }
+                bumped
+            })
+        })
+        .collect();
+
+    let mut taken: Vec<u32> = servers.into_iter().flat_map(|s| s.join().unwrap()).collect();
+    let mut bumped: Vec<u32> = cooks.into_iter().flat_map(|c| c.join().unwrap()).collect();
+    taken.sort();
+    taken.dedup();
+    bumped.sort();
+
+    assert_eq!(taken.len(), total);
+    assert_eq!(bumped, taken);
+    service.with(|r| {
+        assert_eq!(r.orders.len(), total);
+        assert!(r.kitchen.is_empty());
+    });
+}
+
+#[test]
+fn remakes_go_back_through_the_kitchen() {
+    let service = seated_service(1);
+    let id = service.take_order(1, vec![OrderLine::new(Appetizer::Salad)]).unwrap();
+
+    assert!(service.serve_order(id).is_err());
+    service.bump_ticket().unwrap();
+    service.serve_order(id).unwrap();
+    service.fix_incorrect_order("Sam", id).unwrap();
+
+    service.with(|r| {
+        let order = r.orders.get(id).unwrap();
+        assert_eq!(order.status, OrderStatus::Sent);
+        assert_eq!(order.remakes, 1);
+        assert_eq!(r.kitchen.len(), 1);
+        assert_eq!(r.waste.entries().len(), 1);
+    });
+}
+
+#[test]
+fn unseated_tables_cannot_order() {
+    let floor = Floor::new(vec![Table::new(1, 2)]);
+    let service = Service::new(Restaurant::new(floor));
+    assert!(service.take_order(1, vec![OrderLine::new(Appetizer::Soup)]).is_err());
+}
+
+#[test]
+fn an_empty_order_is_refused() {
+    let service = seated_service(1);
+    assert_eq!(service.take_order(1, Vec::new()), Err(RestaurantError::EmptyOrder));
+    service.with(|r| assert!(r.orders.is_empty()));
+
+    let (mut orders, mut kitchen) = (Orders::new(), Kitchen::new());
+    assert_eq!(serving::take_order(&mut orders, &mut kitchen, 1, Vec::new()), Err(RestaurantError::EmptyOrder));
+    assert!(orders.is_empty());
+}
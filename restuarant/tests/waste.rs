@@ -0,0 +1,115 @@
+use restuarant::back_of_house::costing::{Ingredient, Recipe};
+use restuarant::back_of_house::inventory::{Inventory, Unit};
+use restuarant::back_of_house::waste::{WasteReason, Wasted};
+use restuarant::error::RestaurantError;
+use restuarant::hosting::{Floor, Party, Table};
+use restuarant::menu::Dish;
+use restuarant::serving::{OrderLine, OrderStatus};
+use restuarant::service::Restaurant;
+
+// soup takes 250 g of tomato at 0.2 cents a gram, with 1 kg on the shelf
+fn stocked() -> Restaurant {
+    let mut restaurant = Restaurant::new(Floor::new(vec![Table::new(1, 4)]));
+    restaurant.cost_book.add_ingredient(Ingredient::new("tomato", Unit::Gram, 5000.0, 1000));
+    restaurant.cost_book.add_recipe(Recipe::new("Soup").uses("tomato", 250.0)).unwrap();
+    restaurant.inventory.receive("tomato", 1000.0).unwrap();
+    restaurant.add_to_waitlist(Party::new("guests", 2));
+    restaurant.seat_at_table(1).unwrap();
+    restaurant
+}
+
+fn soup() -> OrderLine {
+    OrderLine::new(Dish::new("Soup", 650))
+}
+
+// the order is cooked and on the table
+fn served(restaurant: &mut Restaurant, lines: Vec<OrderLine>) -> u32 {
+    let order = restaurant.take_order(1, lines).unwrap();
+    restaurant.bump_ticket().unwrap();
+    restaurant.serve_order(order).unwrap();
+    order
+}
+
+#[test]
+fn waste_comes_off_the_shelves_at_cost() {
+    let mut restaurant = stocked();
+    restaurant.log_waste(Wasted::Item(String::from("Soup")), 2.0, WasteReason::Dropped, "Sam").unwrap();
+    restaurant
+        .log_waste(Wasted::Ingredient(String::from("tomato")), 100.0, WasteReason::Expired, "Kit")
+        .unwrap();
+
+    assert_eq!(restaurant.inventory.on_hand("tomato"), 400.0);
+    assert_eq!(restaurant.waste.entries()[0].cost, 100.0);
+    assert_eq!(restaurant.waste.total_cost(), 120.0);
+    assert_eq!(restaurant.waste.cost_by_reason()["Expired"], 20.0);
+}
+
+#[test]
+fn quantities_have_to_be_more_than_zero() {
+    let mut restaurant = stocked();
+    for quantity in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert_eq!(
+            restaurant.log_waste(Wasted::Ingredient(String::from("tomato")), quantity, WasteReason::Dropped, "Sam"),
+            Err(RestaurantError::InvalidQuantity(quantity.to_string()))
+        );
+    }
+    assert!(restaurant.waste.entries().is_empty());
+    assert_eq!(restaurant.inventory.on_hand("tomato"), 1000.0);
+
+    let mut inventory = Inventory::new();
+    inventory.receive("tomato", 10.0).unwrap();
+    assert_eq!(inventory.deduct("tomato", -5.0), Err(RestaurantError::InvalidQuantity(String::from("-5"))));
+    assert_eq!(inventory.deduct("tomato", f64::NAN), Err(RestaurantError::InvalidQuantity(String::from("NaN"))));
+    assert_eq!(inventory.on_hand("tomato"), 10.0);
+}
+
+#[test]
+fn a_delivery_of_nothing_or_less_is_refused() {
+    let mut inventory = Inventory::new();
+    inventory.receive("tomato", 10.0).unwrap();
+    for quantity in [0.0, -5.0, f64::NAN, f64::INFINITY] {
+        assert_eq!(inventory.receive("tomato", quantity), Err(RestaurantError::InvalidQuantity(quantity.to_string())));
+    }
+    assert_eq!(inventory.on_hand("tomato"), 10.0);
+    assert_eq!(inventory.on_hand("basil"), 0.0);
+    inventory.receive("basil", f64::NEG_INFINITY).unwrap_err();
+    assert_eq!(inventory.counts().get("basil"), None);
+}
+
+#[test]
+fn a_remake_logs_every_returned_plate_at_once() {
+    let mut restaurant = stocked();
+    let order = served(&mut restaurant, vec![soup(), soup(), OrderLine::new(Dish::new("Bread", 300))]);
+    restaurant.fix_incorrect_order("Sam", order).unwrap();
+
+    let logged: Vec<(Wasted, f64)> = restaurant
+        .waste
+        .entries()
+        .iter()
+        .map(|entry| (entry.wasted.clone(), entry.quantity))
+        .collect();
+    assert_eq!(
+        logged,
+        vec![(Wasted::Item(String::from("Soup")), 2.0), (Wasted::Item(String::from("Bread")), 1.0)]
+    );
+    assert_eq!(restaurant.inventory.on_hand("tomato"), 500.0);
+    assert_eq!(restaurant.orders.get(order).unwrap().remakes, 1);
+}
+
+#[test]
+fn a_remake_the_stock_cannot_cover_changes_nothing() {
+    let mut restaurant = stocked();
+    restaurant.inventory.set_count("tomato", 400.0);
+    let order = served(&mut restaurant, vec![soup(), soup()]);
+
+    assert_eq!(
+        restaurant.fix_incorrect_order("Sam", order),
+        Err(RestaurantError::NotEnoughStock(String::from("tomato")))
+    );
+    let order = restaurant.orders.get(order).unwrap();
+    assert_eq!(order.status, OrderStatus::Delivered);
+    assert_eq!(order.remakes, 0);
+    assert!(restaurant.kitchen.is_empty());
+    assert!(restaurant.waste.entries().is_empty());
+    assert_eq!(restaurant.inventory.on_hand("tomato"), 400.0);
+}